@@ -3,6 +3,7 @@ extern crate cv;
 use cv::*;
 use cv::highgui::*;
 use cv::imgcodecs::ImreadModes;
+use cv::imgproc::Histogram;
 
 fn main() {
     ////////////////////////////////
@@ -32,10 +33,8 @@ fn main() {
     ///////////////////////////////
 
     let hsize = 256;
-    let ranges = [0_f32, 256_f32];
-    let p_ranges: [*const f32; 1] = [ranges.as_ptr() as *const f32];
-    let channels = [0];
-    let hist = mat.calc_hist(channels.as_ptr(), Mat::new(), 1, &hsize, p_ranges.as_ptr());
+    let bins = Histogram::new().channels(&[0]).sizes(&[hsize]).ranges(&[0.0..256.0]);
+    let hist = mat.calc_hist(&bins, None).unwrap();
 
     ////////////////////////////////
     //
@@ -46,19 +45,19 @@ fn main() {
     // Create a 256x200 window, the bin width
     let hist_w = hsize;
     let hist_h = 200;
-    let hist_image = Mat::with_size(hist_h, hist_w, CvType::Cv8UC3 as i32);
+    let hist_image = Mat::with_size(hist_h, hist_w, CvType::Cv8UC3 as i32).unwrap();
 
     // Normalize the histogram to the height of the histogram window
-    let b_hist = hist.normalize(0.0, hist_h as f64, NormTypes::NormMinMax);
+    let b_hist = hist.normalize(0.0, hist_h as f64, NormTypes::NormMinMax).unwrap();
 
     // Plot each segment as a line element
     for i in 1..hsize {
         let start = Point2i::new(i - 1, hist_h - b_hist.at::<f32>(i - 1) as i32);
         let end = Point2i::new(i, hist_h - b_hist.at::<f32>(i) as i32);
-        hist_image.line(start, end);
+        hist_image.line(start, end).unwrap();
     }
 
     // Show the histogram
-    highgui_named_window("Display window", WindowFlags::WindowNormal);
+    highgui_named_window("Display window", WindowFlags::WindowNormal).unwrap();
     hist_image.show("Histogram", 0).unwrap();
 }
@@ -41,57 +41,50 @@ fn main() {
     };
     let ss_ptr = &mut selection_status as *mut SelectionStatus;
 
-    let cap = VideoCapture::new(0);
+    let cap = VideoCapture::new(0).unwrap();
     assert!(cap.is_open());
 
-    highgui_named_window("Window", WindowFlags::WindowAutosize);
-    highgui_set_mouse_callback("Window", on_mouse, ss_ptr as MouseCallbackData);
+    highgui_named_window("Window", WindowFlags::WindowAutosize).unwrap();
+    highgui_set_mouse_callback("Window", on_mouse, ss_ptr as MouseCallbackData).unwrap();
 
     let mut is_tracking = false;
 
     let mut hist = Mat::new();
-    let hsize = 16;
-    let hranges = [0_f32, 180_f32];
-    let phranges: [*const f32; 1] = [&hranges[0] as *const f32];
+    let bins = Histogram::new().channels(&[0]).sizes(&[16]).ranges(&[0.0..180.0]);
     let mut track_window = Rect::default();
 
-    while let Some(mut m) = cap.read() {
-        m.flip(FlipCode::YAxis);
+    while let Some(mut m) = cap.read().unwrap() {
+        m.flip(FlipCode::YAxis).unwrap();
 
-        let hsv = m.cvt_color(ColorConversionCodes::BGR2HSV);
+        let hsv = m.cvt_color(ColorConversionCodes::BGR2HSV).unwrap();
 
-        let ch = [0, 0];
-        let hue = hsv.mix_channels(1, 1, &ch[0] as *const i32, 1);
-        let mask = hsv.in_range(Scalar::new(0, 30, 10, 0), Scalar::new(180, 256, 256, 0));
+        let mut hue = Mat::with_size(hsv.rows, hsv.cols, CvType::Cv8UC1 as i32).unwrap();
+        Mat::mix_channels(&[&hsv], &mut [&mut hue], &[(0, 0)]).unwrap();
+        let mask = hsv.in_range((0.0, 30.0, 10.0), (180.0, 256.0, 256.0))
+            .unwrap();
 
         if selection_status.status {
             println!("Initialize tracking, setting up CAMShift search");
             let selection = selection_status.selection;
-            let roi = hue.roi(selection);
-            let maskroi = mask.roi(selection);
-
-            let raw_hist = roi.calc_hist(
-                std::ptr::null(),
-                maskroi,
-                1,
-                &hsize,
-                &phranges[0] as *const *const f32,
-            );
-            hist = raw_hist.normalize(0.0, 255.0, NormTypes::NormMinMax);
+            let roi = hue.roi(selection).unwrap();
+            let maskroi = mask.roi(selection).unwrap();
+
+            let raw_hist = roi.calc_hist(&bins, Some(&maskroi)).unwrap();
+            hist = raw_hist.normalize(0.0, 255.0, NormTypes::NormMinMax).unwrap();
 
             track_window = selection;
-            m.rectangle(selection);
+            m.rectangle(selection).unwrap();
             selection_status.status = false;
             is_tracking = true;
         }
 
         if is_tracking {
-            let mut back_project = hue.calc_back_project(std::ptr::null(), &hist, &phranges[0] as *const *const f32);
-            back_project.logic_and(mask);
+            let mut back_project = hue.calc_back_project(&bins, &hist).unwrap();
+            back_project.logic_and(mask).unwrap();
             let criteria = TermCriteria::new(TermType::Count, 10, 1.0);
-            let track_box = back_project.camshift(track_window, &criteria);
+            let track_box = back_project.camshift(track_window, &criteria).unwrap();
 
-            m.rectangle(track_box.bounding_rect());
+            m.rectangle(track_box.bounding_rect()).unwrap();
         }
 
         m.show("Window", 30).unwrap();
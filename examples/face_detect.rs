@@ -14,16 +14,17 @@ fn main() {
 
     let mut buf = Vec::new();
     File::open(d).unwrap().read_to_end(&mut buf).unwrap();
-    let mat = Mat::imdecode(&buf, ImreadModes::ImreadGrayscale);
+    let mat = Mat::imdecode(&buf, ImreadModes::ImreadGrayscale).unwrap();
 
     let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     d.push("assets/haarcascade_frontalface_default.xml");
     let cascade = CascadeClassifier::from_path(d).unwrap();
 
-    highgui_named_window("window", WindowFlags::WindowNormal);
+    highgui_named_window("window", WindowFlags::WindowNormal).unwrap();
 
     // result is a vector of rectangles
-    let result = cascade.detect_with_params(&mat, 1.1, 15, Size2i::new(80, 80), Size2i::default());
+    let result = cascade.detect_with_params(&mat, 1.1, 15, Size2i::new(80, 80), Size2i::default())
+        .unwrap();
 
     println!("Detected {} faces", result.len());
     // we draw each of them on the image
@@ -32,10 +33,10 @@ fn main() {
         .map(|&r| {
             mat.rectangle_custom(
                 r.scale(1.2),
-                Scalar::new(255, 255, 0, 255),
+                Color::cyan(),
                 10,
                 LineTypes::Line8,
-            )
+            ).unwrap()
         })
         .count();
     mat.show("window", 0).unwrap();
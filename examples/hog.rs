@@ -51,14 +51,14 @@ fn run() -> Result<()> {
         .expect("You need to provide the directory");
 
     if show {
-        highgui_named_window("window", WindowFlags::WindowAutosize);
+        highgui_named_window("window", WindowFlags::WindowAutosize).unwrap();
     }
 
     let mut param = HogParams::default();
     param.group_threshold = 0;
-    let mut hog = Hog::with_params(param);
+    let mut hog = Hog::with_params(param).unwrap();
     let detector = SvmDetector::default_people_detector();
-    hog.set_svm_detector(detector);
+    hog.set_svm_detector(detector).unwrap();
 
     for entry in try!(fs::read_dir(Path::new(&dir))) {
         let dir = try!(entry);
@@ -77,10 +77,10 @@ fn run_detect_for_image<P: AsRef<Path>, OD: ObjectDetect>(detector: &mut OD, pat
         .into_owned();
     let frame_num = filename.parse::<usize>().unwrap();
     File::open(path).unwrap().read_to_end(&mut buf).unwrap();
-    let mat = Mat::imdecode(&buf, ImreadModes::ImreadGrayscale);
+    let mat = Mat::imdecode(&buf, ImreadModes::ImreadGrayscale).unwrap();
 
     let start = ::std::time::Instant::now();
-    let results = detector.detect(&mat);
+    let results = detector.detect(&mat).unwrap();
     let elapsed = start.elapsed();
 
     print!("{},{},", frame_num, results.len());
@@ -94,7 +94,7 @@ fn run_detect_for_image<P: AsRef<Path>, OD: ObjectDetect>(detector: &mut OD, pat
     if show {
         results
             .iter()
-            .map(|&(r, _w)| mat.rectangle(r.scale(0.6)))
+            .map(|&(r, _w)| mat.rectangle(r.scale(0.6)).unwrap())
             .count();
         mat.show("window", 0).unwrap();
     }
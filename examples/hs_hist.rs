@@ -3,7 +3,7 @@ extern crate cv;
 use cv::*;
 use cv::highgui::*;
 use cv::imgcodecs::ImreadModes;
-use cv::imgproc::ColorConversionCodes;
+use cv::imgproc::{ColorConversionCodes, Histogram};
 
 fn main() {
     ////////////////////////////////
@@ -25,7 +25,7 @@ fn main() {
         std::process::exit(-1);
     }
 
-    let hsv = mat.cvt_color(ColorConversionCodes::BGR2HSV);
+    let hsv = mat.cvt_color(ColorConversionCodes::BGR2HSV).unwrap();
 
     ////////////////////////////////
     //
@@ -36,21 +36,12 @@ fn main() {
 
     let hbins = 30;
     let sbins = 32;
-    let hist_size = [hbins, sbins];
+    let bins = Histogram::new()
+        .channels(&[0, 1])
+        .sizes(&[hbins, sbins])
+        .ranges(&[0.0..180.0, 0.0..256.0]);
 
-    let hranges = [0.0, 180.0];
-    let sranges = [0.0, 256.0];
-    let ranges = [hranges.as_ptr(), sranges.as_ptr()];
-
-    let channels = [0, 1];
-
-    let hist = hsv.calc_hist(
-        channels.as_ptr(),
-        Mat::new(),
-        2,
-        hist_size.as_ptr(),
-        ranges.as_ptr(),
-    );
+    let hist = hsv.calc_hist(&bins, None).unwrap();
 
     ////////////////////////////////
     //
@@ -58,27 +49,27 @@ fn main() {
     //
     ///////////////////////////////
 
-    let min_max = hist.min_max_loc(Mat::new());
+    let min_max = hist.min_max_loc(Mat::new()).unwrap();
     let max_val = min_max.1 as f32;
 
     let scale = 10;
-    let hist_image = Mat::with_size(sbins * scale, hbins * scale, CvType::Cv8UC3 as i32);
+    let hist_image = Mat::with_size(sbins * scale, hbins * scale, CvType::Cv8UC3 as i32).unwrap();
 
     for h in 0..hbins {
         for s in 0..sbins {
             let bin_val = hist.at2::<f32>(h, s);
-            let intensity = (bin_val * 255.0 / max_val) as i32;
+            let intensity = (bin_val * 255.0 / max_val) as u8;
             let rect = Rect::new(h * scale + 1, s * scale + 1, scale - 1, scale - 1);
 
             hist_image.rectangle_custom(
                 rect,
-                Scalar::all(intensity),
+                Color::gray(intensity),
                 LineTypes::Filled as i32,
                 LineTypes::Line8,
-            );
+            ).unwrap();
         }
     }
 
-    highgui_named_window("Display window", WindowFlags::WindowNormal);
+    highgui_named_window("Display window", WindowFlags::WindowNormal).unwrap();
     hist_image.show("Histogram", 0).unwrap();
 }
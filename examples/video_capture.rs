@@ -28,11 +28,11 @@ use cv::highgui::*;
 use cv::videoio::VideoCapture;
 
 fn main() {
-    let cap = VideoCapture::new(0);
+    let cap = VideoCapture::new(0).unwrap();
     assert!(cap.is_open());
 
-    highgui_named_window("Window", WindowFlags::WindowAutosize);
-    while let Some(image) = cap.read() {
+    highgui_named_window("Window", WindowFlags::WindowAutosize).unwrap();
+    while let Some(image) = cap.read().unwrap() {
         image.show("Window", 30).unwrap();
     }
 }
@@ -19,20 +19,16 @@ void cv_gpu_mat_drop(GpuMat* gpu_mat) {
     gpu_mat = nullptr;
 }
 
-void cv_gpu_mat_upload(GpuMat* gpu_mat, CvMatrix* cpu_mat) {
+void cv_gpu_mat_upload(GpuMat* gpu_mat, CvMatrix* cpu_mat, CError* error) {
     cv::cuda::GpuMat* gpu_image = reinterpret_cast<cv::cuda::GpuMat*>(gpu_mat);
     cv::Mat* image = reinterpret_cast<cv::Mat*>(cpu_mat);
-    gpu_image->upload(*image);
+    cv_try(error, [&]() { gpu_image->upload(*image); });
 }
 
-CvMatrix* cv_mat_from_gpu_mat(GpuMat* gpu_mat) {
+void cv_gpu_mat_download(GpuMat* gpu_mat, CvMatrix* cpu_mat, CError* error) {
     cv::cuda::GpuMat* gpu_image = reinterpret_cast<cv::cuda::GpuMat*>(gpu_mat);
-    return reinterpret_cast<CvMatrix*>(new cv::Mat(*gpu_image));
-}
-
-GpuMat *cv_gpu_mat_from_mat(CvMatrix *cmat) {
-  cv::Mat *image = reinterpret_cast<cv::Mat *>(cmat);
-  return reinterpret_cast<GpuMat *>(new cv::cuda::GpuMat(*image));
+    cv::Mat* image = reinterpret_cast<cv::Mat*>(cpu_mat);
+    cv_try(error, [&]() { gpu_image->download(*image); });
 }
 
 // =============================================================================
@@ -46,14 +42,19 @@ GpuHog* cv_gpu_hog_default() {
 }
 
 GpuHog* cv_gpu_hog_new(Size2i win_size, Size2i block_size,
-                       Size2i block_stride, Size2i cell_size, int32_t nbins) {
+                       Size2i block_stride, Size2i cell_size, int32_t nbins,
+                       CError* error) {
     cv::Size cv_win_size(win_size.width, win_size.height);
     cv::Size cv_block_size(block_size.width, block_size.height);
     cv::Size cv_block_stride(block_stride.width, block_stride.height);
     cv::Size cv_cell_size(cell_size.width, cell_size.height);
 
-    return reinterpret_cast<GpuHog*>(new CV_GPU_HOG(cv::cuda::HOG::create(
-        cv_win_size, cv_block_size, cv_block_stride, cv_cell_size, nbins)));
+    CV_GPU_HOG* hog = new CV_GPU_HOG();
+    cv_try(error, [&]() {
+        *hog = cv::cuda::HOG::create(cv_win_size, cv_block_size,
+                                     cv_block_stride, cv_cell_size, nbins);
+    });
+    return reinterpret_cast<GpuHog*>(hog);
 }
 
 void cv_gpu_hog_drop(GpuHog* hog) {
@@ -62,28 +63,33 @@ void cv_gpu_hog_drop(GpuHog* hog) {
     hog = nullptr;
 }
 
-void cv_gpu_hog_set_detector(GpuHog* hog, SvmDetector* detector) {
+void cv_gpu_hog_set_detector(GpuHog* hog, SvmDetector* detector,
+                             CError* error) {
     CV_GPU_HOG* cv_hog = reinterpret_cast<CV_GPU_HOG*>(hog);
     std::vector<float>* cv_detector =
         reinterpret_cast<std::vector<float>*>(detector);
-    (*cv_hog)->setSVMDetector(*cv_detector);
+    cv_try(error, [&]() { (*cv_hog)->setSVMDetector(*cv_detector); });
 }
 
-void cv_gpu_hog_detect(GpuHog* hog, GpuMat* image, VecRect* found) {
+void cv_gpu_hog_detect(GpuHog* hog, GpuMat* image, VecRect* found,
+                       CError* error) {
     CV_GPU_HOG* cv_hog = reinterpret_cast<CV_GPU_HOG*>(hog);
     cv::cuda::GpuMat* cv_image = reinterpret_cast<cv::cuda::GpuMat*>(image);
     std::vector<cv::Rect> vec_object;
-    (*cv_hog)->detectMultiScale(*cv_image, vec_object);
+    cv_try(error, [&]() { (*cv_hog)->detectMultiScale(*cv_image, vec_object); });
     vec_rect_cxx_to_c(vec_object, found);
 }
 
-void cv_gpu_hog_detect_with_conf(GpuHog* hog, GpuMat* image, VecRect* found, VecDouble* conf) {
+void cv_gpu_hog_detect_with_conf(GpuHog* hog, GpuMat* image, VecRect* found, VecDouble* conf,
+                                 CError* error) {
     CV_GPU_HOG* cv_hog = reinterpret_cast<CV_GPU_HOG*>(hog);
     cv::cuda::GpuMat* cv_image = reinterpret_cast<cv::cuda::GpuMat*>(image);
     std::vector<cv::Rect> vec_object;
     std::vector<double> vec_confidences;
-    (*cv_hog)->setGroupThreshold(0);
-    (*cv_hog)->detectMultiScale(*cv_image, vec_object, &vec_confidences);
+    cv_try(error, [&]() {
+        (*cv_hog)->setGroupThreshold(0);
+        (*cv_hog)->detectMultiScale(*cv_image, vec_object, &vec_confidences);
+    });
     vec_rect_cxx_to_c(vec_object, found);
     vec_double_cxx_to_c(vec_confidences, conf);
 }
@@ -179,8 +185,13 @@ Size2i cv_gpu_hog_get_win_stride(GpuHog* hog) {
 // =============================================================================
 using GpuCascadePtr = cv::Ptr<cv::cuda::CascadeClassifier>;
 
-GpuCascade* cv_gpu_cascade_new(const char* const filename) {
-    auto cascade = cv::cuda::CascadeClassifier::create(filename);
+GpuCascade* cv_gpu_cascade_new(const char* const filename, CError* error) {
+    GpuCascadePtr cascade;
+    cv_try(error,
+           [&]() { cascade = cv::cuda::CascadeClassifier::create(filename); });
+    if (cascade.empty()) {
+        return nullptr;
+    }
     return reinterpret_cast<GpuCascade*>(new GpuCascadePtr(cascade));
 }
 
@@ -190,14 +201,17 @@ void cv_gpu_cascade_drop(GpuCascade* cascade) {
     cascade_ptr = nullptr;
 }
 
-void cv_gpu_cascade_detect(GpuCascade* cascade, GpuMat* image, VecRect* objects) {
+void cv_gpu_cascade_detect(GpuCascade* cascade, GpuMat* image, VecRect* objects,
+                           CError* error) {
     GpuCascadePtr* cv_cascade = reinterpret_cast<GpuCascadePtr*>(cascade);
     cv::cuda::GpuMat* cv_image = reinterpret_cast<cv::cuda::GpuMat*>(image);
     cv::cuda::GpuMat objbuf;
     std::vector<cv::Rect> vec_object;
 
-    (*cv_cascade)->detectMultiScale(*cv_image, objbuf);
-    (*cv_cascade)->convert(objbuf, vec_object);
+    cv_try(error, [&]() {
+        (*cv_cascade)->detectMultiScale(*cv_image, objbuf);
+        (*cv_cascade)->convert(objbuf, vec_object);
+    });
 
     vec_rect_cxx_to_c(vec_object, objects);
 }
@@ -22,9 +22,8 @@ EXTERN_C_BEGIN
 typedef struct _GpuMat GpuMat;
 GpuMat* cv_gpu_mat_default();
 void cv_gpu_mat_drop(GpuMat*);
-void cv_gpu_mat_upload(GpuMat*, CvMatrix*);
-CvMatrix* cv_mat_from_gpu_mat(GpuMat*);
-GpuMat* cv_gpu_mat_from_mat(CvMatrix*);
+void cv_gpu_mat_upload(GpuMat*, CvMatrix*, CError*);
+void cv_gpu_mat_download(GpuMat*, CvMatrix*, CError*);
 
 // =============================================================================
 //   Hog
@@ -32,11 +31,13 @@ GpuMat* cv_gpu_mat_from_mat(CvMatrix*);
 typedef struct _GpuHog GpuHog;
 GpuHog* cv_gpu_hog_default();
 GpuHog* cv_gpu_hog_new(Size2i win_size, Size2i block_size,
-                       Size2i block_stride, Size2i cell_size, int32_t nbins);
+                       Size2i block_stride, Size2i cell_size, int32_t nbins,
+                       CError* error);
 void cv_gpu_hog_drop(GpuHog*);
-void cv_gpu_hog_set_detector(GpuHog*, SvmDetector*);
-void cv_gpu_hog_detect(GpuHog*, GpuMat*, VecRect*);
-void cv_gpu_hog_detect_with_conf(GpuHog*, GpuMat*, VecRect*, VecDouble*);
+void cv_gpu_hog_set_detector(GpuHog*, SvmDetector*, CError*);
+void cv_gpu_hog_detect(GpuHog*, GpuMat*, VecRect*, CError*);
+void cv_gpu_hog_detect_with_conf(GpuHog*, GpuMat*, VecRect*, VecDouble*,
+                                 CError*);
 
 void cv_gpu_hog_set_gamma_correction(GpuHog*, bool gamma);
 void cv_gpu_hog_set_group_threshold(GpuHog*, int32_t group_threshold);
@@ -60,9 +61,9 @@ Size2i cv_gpu_hog_get_win_stride(GpuHog *);
 //   CascadeClassifier
 // =============================================================================
 typedef struct _GpuCascade GpuCascade;
-GpuCascade* cv_gpu_cascade_new(const char* const filename);
+GpuCascade* cv_gpu_cascade_new(const char* const filename, CError* error);
 void cv_gpu_cascade_drop(GpuCascade*);
-void cv_gpu_cascade_detect(GpuCascade*, GpuMat*, VecRect*);
+void cv_gpu_cascade_detect(GpuCascade*, GpuMat*, VecRect*, CError*);
 
 void cv_gpu_cascade_set_find_largest_object(GpuCascade*, bool);
 void cv_gpu_cascade_set_max_num_objects(GpuCascade*, int32_t);
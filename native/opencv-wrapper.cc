@@ -1,6 +1,9 @@
 #include "opencv-wrapper.h"
 #include "utils.h"
 
+#include <cstring>
+#include <sstream>
+
 #include <opencv2/core.hpp>
 #include <opencv2/highgui.hpp>
 #include <opencv2/imgproc.hpp>
@@ -18,20 +21,58 @@ CvMatrix* cv_mat_new() {
     return reinterpret_cast<CvMatrix*>(image);
 }
 
-CvMatrix* cv_mat_new_with_size(int rows, int cols, int type) {
-    return reinterpret_cast<CvMatrix*>(new cv::Mat(rows, cols, type));
+void cv_error_drop(CError* error) {
+    free(error->func);
+    free(error->file);
+    free(error->msg);
+    error->func = nullptr;
+    error->file = nullptr;
+    error->msg = nullptr;
+}
+
+void cv_string_drop(char* s) {
+    free(s);
+}
+
+CvMatrix* cv_mat_new_with_size(int rows, int cols, int type, CError* error) {
+    cv::Mat* mat = new cv::Mat();
+    cv_try(error, [&]() { mat->create(rows, cols, type); });
+    return reinterpret_cast<CvMatrix*>(mat);
+}
+
+CvMatrix* cv_mat_zeros(int rows, int cols, int type, CError* error) {
+    cv::Mat* mat = new cv::Mat();
+    cv_try(error, [&]() { *mat = cv::Mat::zeros(rows, cols, type); });
+    return reinterpret_cast<CvMatrix*>(mat);
+}
+
+CvMatrix* cv_mat_zeros_nd(int ndims, const int* sizes, int type,
+                          CError* error) {
+    cv::Mat* mat = new cv::Mat();
+    cv_try(error, [&]() { *mat = cv::Mat::zeros(ndims, sizes, type); });
+    return reinterpret_cast<CvMatrix*>(mat);
 }
 
-CvMatrix* cv_mat_zeros(int rows, int cols, int type) {
+CvMatrix* cv_mat_ones(int rows, int cols, int type, CError* error) {
     cv::Mat* mat = new cv::Mat();
-    *mat = cv::Mat::zeros(rows, cols, type);
+    cv_try(error, [&]() { *mat = cv::Mat::ones(rows, cols, type); });
     return reinterpret_cast<CvMatrix*>(mat);
 }
 
-CvMatrix* cv_mat_from_buffer(int rows, int cols, int type, const uint8_t* buf) {
-    return reinterpret_cast<CvMatrix*>(
-        new cv::Mat(rows, cols, type,
-                    const_cast<void*>(reinterpret_cast<const void*>(buf))));
+CvMatrix* cv_mat_eye(int rows, int cols, int type, CError* error) {
+    cv::Mat* mat = new cv::Mat();
+    cv_try(error, [&]() { *mat = cv::Mat::eye(rows, cols, type); });
+    return reinterpret_cast<CvMatrix*>(mat);
+}
+
+CvMatrix* cv_mat_from_buffer(int rows, int cols, int type, const uint8_t* buf,
+                             CError* error) {
+    cv::Mat* mat = new cv::Mat();
+    cv_try(error, [&]() {
+        *mat = cv::Mat(rows, cols, type,
+                       const_cast<void*>(reinterpret_cast<const void*>(buf)));
+    });
+    return reinterpret_cast<CvMatrix*>(mat);
 }
 
 bool cv_mat_is_valid(CvMatrix* cmat) {
@@ -39,27 +80,29 @@ bool cv_mat_is_valid(CvMatrix* cmat) {
     return mat->data != NULL;
 }
 
-CvMatrix* cv_mat_roi(CvMatrix* cmat, Rect crect) {
+CvMatrix* cv_mat_roi(CvMatrix* cmat, Rect crect, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Rect rect(crect.x, crect.y, crect.width, crect.height);
-    cv::Mat* dst = new cv::Mat(*mat, rect);
+    cv::Mat* dst = new cv::Mat();
+    cv_try(error, [&]() { *dst = cv::Mat(*mat, rect); });
     return reinterpret_cast<CvMatrix*>(dst);
 }
 
-void cv_mat_logic_and(CvMatrix* cimage, const CvMatrix* const cmask) {
+void cv_mat_logic_and(CvMatrix* cimage, const CvMatrix* const cmask,
+                      CError* error) {
     cv::Mat* image = reinterpret_cast<cv::Mat*>(cimage);
     const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
-    (*image) &= (*mask);
+    cv_try(error, [&]() { (*image) &= (*mask); });
 }
 
-void cv_mat_flip(CvMatrix* cimage, int code) {
+void cv_mat_flip(CvMatrix* cimage, int code, CError* error) {
     cv::Mat* image = reinterpret_cast<cv::Mat*>(cimage);
-    cv::flip(*image, *image, code);
+    cv_try(error, [&]() { cv::flip(*image, *image, code); });
 }
 
-CvMatrix* cv_imread(const char* const filename, int flags) {
+CvMatrix* cv_imread(const char* const filename, int flags, CError* error) {
     cv::Mat* image = new cv::Mat();
-    *image = cv::imread(filename, flags);
+    cv_try(error, [&]() { *image = cv::imread(filename, flags); });
     return reinterpret_cast<CvMatrix*>(image);
 }
 
@@ -103,6 +146,93 @@ size_t cv_mat_step1(const CvMatrix* const cmat, int i) {
     return (reinterpret_cast<const cv::Mat* const>(cmat))->step1(i);
 }
 
+int cv_mat_dims(const CvMatrix* const cmat) {
+    return (reinterpret_cast<const cv::Mat* const>(cmat))->dims;
+}
+
+int cv_mat_size_nd(const CvMatrix* const cmat, int i) {
+    return (reinterpret_cast<const cv::Mat* const>(cmat))->size[i];
+}
+
+CvMatrix* cv_mat_clone(const CvMatrix* const cmat, CError* error) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    cv::Mat* dst = new cv::Mat();
+    cv_try(error, [&]() { *dst = mat->clone(); });
+    return reinterpret_cast<CvMatrix*>(dst);
+}
+
+CvMatrix* cv_mat_share(const CvMatrix* const cmat) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    return reinterpret_cast<CvMatrix*>(new cv::Mat(*mat));
+}
+
+CvMatrix* cv_mat_reshape(const CvMatrix* const cmat, int cn, int rows,
+                         CError* error) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    cv::Mat* dst = new cv::Mat();
+    cv_try(error, [&]() { *dst = mat->reshape(cn, rows); });
+    return reinterpret_cast<CvMatrix*>(dst);
+}
+
+bool cv_mat_is_refcounted(const CvMatrix* const cmat) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    return mat->data == nullptr || mat->u != nullptr;
+}
+
+bool cv_mat_is_continuous(const CvMatrix* const cmat) {
+    return (reinterpret_cast<const cv::Mat* const>(cmat))->isContinuous();
+}
+
+bool cv_mat_eq(const CvMatrix* const ca, const CvMatrix* const cb, CError* error) {
+    const cv::Mat* a = reinterpret_cast<const cv::Mat*>(ca);
+    const cv::Mat* b = reinterpret_cast<const cv::Mat*>(cb);
+    if (a->type() != b->type() || a->size != b->size) {
+        return false;
+    }
+    if (a->empty()) {
+        return true;
+    }
+    bool equal = true;
+    cv_try(error, [&]() {
+        const cv::Mat* arrays[] = {a, b, nullptr};
+        uchar* planes[2];
+        cv::NAryMatIterator it(arrays, planes, 2);
+        size_t len = it.size * a->elemSize();
+        for (size_t i = 0; i < it.nplanes && equal; i++, ++it) {
+            equal = memcmp(planes[0], planes[1], len) == 0;
+        }
+    });
+    return equal;
+}
+
+void cv_mat_copy_to(const CvMatrix* const csrc, CvMatrix* cdst,
+                    const CvMatrix* const cmask, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    cv_try(error, [&]() {
+        if (mask == nullptr) {
+            src->copyTo(*dst);
+        } else {
+            src->copyTo(*dst, *mask);
+        }
+    });
+}
+
+void cv_mat_set_to(CvMatrix* cmat, Scalar value, const CvMatrix* const cmask,
+                   CError* error) {
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    cv::Scalar cv_value = scalar_c_to_cxx(value);
+    cv_try(error, [&]() {
+        if (mask == nullptr) {
+            mat->setTo(cv_value);
+        } else {
+            mat->setTo(cv_value, *mask);
+        }
+    });
+}
+
 void cv_mat_drop(CvMatrix* cmat) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     delete mat;
@@ -123,188 +253,887 @@ void cv_vec_drop(Vec* vec, unsigned int depth) {
     }
 }
 
+// =============================================================================
+//  SparseMat
+// =============================================================================
+CSparseMat* cv_sparse_mat_new(int ndims, const int* sizes, int type,
+                              CError* error) {
+    cv::SparseMat* sm = new cv::SparseMat();
+    cv_try(error, [&]() { sm->create(ndims, sizes, type); });
+    return reinterpret_cast<CSparseMat*>(sm);
+}
+
+CSparseMat* cv_sparse_mat_from_mat(const CvMatrix* const cmat, CError* error) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    cv::SparseMat* sm = new cv::SparseMat();
+    cv_try(error, [&]() { *sm = cv::SparseMat(*mat); });
+    return reinterpret_cast<CSparseMat*>(sm);
+}
+
+CSparseMat* cv_sparse_mat_clone(const CSparseMat* const csm, CError* error) {
+    const cv::SparseMat* sm = reinterpret_cast<const cv::SparseMat*>(csm);
+    cv::SparseMat* result = new cv::SparseMat();
+    cv_try(error, [&]() { *result = sm->clone(); });
+    return reinterpret_cast<CSparseMat*>(result);
+}
+
+void cv_sparse_mat_drop(CSparseMat* csm) {
+    cv::SparseMat* sm = reinterpret_cast<cv::SparseMat*>(csm);
+    delete sm;
+    csm = nullptr;
+}
+
+int cv_sparse_mat_dims(const CSparseMat* const csm) {
+    return reinterpret_cast<const cv::SparseMat*>(csm)->dims();
+}
+
+int cv_sparse_mat_size(const CSparseMat* const csm, int i) {
+    return reinterpret_cast<const cv::SparseMat*>(csm)->size(i);
+}
+
+int cv_sparse_mat_type(const CSparseMat* const csm) {
+    return reinterpret_cast<const cv::SparseMat*>(csm)->type();
+}
+
+size_t cv_sparse_mat_nzcount(const CSparseMat* const csm) {
+    return reinterpret_cast<const cv::SparseMat*>(csm)->nzcount();
+}
+
+uint8_t* cv_sparse_mat_ptr(CSparseMat* csm, const int* idx, bool create,
+                           CError* error) {
+    cv::SparseMat* sm = reinterpret_cast<cv::SparseMat*>(csm);
+    uint8_t* ptr = nullptr;
+    cv_try(error, [&]() { ptr = sm->ptr(idx, create); });
+    return ptr;
+}
+
+void cv_sparse_mat_erase(CSparseMat* csm, const int* idx, CError* error) {
+    cv::SparseMat* sm = reinterpret_cast<cv::SparseMat*>(csm);
+    cv_try(error, [&]() { sm->erase(idx); });
+}
+
+void cv_sparse_mat_nonzeros(const CSparseMat* const csm, int* indices,
+                            uint8_t* values) {
+    const cv::SparseMat* sm = reinterpret_cast<const cv::SparseMat*>(csm);
+    int dims = sm->dims();
+    size_t elem_size = sm->elemSize();
+    cv::SparseMatConstIterator it = sm->begin();
+    cv::SparseMatConstIterator end = sm->end();
+    for (size_t i = 0; it != end; ++it, ++i) {
+        const cv::SparseMat::Node* node = it.node();
+        ::memcpy(indices + i * dims, node->idx, dims * sizeof(int));
+        ::memcpy(values + i * elem_size, it.ptr, elem_size);
+    }
+}
+
+void cv_sparse_mat_to_dense(const CSparseMat* const csm, CvMatrix* cdst,
+                            CError* error) {
+    const cv::SparseMat* sm = reinterpret_cast<const cv::SparseMat*>(csm);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { sm->copyTo(*dst); });
+}
+
 // =============================================================================
 //  core array
 // =============================================================================
-void cv_in_range(CvMatrix* cmat, Scalar lowerb, Scalar upperb, CvMatrix* cdst) {
+void cv_in_range(CvMatrix* cmat, Scalar lowerb, Scalar upperb, CvMatrix* cdst,
+                 CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
-    cv::Scalar lb(lowerb.v0, lowerb.v1, lowerb.v2);
-    cv::Scalar ub(upperb.v0, upperb.v1, upperb.v2);
+    cv::Scalar lb = scalar_c_to_cxx(lowerb);
+    cv::Scalar ub = scalar_c_to_cxx(upperb);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
-    cv::inRange(*mat, lb, ub, *dst);
+    cv_try(error, [&]() { cv::inRange(*mat, lb, ub, *dst); });
 }
 
 void cv_min_max_loc(const CvMatrix* const cmat, double* min, double* max,
                     Point2i* minLoc, Point2i* maxLoc,
-                    const CvMatrix* const cmask) {
+                    const CvMatrix* const cmask, CError* error) {
     const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
     const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
 
-    if (minLoc == NULL && maxLoc == NULL) {
-        cv::minMaxLoc(*mat, min, max, NULL, NULL, *mask);
-    } else if (minLoc == NULL && maxLoc != NULL) {
-        cv::Point maxPoint = cv::Point();
-        cv::minMaxLoc(*mat, min, max, NULL, &maxPoint, *mask);
-        maxLoc->x = maxPoint.x;
-        maxLoc->y = maxPoint.y;
-    } else if (minLoc != NULL && maxLoc == NULL) {
-        cv::Point minPoint = cv::Point();
-        cv::minMaxLoc(*mat, min, max, &minPoint, NULL, *mask);
-        minLoc->x = minPoint.x;
-        minLoc->y = minPoint.y;
-    } else {
+    cv_try(error, [&]() {
         cv::Point minPoint = cv::Point();
         cv::Point maxPoint = cv::Point();
-        cv::minMaxLoc(*mat, min, max, &minPoint, &maxPoint, *mask);
-        minLoc->x = minPoint.x;
-        minLoc->y = minPoint.y;
-        maxLoc->x = maxPoint.x;
-        maxLoc->y = maxPoint.y;
-    }
+        cv::minMaxLoc(*mat, min, max, minLoc == NULL ? NULL : &minPoint,
+                      maxLoc == NULL ? NULL : &maxPoint, *mask);
+        if (minLoc != NULL) {
+            minLoc->x = minPoint.x;
+            minLoc->y = minPoint.y;
+        }
+        if (maxLoc != NULL) {
+            maxLoc->x = maxPoint.x;
+            maxLoc->y = maxPoint.y;
+        }
+    });
+}
+
+void cv_mix_channels(const CvMatrix* const* csrcs, size_t nsrcs,
+                     const CvMatrix* const* cdsts, size_t ndsts,
+                     const int* from_to, size_t npairs, CError* error) {
+    cv_try(error, [&]() {
+        std::vector<cv::Mat> srcs = mat_vector(csrcs, nsrcs);
+        std::vector<cv::Mat> dsts = mat_vector(cdsts, ndsts);
+        cv::mixChannels(srcs.data(), nsrcs, dsts.data(), ndsts, from_to,
+                        npairs);
+    });
+}
+
+void cv_split(const CvMatrix* const csrc, CvMatrix* const* cdsts, size_t ndsts,
+              CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv_try(error, [&]() {
+        std::vector<cv::Mat> channels;
+        cv::split(*src, channels);
+        for (size_t i = 0; i < ndsts && i < channels.size(); i++) {
+            *reinterpret_cast<cv::Mat*>(cdsts[i]) = channels[i];
+        }
+    });
 }
 
-void cv_mix_channels(CvMatrix* cmat, size_t nsrcs, CvMatrix* dst, size_t ndsts,
-                     const int* from_to, size_t npairs) {
-    cv::Mat* from = reinterpret_cast<cv::Mat*>(cmat);
-    cv::Mat* to = reinterpret_cast<cv::Mat*>(dst);
-    cv::mixChannels(from, nsrcs, to, ndsts, from_to, npairs);
+void cv_merge(const CvMatrix* const* csrcs, size_t nsrcs, CvMatrix* cdst,
+              CError* error) {
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::merge(mat_vector(csrcs, nsrcs), *dst); });
+}
+
+void cv_extract_channel(const CvMatrix* const csrc, CvMatrix* cdst, int coi,
+                        CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::extractChannel(*src, *dst, coi); });
+}
+
+void cv_insert_channel(const CvMatrix* const csrc, CvMatrix* cdst, int coi,
+                       CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::insertChannel(*src, *dst, coi); });
 }
 
 void cv_normalize(CvMatrix* csrc, CvMatrix* cdst, double alpha, double beta,
-                  int norm_type) {
+                  int norm_type, CError* error) {
     cv::Mat* src = reinterpret_cast<cv::Mat*>(csrc);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
-    cv::normalize(*src, *dst, alpha, beta, norm_type);
+    cv_try(error, [&]() { cv::normalize(*src, *dst, alpha, beta, norm_type); });
 }
 
 void cv_bitwise_and(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
-                    CvMatrix* cdst) {
+                    CvMatrix* cdst, CError* error) {
     const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
     const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
 
-    cv::bitwise_and(*src1, *src2, *dst);
+    cv_try(error, [&]() { cv::bitwise_and(*src1, *src2, *dst); });
 }
 
-void cv_bitwise_not(const CvMatrix* const csrc, CvMatrix* const cdst) {
+void cv_bitwise_not(const CvMatrix* const csrc, CvMatrix* const cdst,
+                    CError* error) {
     const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
 
-    cv::bitwise_not(*src, *dst);
+    cv_try(error, [&]() { cv::bitwise_not(*src, *dst); });
 }
 
 void cv_bitwise_or(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
-                   CvMatrix* cdst) {
+                   CvMatrix* cdst, CError* error) {
     const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
     const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
 
-    cv::bitwise_or(*src1, *src2, *dst);
+    cv_try(error, [&]() { cv::bitwise_or(*src1, *src2, *dst); });
 }
 
 void cv_bitwise_xor(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
-                    CvMatrix* cdst) {
+                    CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+
+    cv_try(error, [&]() { cv::bitwise_xor(*src1, *src2, *dst); });
+}
+
+int cv_count_non_zero(const CvMatrix* const csrc, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    int count = 0;
+    cv_try(error, [&]() { count = cv::countNonZero(*src); });
+    return count;
+}
+
+Scalar cv_sum(const CvMatrix* const csrc, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Scalar result;
+    cv_try(error, [&]() { result = cv::sum(*src); });
+    return scalar_cxx_to_c(result);
+}
+
+Scalar cv_mean(const CvMatrix* const csrc, const CvMatrix* const cmask,
+               CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    cv::Scalar result;
+    cv_try(error, [&]() {
+        result = cv::mean(*src, mask == nullptr ? cv::Mat() : *mask);
+    });
+    return scalar_cxx_to_c(result);
+}
+
+void cv_mean_std_dev(const CvMatrix* const csrc, Scalar* mean, Scalar* stddev,
+                     const CvMatrix* const cmask, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    cv_try(error, [&]() {
+        cv::Scalar cv_mean;
+        cv::Scalar cv_stddev;
+        cv::meanStdDev(*src, cv_mean, cv_stddev,
+                       mask == nullptr ? cv::Mat() : *mask);
+        *mean = scalar_cxx_to_c(cv_mean);
+        *stddev = scalar_cxx_to_c(cv_stddev);
+    });
+}
+
+double cv_norm(const CvMatrix* const csrc, int norm_type,
+               const CvMatrix* const cmask, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    double result = 0.0;
+    cv_try(error, [&]() {
+        result = cv::norm(*src, norm_type, mask == nullptr ? cv::Mat() : *mask);
+    });
+    return result;
+}
+
+double cv_norm_between(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                       int norm_type, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    double result = 0.0;
+    cv_try(error, [&]() { result = cv::norm(*src1, *src2, norm_type); });
+    return result;
+}
+
+void cv_reduce(const CvMatrix* const csrc, CvMatrix* cdst, int dim, int rtype,
+               int dtype, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::reduce(*src, *dst, dim, rtype, dtype); });
+}
+
+void cv_add(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+            CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::add(*src1, *src2, *dst); });
+}
+
+void cv_add_scalar(const CvMatrix* const csrc, Scalar value, CvMatrix* cdst,
+                   CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv::Scalar cv_value = scalar_c_to_cxx(value);
+    cv_try(error, [&]() { cv::add(*src, cv_value, *dst); });
+}
+
+void cv_subtract(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                 CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::subtract(*src1, *src2, *dst); });
+}
+
+void cv_subtract_scalar(const CvMatrix* const csrc, Scalar value, CvMatrix* cdst,
+                        CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv::Scalar cv_value = scalar_c_to_cxx(value);
+    cv_try(error, [&]() { cv::subtract(*src, cv_value, *dst); });
+}
+
+void cv_multiply(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                 CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::multiply(*src1, *src2, *dst); });
+}
+
+void cv_multiply_scalar(const CvMatrix* const csrc, Scalar value, CvMatrix* cdst,
+                        CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv::Scalar cv_value = scalar_c_to_cxx(value);
+    cv_try(error, [&]() { cv::multiply(*src, cv_value, *dst); });
+}
+
+void cv_divide(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+               CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::divide(*src1, *src2, *dst); });
+}
+
+void cv_divide_scalar(const CvMatrix* const csrc, Scalar value, CvMatrix* cdst,
+                      CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv::Scalar cv_value = scalar_c_to_cxx(value);
+    cv_try(error, [&]() { cv::divide(*src, cv_value, *dst); });
+}
+
+void cv_add_weighted(const CvMatrix* const csrc1, double alpha,
+                     const CvMatrix* const csrc2, double beta, double gamma,
+                     CvMatrix* cdst, CError* error) {
     const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
     const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
     cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error,
+           [&]() { cv::addWeighted(*src1, alpha, *src2, beta, gamma, *dst); });
+}
 
-    cv::bitwise_xor(*src1, *src2, *dst);
+void cv_absdiff(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::absdiff(*src1, *src2, *dst); });
 }
 
-int cv_count_non_zero(const CvMatrix* const csrc) {
+void cv_scale_add(const CvMatrix* const csrc1, double alpha,
+                  const CvMatrix* const csrc2, CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::scaleAdd(*src1, alpha, *src2, *dst); });
+}
+
+void cv_mat_convert_to(const CvMatrix* const csrc, CvMatrix* cdst, int rtype,
+                       double alpha, double beta, CError* error) {
     const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
-    return cv::countNonZero(*src);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { src->convertTo(*dst, rtype, alpha, beta); });
+}
+
+void cv_transpose(const CvMatrix* const csrc, CvMatrix* cdst, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::transpose(*src, *dst); });
+}
+
+void cv_repeat(const CvMatrix* const csrc, int ny, int nx, CvMatrix* cdst,
+               CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::repeat(*src, ny, nx, *dst); });
+}
+
+void cv_hconcat(const CvMatrix* const* csrcs, size_t nsrcs, CvMatrix* cdst,
+                CError* error) {
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::hconcat(mat_vector(csrcs, nsrcs), *dst); });
+}
+
+void cv_vconcat(const CvMatrix* const* csrcs, size_t nsrcs, CvMatrix* cdst,
+                CError* error) {
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::vconcat(mat_vector(csrcs, nsrcs), *dst); });
+}
+
+void cv_rotate(const CvMatrix* const csrc, CvMatrix* cdst, int code,
+               CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::rotate(*src, *dst, code); });
+}
+
+char* cv_mat_format(const CvMatrix* const cmat, int fmt, CError* error) {
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    std::ostringstream out;
+    cv_try(error, [&]() { out << cv::format(*mat, static_cast<cv::Formatter::FormatType>(fmt)); });
+    return string_cxx_to_c(out.str());
+}
+
+// =============================================================================
+//  Comparison
+// =============================================================================
+void cv_compare(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                CvMatrix* cdst, int cmpop, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::compare(*src1, *src2, *dst, cmpop); });
+}
+
+void cv_min(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+            CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::min(*src1, *src2, *dst); });
+}
+
+void cv_max(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+            CvMatrix* cdst, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::max(*src1, *src2, *dst); });
+}
+
+bool cv_check_range(const CvMatrix* const csrc, Point2i* pos, double min_val,
+                    double max_val, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    bool result = false;
+    cv_try(error, [&]() {
+        cv::Point cv_pos;
+        result = cv::checkRange(*src, true, &cv_pos, min_val, max_val);
+        pos->x = cv_pos.x;
+        pos->y = cv_pos.y;
+    });
+    return result;
+}
+
+// =============================================================================
+//  Discrete transforms
+// =============================================================================
+void cv_dft(const CvMatrix* const csrc, CvMatrix* cdst, int flags,
+            int nonzero_rows, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::dft(*src, *dst, flags, nonzero_rows); });
+}
+
+void cv_dct(const CvMatrix* const csrc, CvMatrix* cdst, int flags,
+            CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::dct(*src, *dst, flags); });
+}
+
+void cv_mul_spectrums(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+                      CvMatrix* cdst, int flags, bool conj, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error,
+           [&]() { cv::mulSpectrums(*src1, *src2, *dst, flags, conj); });
+}
+
+int cv_get_optimal_dft_size(int size) {
+    return cv::getOptimalDFTSize(size);
+}
+
+void cv_copy_make_border(const CvMatrix* const csrc, CvMatrix* cdst, int top,
+                         int bottom, int left, int right, int border_type,
+                         Scalar value, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() {
+        cv::copyMakeBorder(*src, *dst, top, bottom, left, right, border_type,
+                           scalar_c_to_cxx(value));
+    });
+}
+
+void cv_magnitude(const CvMatrix* const cx, const CvMatrix* const cy,
+                  CvMatrix* cdst, CError* error) {
+    const cv::Mat* x = reinterpret_cast<const cv::Mat*>(cx);
+    const cv::Mat* y = reinterpret_cast<const cv::Mat*>(cy);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::magnitude(*x, *y, *dst); });
+}
+
+void cv_phase(const CvMatrix* const cx, const CvMatrix* const cy,
+              CvMatrix* cdst, bool angle_in_degrees, CError* error) {
+    const cv::Mat* x = reinterpret_cast<const cv::Mat*>(cx);
+    const cv::Mat* y = reinterpret_cast<const cv::Mat*>(cy);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::phase(*x, *y, *dst, angle_in_degrees); });
+}
+
+void cv_cart_to_polar(const CvMatrix* const cx, const CvMatrix* const cy,
+                      CvMatrix* cmagnitude, CvMatrix* cangle,
+                      bool angle_in_degrees, CError* error) {
+    const cv::Mat* x = reinterpret_cast<const cv::Mat*>(cx);
+    const cv::Mat* y = reinterpret_cast<const cv::Mat*>(cy);
+    cv::Mat* magnitude = reinterpret_cast<cv::Mat*>(cmagnitude);
+    cv::Mat* angle = reinterpret_cast<cv::Mat*>(cangle);
+    cv_try(error, [&]() {
+        cv::cartToPolar(*x, *y, *magnitude, *angle, angle_in_degrees);
+    });
+}
+
+// =============================================================================
+//  Random numbers
+// =============================================================================
+CRng* cv_rng_new(uint64_t state, CError* error) {
+    cv::RNG* rng = new cv::RNG();
+    cv_try(error, [&]() { *rng = cv::RNG(state); });
+    return reinterpret_cast<CRng*>(rng);
+}
+
+void cv_rng_drop(CRng* crng) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    delete rng;
+    rng = nullptr;
+}
+
+uint64_t cv_rng_state(const CRng* const crng) {
+    const cv::RNG* rng = reinterpret_cast<const cv::RNG*>(crng);
+    return rng->state;
+}
+
+unsigned cv_rng_next(CRng* crng, CError* error) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    unsigned result = 0;
+    cv_try(error, [&]() { result = rng->next(); });
+    return result;
+}
+
+int cv_rng_uniform_int(CRng* crng, int a, int b, CError* error) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    int result = 0;
+    cv_try(error, [&]() { result = rng->uniform(a, b); });
+    return result;
+}
+
+double cv_rng_uniform_double(CRng* crng, double a, double b, CError* error) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    double result = 0;
+    cv_try(error, [&]() { result = rng->uniform(a, b); });
+    return result;
+}
+
+double cv_rng_gaussian(CRng* crng, double sigma, CError* error) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    double result = 0;
+    cv_try(error, [&]() { result = rng->gaussian(sigma); });
+    return result;
+}
+
+void cv_rng_fill(CRng* crng, CvMatrix* cmat, int dist_type, Scalar a,
+                 Scalar b, bool saturate_range, CError* error) {
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
+    cv_try(error, [&]() {
+        rng->fill(*mat, dist_type, scalar_c_to_cxx(a), scalar_c_to_cxx(b),
+                  saturate_range);
+    });
+}
+
+void cv_set_rng_seed(int seed) {
+    cv::setRNGSeed(seed);
+}
+
+void cv_randu(CvMatrix* cmat, Scalar low, Scalar high, CError* error) {
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
+    cv_try(error, [&]() {
+        cv::randu(*mat, scalar_c_to_cxx(low), scalar_c_to_cxx(high));
+    });
+}
+
+void cv_randn(CvMatrix* cmat, Scalar mean, Scalar stddev, CError* error) {
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
+    cv_try(error, [&]() {
+        cv::randn(*mat, scalar_c_to_cxx(mean), scalar_c_to_cxx(stddev));
+    });
+}
+
+void cv_rand_shuffle(CvMatrix* cmat, double iter_factor, CRng* crng,
+                     CError* error) {
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
+    cv::RNG* rng = reinterpret_cast<cv::RNG*>(crng);
+    cv_try(error, [&]() { cv::randShuffle(*mat, iter_factor, rng); });
+}
+
+// =============================================================================
+//  Linalg
+// =============================================================================
+void cv_gemm(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+             double alpha, const CvMatrix* const csrc3, double beta,
+             CvMatrix* cdst, int flags, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    const cv::Mat* src3 = reinterpret_cast<const cv::Mat*>(csrc3);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() {
+        if (src3 == nullptr) {
+            cv::gemm(*src1, *src2, alpha, cv::noArray(), 0.0, *dst, flags);
+        } else {
+            cv::gemm(*src1, *src2, alpha, *src3, beta, *dst, flags);
+        }
+    });
+}
+
+double cv_invert(const CvMatrix* const csrc, CvMatrix* cdst, int method,
+                 CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    double result = 0.0;
+    cv_try(error, [&]() { result = cv::invert(*src, *dst, method); });
+    return result;
+}
+
+bool cv_solve(const CvMatrix* const csrc1, const CvMatrix* const csrc2,
+              CvMatrix* cdst, int method, CError* error) {
+    const cv::Mat* src1 = reinterpret_cast<const cv::Mat*>(csrc1);
+    const cv::Mat* src2 = reinterpret_cast<const cv::Mat*>(csrc2);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    bool result = false;
+    cv_try(error, [&]() { result = cv::solve(*src1, *src2, *dst, method); });
+    return result;
+}
+
+void cv_svd_compute(const CvMatrix* const csrc, CvMatrix* cw, CvMatrix* cu,
+                    CvMatrix* cvt, int flags, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* w = reinterpret_cast<cv::Mat*>(cw);
+    cv::Mat* u = reinterpret_cast<cv::Mat*>(cu);
+    cv::Mat* vt = reinterpret_cast<cv::Mat*>(cvt);
+    cv_try(error, [&]() { cv::SVD::compute(*src, *w, *u, *vt, flags); });
+}
+
+void cv_eigen(const CvMatrix* const csrc, CvMatrix* ceigenvalues,
+              CvMatrix* ceigenvectors, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* eigenvalues = reinterpret_cast<cv::Mat*>(ceigenvalues);
+    cv::Mat* eigenvectors = reinterpret_cast<cv::Mat*>(ceigenvectors);
+    cv_try(error, [&]() { cv::eigen(*src, *eigenvalues, *eigenvectors); });
+}
+
+void cv_eigen_non_symmetric(const CvMatrix* const csrc, CvMatrix* ceigenvalues,
+                            CvMatrix* ceigenvectors, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* eigenvalues = reinterpret_cast<cv::Mat*>(ceigenvalues);
+    cv::Mat* eigenvectors = reinterpret_cast<cv::Mat*>(ceigenvectors);
+    cv_try(error, [&]() {
+        cv::eigenNonSymmetric(*src, *eigenvalues, *eigenvectors);
+    });
+}
+
+double cv_determinant(const CvMatrix* const csrc, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    double result = 0.0;
+    cv_try(error, [&]() { result = cv::determinant(*src); });
+    return result;
+}
+
+Scalar cv_trace(const CvMatrix* const csrc, CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Scalar result;
+    cv_try(error, [&]() { result = cv::trace(*src); });
+    return scalar_cxx_to_c(result);
 }
 
 // =============================================================================
 //  Imgproc
 // =============================================================================
 void cv_line(CvMatrix* cmat, Point2i pt1, Point2i pt2, Scalar color,
-             int thickness, int linetype, int shift) {
+             int thickness, int linetype, int shift, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Point point1(pt1.x, pt1.y);
     cv::Point point2(pt2.x, pt2.y);
-    cv::Scalar colour(color.v0, color.v1, color.v2, color.v3);
-    cv::line(*mat, point1, point2, colour, thickness, linetype, shift);
+    cv::Scalar colour = scalar_c_to_cxx(color);
+    cv_try(error, [&]() {
+        cv::line(*mat, point1, point2, colour, thickness, linetype, shift);
+    });
 }
 
 void cv_rectangle(CvMatrix* cmat, Rect crect, Scalar color, int thickness,
-                  int linetype) {
+                  int linetype, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Rect rect(crect.x, crect.y, crect.width, crect.height);
-    cv::Scalar colour(color.v0, color.v1, color.v2, color.v3);
-    cv::rectangle(*mat, rect, colour, thickness, linetype);
+    cv::Scalar colour = scalar_c_to_cxx(color);
+    cv_try(error, [&]() {
+        cv::rectangle(*mat, rect, colour, thickness, linetype);
+    });
 }
 
 void cv_ellipse(CvMatrix* cmat, Point2i center, Size2i axes, double angle,
                 double start_angle, double end_angle, Scalar color,
-                int thickness, int linetype, int shift) {
+                int thickness, int linetype, int shift, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Point cv_center(center.x, center.y);
     cv::Size cv_axes(axes.width, axes.height);
-    cv::Scalar cv_color(color.v0, color.v1, color.v2, color.v3);
+    cv::Scalar cv_color = scalar_c_to_cxx(color);
 
-    cv::ellipse(*mat, cv_center, cv_axes, angle, start_angle, end_angle,
-                cv_color, thickness, linetype, shift);
+    cv_try(error, [&]() {
+        cv::ellipse(*mat, cv_center, cv_axes, angle, start_angle, end_angle,
+                    cv_color, thickness, linetype, shift);
+    });
 }
 
-void cv_cvt_color(CvMatrix* cmat, CvMatrix* output, int code) {
+void cv_cvt_color(CvMatrix* cmat, CvMatrix* output, int code, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Mat* out = reinterpret_cast<cv::Mat*>(output);
-    cv::cvtColor(*mat, *out, code);
+    cv_try(error, [&]() { cv::cvtColor(*mat, *out, code); });
 }
 
-void cv_pyr_down(CvMatrix* cmat, CvMatrix* output) {
+void cv_pyr_down(CvMatrix* cmat, CvMatrix* output, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     cv::Mat* out = reinterpret_cast<cv::Mat*>(output);
-    cv::pyrDown(*mat, *out);
+    cv_try(error, [&]() { cv::pyrDown(*mat, *out); });
 }
 
 void cv_resize(CvMatrix* from, CvMatrix* to, Size2i dsize, double fx, double fy,
-               int interpolation) {
+               int interpolation, CError* error) {
     cv::Mat* cv_from = reinterpret_cast<cv::Mat*>(from);
     cv::Mat* cv_to = reinterpret_cast<cv::Mat*>(to);
     cv::Size cv_dsize(dsize.width, dsize.height);
-    cv::resize(*cv_from, *cv_to, cv_dsize, fx, fy, interpolation);
+    cv_try(error, [&]() {
+        cv::resize(*cv_from, *cv_to, cv_dsize, fx, fy, interpolation);
+    });
 }
 
-void cv_calc_hist(const CvMatrix* cimages, int nimages, const int* channels,
-                  CvMatrix* cmask, CvMatrix* chist, int dims,
-                  const int* hist_size, const float** ranges) {
-    const cv::Mat* images = reinterpret_cast<const cv::Mat*>(cimages);
-    cv::Mat* mask = reinterpret_cast<cv::Mat*>(cmask);
+void cv_calc_hist(const CvMatrix* const* cimages, size_t nimages,
+                  const int* channels, const CvMatrix* const cmask,
+                  CvMatrix* chist, int dims, const int* hist_size,
+                  const float* ranges, CError* error) {
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
     cv::Mat* hist = reinterpret_cast<cv::Mat*>(chist);
-    cv::calcHist(images, nimages, channels, *mask, *hist, dims, hist_size,
-                 ranges);
+    cv_try(error, [&]() {
+        std::vector<cv::Mat> images = mat_vector(cimages, nimages);
+        std::vector<const float*> dim_ranges = hist_ranges(ranges, dims);
+        cv::calcHist(images.data(), images.size(), channels,
+                     mask == nullptr ? cv::Mat() : *mask, *hist, dims,
+                     hist_size, dim_ranges.data());
+    });
+}
+
+void cv_calc_hist_sparse(const CvMatrix* const* cimages, size_t nimages,
+                         const int* channels, const CvMatrix* const cmask,
+                         CSparseMat* chist, int dims, const int* hist_size,
+                         const float* ranges, CError* error) {
+    const cv::Mat* mask = reinterpret_cast<const cv::Mat*>(cmask);
+    cv::SparseMat* hist = reinterpret_cast<cv::SparseMat*>(chist);
+    cv_try(error, [&]() {
+        std::vector<cv::Mat> images = mat_vector(cimages, nimages);
+        std::vector<const float*> dim_ranges = hist_ranges(ranges, dims);
+        cv::calcHist(images.data(), images.size(), channels,
+                     mask == nullptr ? cv::Mat() : *mask, *hist, dims,
+                     hist_size, dim_ranges.data());
+    });
+}
+
+void cv_calc_back_project(const CvMatrix* const* cimages, size_t nimages,
+                          const int* channels, const CvMatrix* const chist,
+                          CvMatrix* cback_project, int dims,
+                          const float* ranges, double scale, CError* error) {
+    const cv::Mat* hist = reinterpret_cast<const cv::Mat*>(chist);
+    cv::Mat* back_project = reinterpret_cast<cv::Mat*>(cback_project);
+    cv_try(error, [&]() {
+        std::vector<cv::Mat> images = mat_vector(cimages, nimages);
+        std::vector<const float*> dim_ranges = hist_ranges(ranges, dims);
+        cv::calcBackProject(images.data(), images.size(), channels, *hist,
+                            *back_project, dim_ranges.data(), scale);
+    });
+}
+
+double cv_compare_hist(const CvMatrix* const chist1,
+                       const CvMatrix* const chist2, int method,
+                       CError* error) {
+    const cv::Mat* hist1 = reinterpret_cast<const cv::Mat*>(chist1);
+    const cv::Mat* hist2 = reinterpret_cast<const cv::Mat*>(chist2);
+    double result = 0.0;
+    cv_try(error, [&]() { result = cv::compareHist(*hist1, *hist2, method); });
+    return result;
 }
 
-void cv_calc_back_project(const CvMatrix* cimages, int nimages,
-                          const int* channels, CvMatrix* chist,
-                          CvMatrix* cback_project, const float** ranges) {
-    const cv::Mat* images = reinterpret_cast<const cv::Mat*>(cimages);
-    cv::Mat* hist = reinterpret_cast<cv::Mat*>(chist);
-    cv::Mat* back_project = reinterpret_cast<cv::Mat*>(cback_project);
-    cv::calcBackProject(images, nimages, channels, *hist, *back_project,
-                        ranges);
+void cv_equalize_hist(const CvMatrix* const csrc, CvMatrix* cdst,
+                      CError* error) {
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { cv::equalizeHist(*src, *dst); });
+}
+
+CCLAHE* cv_clahe_new(double clip_limit, Size2i tile_grid_size,
+                     CError* error) {
+    cv::Size cv_tile_grid_size(tile_grid_size.width, tile_grid_size.height);
+    cv::Ptr<cv::CLAHE>* clahe = new cv::Ptr<cv::CLAHE>();
+    cv_try(error, [&]() {
+        *clahe = cv::createCLAHE(clip_limit, cv_tile_grid_size);
+    });
+    return reinterpret_cast<CCLAHE*>(clahe);
+}
+
+void cv_clahe_drop(CCLAHE* cclahe) {
+    cv::Ptr<cv::CLAHE>* clahe = reinterpret_cast<cv::Ptr<cv::CLAHE>*>(cclahe);
+    delete clahe;
+    clahe = nullptr;
+}
+
+void cv_clahe_apply(CCLAHE* cclahe, const CvMatrix* const csrc, CvMatrix* cdst,
+                    CError* error) {
+    cv::Ptr<cv::CLAHE>* clahe = reinterpret_cast<cv::Ptr<cv::CLAHE>*>(cclahe);
+    const cv::Mat* src = reinterpret_cast<const cv::Mat*>(csrc);
+    cv::Mat* dst = reinterpret_cast<cv::Mat*>(cdst);
+    cv_try(error, [&]() { clahe->get()->apply(*src, *dst); });
+}
+
+RotatedRect cv_min_area_rect(const Point2f* points, size_t npoints,
+                             CError* error) {
+    cv::RotatedRect rr;
+    cv_try(error, [&]() {
+        rr = cv::minAreaRect(vec_point2f_c_to_cxx(points, npoints));
+    });
+    return rotated_rect_cxx_to_c(rr);
+}
+
+RotatedRect cv_fit_ellipse(const Point2f* points, size_t npoints,
+                           CError* error) {
+    cv::RotatedRect rr;
+    cv_try(error, [&]() {
+        rr = cv::fitEllipse(vec_point2f_c_to_cxx(points, npoints));
+    });
+    return rotated_rect_cxx_to_c(rr);
+}
+
+void cv_box_points(RotatedRect rect, Point2f* points, CError* error) {
+    cv_try(error, [&]() {
+        cv::Point2f vertices[4];
+        rotated_rect_c_to_cxx(rect).points(vertices);
+        for (int i = 0; i < 4; i++) {
+            points[i].x = vertices[i].x;
+            points[i].y = vertices[i].y;
+        }
+    });
+}
+
+int cv_rotated_rectangle_intersection(RotatedRect rect1, RotatedRect rect2,
+                                      VecPoint2f* region, CError* error) {
+    int result = cv::INTERSECT_NONE;
+    std::vector<cv::Point2f> cv_region;
+    cv_try(error, [&]() {
+        result = cv::rotatedRectangleIntersection(rotated_rect_c_to_cxx(rect1),
+                                                  rotated_rect_c_to_cxx(rect2),
+                                                  cv_region);
+    });
+    vec_point2f_cxx_to_c(cv_region, region);
+    return result;
 }
 
 // =============================================================================
 //  Imgcodecs
 // =============================================================================
-CvMatrix* cv_imdecode(const uint8_t* const buffer, size_t len, int flag) {
+CvMatrix* cv_imdecode(const uint8_t* const buffer, size_t len, int flag,
+                      CError* error) {
     cv::Mat* dst = new cv::Mat();
-    std::vector<uchar> input(buffer, buffer + len);
-    cv::imdecode(cv::Mat(input), flag, dst);
+    cv_try(error, [&]() {
+        std::vector<uchar> input(buffer, buffer + len);
+        cv::imdecode(cv::Mat(input), flag, dst);
+    });
     return reinterpret_cast<CvMatrix*>(dst);
 }
 
 // The caller is responsible for the allocated buffer
 ImencodeResult cv_imencode(const char* const ext, const CvMatrix* const cmat,
-                           const int* const flag_ptr, size_t flag_size) {
+                           const int* const flag_ptr, size_t flag_size,
+                           CError* error) {
     const cv::Mat* image = reinterpret_cast<const cv::Mat*>(cmat);
     std::vector<uchar> buf;
     std::vector<int> params(flag_ptr, flag_ptr + flag_size);
-    bool r = cv::imencode(ext, *image, buf, params);
+    bool r = false;
+    cv_try(error, [&]() { r = cv::imencode(ext, *image, buf, params); });
 
     int size = buf.size();
     uint8_t* buffer = new uint8_t[size];
@@ -317,43 +1146,55 @@ ImencodeResult cv_imencode(const char* const ext, const CvMatrix* const cmat,
     return result;
 }
 
+void cv_imencode_result_drop(ImencodeResult* result) {
+    delete[] result->buf;
+    result->buf = nullptr;
+    result->size = 0;
+}
+
 // =============================================================================
 //   Highgui: high-level GUI
 // =============================================================================
-void cv_named_window(const char* const winname, int flags) {
-    cv::namedWindow(winname, flags);
+void cv_named_window(const char* const winname, int flags, CError* error) {
+    cv_try(error, [&]() { cv::namedWindow(winname, flags); });
 }
 
-void cv_destroy_window(const char* const winname) {
-    cv::destroyWindow(winname);
+void cv_destroy_window(const char* const winname, CError* error) {
+    cv_try(error, [&]() { cv::destroyWindow(winname); });
 }
 
-void cv_imshow(const char* const winname, CvMatrix* cmat) {
+void cv_imshow(const char* const winname, CvMatrix* cmat, CError* error) {
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
     if (mat != NULL) {
-        cv::imshow(winname, *mat);
+        cv_try(error, [&]() { cv::imshow(winname, *mat); });
     }
 }
 
-int cv_wait_key(int delay) {
-    return cv::waitKey(delay);
+int cv_wait_key(int delay, CError* error) {
+    int key = -1;
+    cv_try(error, [&]() { key = cv::waitKey(delay); });
+    return key;
 }
 
 void cv_set_mouse_callback(const char* const winname, MouseCallback on_mouse,
-                           void* userdata) {
-    cv::setMouseCallback(winname, on_mouse, userdata);
+                           void* userdata, CError* error) {
+    cv_try(error,
+           [&]() { cv::setMouseCallback(winname, on_mouse, userdata); });
 }
 
 // =============================================================================
 //   VideoCapture
 // =============================================================================
-CVideoCapture* cv_videocapture_new(int index) {
-    cv::VideoCapture* cap = new cv::VideoCapture(index);
+CVideoCapture* cv_videocapture_new(int index, CError* error) {
+    cv::VideoCapture* cap = new cv::VideoCapture();
+    cv_try(error, [&]() { cap->open(index); });
     return reinterpret_cast<CVideoCapture*>(cap);
 }
 
-CVideoCapture* cv_videocapture_from_file(const char* const filename) {
-    cv::VideoCapture* cap = new cv::VideoCapture(filename);
+CVideoCapture* cv_videocapture_from_file(const char* const filename,
+                                         CError* error) {
+    cv::VideoCapture* cap = new cv::VideoCapture();
+    cv_try(error, [&]() { cap->open(filename); });
     return reinterpret_cast<CVideoCapture*>(cap);
 }
 
@@ -363,10 +1204,13 @@ bool cv_videocapture_is_opened(const CVideoCapture* const ccap) {
     return cap->isOpened();
 }
 
-bool cv_videocapture_read(CVideoCapture* ccap, CvMatrix* cmat) {
+bool cv_videocapture_read(CVideoCapture* ccap, CvMatrix* cmat,
+                          CError* error) {
     cv::VideoCapture* cap = reinterpret_cast<cv::VideoCapture*>(ccap);
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
-    return cap->read(*mat);
+    bool grabbed = false;
+    cv_try(error, [&]() { grabbed = cap->read(*mat); });
+    return grabbed;
 }
 
 void cv_videocapture_drop(CVideoCapture* ccap) {
@@ -375,14 +1219,19 @@ void cv_videocapture_drop(CVideoCapture* ccap) {
     ccap = nullptr;
 }
 
-bool cv_videocapture_set(CVideoCapture* ccap, int property, double value) {
+bool cv_videocapture_set(CVideoCapture* ccap, int property, double value,
+                         CError* error) {
     cv::VideoCapture* cap = reinterpret_cast<cv::VideoCapture*>(ccap);
-    return cap->set(property, value);
+    bool result = false;
+    cv_try(error, [&]() { result = cap->set(property, value); });
+    return result;
 }
 
-double cv_videocapture_get(CVideoCapture* ccap, int property) {
+double cv_videocapture_get(CVideoCapture* ccap, int property, CError* error) {
     cv::VideoCapture* cap = reinterpret_cast<cv::VideoCapture*>(ccap);
-    return cap->get(property);
+    double result = 0;
+    cv_try(error, [&]() { result = cap->get(property); });
+    return result;
 }
 
 // =============================================================================
@@ -400,10 +1249,13 @@ CVideoWriter* cv_videowriter_default() {
 }
 
 CVideoWriter* cv_videowriter_new(const char* const path, int fourcc, double fps,
-                                 Size2i frame_size, bool is_color) {
+                                 Size2i frame_size, bool is_color,
+                                 CError* error) {
     cv::Size cv_frame_size(frame_size.width, frame_size.height);
-    cv::VideoWriter* writer =
-        new cv::VideoWriter(path, fourcc, fps, cv_frame_size, is_color);
+    cv::VideoWriter* writer = new cv::VideoWriter();
+    cv_try(error, [&]() {
+        writer->open(path, fourcc, fps, cv_frame_size, is_color);
+    });
     return reinterpret_cast<CVideoWriter*>(writer);
 }
 
@@ -415,10 +1267,14 @@ void cv_videowriter_drop(CVideoWriter* writer) {
 
 bool cv_videowriter_open(CVideoWriter* writer, const char* const path,
                          int fourcc, double fps, Size2i frame_size,
-                         bool is_color) {
+                         bool is_color, CError* error) {
     cv::VideoWriter* cv_writer = reinterpret_cast<cv::VideoWriter*>(writer);
     cv::Size cv_frame_size(frame_size.width, frame_size.height);
-    return cv_writer->open(path, fourcc, fps, cv_frame_size, is_color);
+    bool opened = false;
+    cv_try(error, [&]() {
+        opened = cv_writer->open(path, fourcc, fps, cv_frame_size, is_color);
+    });
+    return opened;
 }
 
 bool cv_videowriter_is_opened(CVideoWriter* writer) {
@@ -426,20 +1282,26 @@ bool cv_videowriter_is_opened(CVideoWriter* writer) {
     return cv_writer->isOpened();
 }
 
-void cv_videowriter_write(CVideoWriter* writer, CvMatrix* cmat) {
+void cv_videowriter_write(CVideoWriter* writer, CvMatrix* cmat,
+                          CError* error) {
     cv::VideoWriter* cv_writer = reinterpret_cast<cv::VideoWriter*>(writer);
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(cmat);
-    (*cv_writer) << (*mat);
+    cv_try(error, [&]() { (*cv_writer) << (*mat); });
 }
 
-bool cv_videowriter_set(CVideoWriter* writer, int property, double value) {
+bool cv_videowriter_set(CVideoWriter* writer, int property, double value,
+                        CError* error) {
     cv::VideoWriter* cv_writer = reinterpret_cast<cv::VideoWriter*>(writer);
-    return cv_writer->set(property, value);
+    bool result = false;
+    cv_try(error, [&]() { result = cv_writer->set(property, value); });
+    return result;
 }
 
-double cv_videowriter_get(CVideoWriter* writer, int property) {
+double cv_videowriter_get(CVideoWriter* writer, int property, CError* error) {
     cv::VideoWriter* cv_writer = reinterpret_cast<cv::VideoWriter*>(writer);
-    return cv_writer->get(property);
+    double result = 0;
+    cv_try(error, [&]() { result = cv_writer->get(property); });
+    return result;
 }
 
 // =============================================================================
@@ -450,14 +1312,19 @@ CCascadeClassifier* cv_cascade_classifier_new() {
     return reinterpret_cast<CCascadeClassifier*>(cc);
 }
 
-bool cv_cascade_classifier_load(CCascadeClassifier* cc, const char* const p) {
+bool cv_cascade_classifier_load(CCascadeClassifier* cc, const char* const p,
+                                CError* error) {
     cv::CascadeClassifier* cascade =
         reinterpret_cast<cv::CascadeClassifier*>(cc);
-    return cascade->load(p);
+    bool loaded = false;
+    cv_try(error, [&]() { loaded = cascade->load(p); });
+    return loaded;
 }
 
-CCascadeClassifier* cv_cascade_classifier_from_path(const char* const p) {
-    cv::CascadeClassifier* cc = new cv::CascadeClassifier(p);
+CCascadeClassifier* cv_cascade_classifier_from_path(const char* const p,
+                                                    CError* error) {
+    cv::CascadeClassifier* cc = new cv::CascadeClassifier();
+    cv_try(error, [&]() { cc->load(p); });
     return reinterpret_cast<CCascadeClassifier*>(cc);
 }
 
@@ -471,7 +1338,7 @@ void cv_cascade_classifier_drop(CCascadeClassifier* cc) {
 void cv_cascade_classifier_detect(CCascadeClassifier* cc, CvMatrix* cmat,
                                   VecRect* vec_of_rect, double scale_factor,
                                   int min_neighbors, int flags, Size2i min_size,
-                                  Size2i max_size) {
+                                  Size2i max_size, CError* error) {
     cv::CascadeClassifier* cascade =
         reinterpret_cast<cv::CascadeClassifier*>(cc);
     cv::Mat* image = reinterpret_cast<cv::Mat*>(cmat);
@@ -479,8 +1346,10 @@ void cv_cascade_classifier_detect(CCascadeClassifier* cc, CvMatrix* cmat,
 
     cv::Size cv_min_size(min_size.width, min_size.height);
     cv::Size cv_max_size(max_size.width, max_size.height);
-    cascade->detectMultiScale(*image, objects, scale_factor, min_neighbors,
-                              flags, cv_min_size, cv_max_size);
+    cv_try(error, [&]() {
+        cascade->detectMultiScale(*image, objects, scale_factor, min_neighbors,
+                                  flags, cv_min_size, cv_max_size);
+    });
     // Move objects to vec_of_rect
     size_t num = objects.size();
     vec_of_rect->array = (Rect*) malloc(num * sizeof(Rect));
@@ -522,16 +1391,18 @@ void cv_hog_drop(HogDescriptor* hog) {
     cv_hog = nullptr;
 }
 
-void cv_hog_set_svm_detector(HogDescriptor* hog, SvmDetector* detector) {
+void cv_hog_set_svm_detector(HogDescriptor* hog, SvmDetector* detector,
+                             CError* error) {
     cv::HOGDescriptor* cv_hog = reinterpret_cast<cv::HOGDescriptor*>(hog);
     std::vector<float>* cv_detector =
         reinterpret_cast<std::vector<float>*>(detector);
-    cv_hog->setSVMDetector(*cv_detector);
+    cv_try(error, [&]() { cv_hog->setSVMDetector(*cv_detector); });
 }
 
 void cv_hog_detect(HogDescriptor* hog, CvMatrix* cmat, VecRect* vec_rect,
                    VecDouble* vec_weight, Size2i win_stride, Size2i padding,
-                   double scale, double final_threshold, bool use_means_shift) {
+                   double scale, double final_threshold, bool use_means_shift,
+                   CError* error) {
     // convert all types
     cv::HOGDescriptor* cv_hog = reinterpret_cast<cv::HOGDescriptor*>(hog);
     cv::Mat* image = reinterpret_cast<cv::Mat*>(cmat);
@@ -541,9 +1412,11 @@ void cv_hog_detect(HogDescriptor* hog, CvMatrix* cmat, VecRect* vec_rect,
     cv::Size cv_padding(padding.width, padding.height);
 
     // Call the function
-    cv_hog->detectMultiScale(*image, objects, weights, 0.1, cv_win_stride,
-                             cv_padding, scale, final_threshold,
-                             use_means_shift);
+    cv_try(error, [&]() {
+        cv_hog->detectMultiScale(*image, objects, weights, 0.1, cv_win_stride,
+                                 cv_padding, scale, final_threshold,
+                                 use_means_shift);
+    });
 
     // Prepare the results
     vec_rect_cxx_to_c(objects, vec_rect);
@@ -566,19 +1439,14 @@ void cv_term_criteria_drop(CTermCriteria* c_criteria) {
 }
 
 RotatedRect cv_camshift(CvMatrix* c_bp_image, Rect crect,
-                        CTermCriteria* c_criteria) {
+                        CTermCriteria* c_criteria, CError* error) {
     cv::Mat* bp_image = reinterpret_cast<cv::Mat*>(c_bp_image);
     cv::Rect rect(crect.x, crect.y, crect.width, crect.height);
     cv::TermCriteria* criteria =
         reinterpret_cast<cv::TermCriteria*>(c_criteria);
-    cv::RotatedRect rr = cv::CamShift(*bp_image, rect, *criteria);
-    RotatedRect c_rr;
-    c_rr.center.x = rr.center.x;
-    c_rr.center.y = rr.center.y;
-    c_rr.size.width = rr.size.width;
-    c_rr.size.height = rr.size.height;
-    c_rr.angle = rr.angle;
-    return c_rr;
+    cv::RotatedRect rr;
+    cv_try(error, [&]() { rr = cv::CamShift(*bp_image, rect, *criteria); });
+    return rotated_rect_cxx_to_c(rr);
 }
 
 // =============================================================================
@@ -593,17 +1461,21 @@ CMSER* cv_mser_new(int delta,
                    int max_evolution,
                    double area_threshold,
                    double min_margin,
-                   int edge_blur_size) {
-    cv::Ptr<cv::MSER> result = cv::MSER::create(delta,
-                                                min_area,
-                                                max_area,
-                                                max_variation,
-                                                min_diversity,
-                                                max_evolution,
-                                                area_threshold,
-                                                min_margin,
-                                                edge_blur_size);
-    return reinterpret_cast<CMSER*>(new cv::Ptr<cv::MSER>(result));
+                   int edge_blur_size,
+                   CError* error) {
+    cv::Ptr<cv::MSER>* result = new cv::Ptr<cv::MSER>();
+    cv_try(error, [&]() {
+        *result = cv::MSER::create(delta,
+                                   min_area,
+                                   max_area,
+                                   max_variation,
+                                   min_diversity,
+                                   max_evolution,
+                                   area_threshold,
+                                   min_margin,
+                                   edge_blur_size);
+    });
+    return reinterpret_cast<CMSER*>(result);
 }
 
 void cv_mser_drop(CMSER* cmser) {
@@ -612,16 +1484,210 @@ void cv_mser_drop(CMSER* cmser) {
     mser = nullptr;
 }
 
-void cv_mser_detect_regions(CMSER* cmser, CvMatrix* image, VecPoints* msers, VecRect* bboxes) {
+void cv_mser_detect_regions(CMSER* cmser, CvMatrix* image, VecPoints* msers, VecRect* bboxes,
+                            CError* error) {
     cv::Ptr<cv::MSER>* mser = reinterpret_cast<cv::Ptr<cv::MSER>*>(cmser);
     cv::Mat* mat = reinterpret_cast<cv::Mat*>(image);
     std::vector<std::vector<cv::Point>> msers_vector;
     std::vector<cv::Rect> bboxes_vector;
 
-    mser->get()->detectRegions(*mat, msers_vector, bboxes_vector);
+    cv_try(error, [&]() { mser->get()->detectRegions(*mat, msers_vector, bboxes_vector); });
 
     vec_points_cxx_to_c(msers_vector, msers);
     vec_rect_cxx_to_c(bboxes_vector, bboxes);
 }
 
+// =============================================================================
+//   Persistence
+// =============================================================================
+CFileStorage* cv_file_storage_new(const char* source, int flags, CError* error) {
+    cv::FileStorage* fs = new cv::FileStorage();
+    cv_try(error, [&]() { fs->open(source, flags); });
+    return reinterpret_cast<CFileStorage*>(fs);
+}
+
+void cv_file_storage_drop(CFileStorage* cfs) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    delete fs;
+    fs = nullptr;
+}
+
+bool cv_file_storage_is_opened(const CFileStorage* cfs) {
+    const cv::FileStorage* fs = reinterpret_cast<const cv::FileStorage*>(cfs);
+    return fs->isOpened();
+}
+
+char* cv_file_storage_release(CFileStorage* cfs, CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    std::string content;
+    cv_try(error, [&]() { content = fs->releaseAndGetString(); });
+    return string_cxx_to_c(content);
+}
+
+// Elements of a map are preceded by their name; elements of a sequence are
+// written with an empty one.
+static void file_storage_name(cv::FileStorage& fs, const char* name) {
+    if (*name != '\0') {
+        fs << name;
+    }
+}
+
+void cv_file_storage_start_struct(CFileStorage* cfs, const char* name, bool seq, bool flow,
+                                  CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    std::string open = seq ? "[" : "{";
+    if (flow) {
+        open += ":";
+    }
+    cv_try(error, [&]() {
+        file_storage_name(*fs, name);
+        *fs << open;
+    });
+}
+
+void cv_file_storage_end_struct(CFileStorage* cfs, CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    cv_try(error, [&]() {
+        if (fs->structs.empty()) {
+            CV_Error(cv::Error::StsError, "No open sequence or map to end");
+        }
+        *fs << (fs->structs.back() == '[' ? "]" : "}");
+    });
+}
+
+void cv_file_storage_write_int(CFileStorage* cfs, const char* name, int value, CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    cv_try(error, [&]() {
+        file_storage_name(*fs, name);
+        *fs << value;
+    });
+}
+
+void cv_file_storage_write_real(CFileStorage* cfs, const char* name, double value,
+                                CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    cv_try(error, [&]() {
+        file_storage_name(*fs, name);
+        *fs << value;
+    });
+}
+
+void cv_file_storage_write_string(CFileStorage* cfs, const char* name, const char* value,
+                                  CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    // Strings starting with a bracket would open or close a structure unless
+    // escaped.
+    std::string s = value[0] != '\0' && std::strchr("{}[]", value[0]) != nullptr
+                        ? std::string("\\") + value
+                        : std::string(value);
+    cv_try(error, [&]() {
+        file_storage_name(*fs, name);
+        *fs << s;
+    });
+}
+
+void cv_file_storage_write_mat(CFileStorage* cfs, const char* name, const CvMatrix* cmat,
+                               CError* error) {
+    cv::FileStorage* fs = reinterpret_cast<cv::FileStorage*>(cfs);
+    const cv::Mat* mat = reinterpret_cast<const cv::Mat*>(cmat);
+    cv_try(error, [&]() {
+        file_storage_name(*fs, name);
+        *fs << *mat;
+    });
+}
+
+CFileNode* cv_file_storage_root(const CFileStorage* cfs) {
+    const cv::FileStorage* fs = reinterpret_cast<const cv::FileStorage*>(cfs);
+    return reinterpret_cast<CFileNode*>(new cv::FileNode(fs->root()));
+}
+
+void cv_file_node_drop(CFileNode* cnode) {
+    cv::FileNode* node = reinterpret_cast<cv::FileNode*>(cnode);
+    delete node;
+    node = nullptr;
+}
+
+int cv_file_node_type(const CFileNode* cnode) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    return node->type();
+}
+
+size_t cv_file_node_size(const CFileNode* cnode) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    return node->size();
+}
+
+char* cv_file_node_name(const CFileNode* cnode, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    std::string name;
+    cv_try(error, [&]() { name = node->name(); });
+    return string_cxx_to_c(name);
+}
+
+CFileNode* cv_file_node_get(const CFileNode* cnode, const char* key, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    cv::FileNode* result = new cv::FileNode();
+    cv_try(error, [&]() { *result = (*node)[key]; });
+    return reinterpret_cast<CFileNode*>(result);
+}
+
+CFileNode* cv_file_node_child(const CFileNode* cnode, size_t index, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    cv::FileNode* result = new cv::FileNode();
+    cv_try(error, [&]() {
+        cv::FileNodeIterator it = node->begin();
+        it += index;
+        *result = *it;
+    });
+    return reinterpret_cast<CFileNode*>(result);
+}
+
+int cv_file_node_int(const CFileNode* cnode, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    int value = 0;
+    cv_try(error, [&]() { value = (int) *node; });
+    return value;
+}
+
+double cv_file_node_real(const CFileNode* cnode, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    double value = 0.0;
+    cv_try(error, [&]() { value = node->real(); });
+    return value;
+}
+
+char* cv_file_node_string(const CFileNode* cnode, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    std::string value;
+    cv_try(error, [&]() { value = node->string(); });
+    return string_cxx_to_c(value);
+}
+
+void cv_file_node_mat(const CFileNode* cnode, CvMatrix* dst, CError* error) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    cv::Mat* mat = reinterpret_cast<cv::Mat*>(dst);
+    cv_try(error, [&]() { cv::read(*node, *mat); });
+}
+
+CFileNodeIter* cv_file_node_iter_new(const CFileNode* cnode) {
+    const cv::FileNode* node = reinterpret_cast<const cv::FileNode*>(cnode);
+    return reinterpret_cast<CFileNodeIter*>(new cv::FileNodeIterator(node->begin()));
+}
+
+void cv_file_node_iter_drop(CFileNodeIter* citer) {
+    cv::FileNodeIterator* it = reinterpret_cast<cv::FileNodeIterator*>(citer);
+    delete it;
+    it = nullptr;
+}
+
+CFileNode* cv_file_node_iter_next(CFileNodeIter* citer, CError* error) {
+    cv::FileNodeIterator* it = reinterpret_cast<cv::FileNodeIterator*>(citer);
+    cv::FileNode* node = new cv::FileNode();
+    cv_try(error, [&]() {
+        *node = **it;
+        ++(*it);
+    });
+    return reinterpret_cast<CFileNode*>(node);
+}
+
 EXTERN_C_END
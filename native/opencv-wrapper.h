@@ -58,13 +58,14 @@ VecType(void, Vec);
 VecType(Rect, VecRect);
 VecType(double, VecDouble);
 VecType(Point2i, VecPoint);
+VecType(Point2f, VecPoint2f);
 VecType(VecPoint, VecPoints);
 
 typedef struct {
-    int32_t v0;
-    int32_t v1;
-    int32_t v2;
-    int32_t v3;
+    double v0;
+    double v1;
+    double v2;
+    double v3;
 } Scalar;
 
 typedef struct {
@@ -73,22 +74,44 @@ typedef struct {
     size_t size;
 } ImencodeResult;
 
+// Describes a C++ exception caught by the wrapper. `msg` is NULL when no
+// exception occurred. The strings are owned by the struct and released with
+// `cv_error_drop`.
+typedef struct {
+    int code;
+    int line;
+    char* func;
+    char* file;
+    char* msg;
+} CError;
+
+void cv_error_drop(CError* error);
+
+// Releases a string returned by the wrapper.
+void cv_string_drop(char* s);
+
 // The caller owns the returned data CvMatrix
 CvMatrix* cv_mat_new();
-CvMatrix* cv_mat_new_with_size(int rows, int cols, int type);
-CvMatrix* cv_mat_zeros(int rows, int cols, int type);
-CvMatrix* cv_mat_from_buffer(int rows, int cols, int type, const uint8_t* buf);
+CvMatrix* cv_mat_new_with_size(int rows, int cols, int type, CError* error);
+CvMatrix* cv_mat_zeros(int rows, int cols, int type, CError* error);
+CvMatrix* cv_mat_zeros_nd(int ndims, const int* sizes, int type,
+                          CError* error);
+CvMatrix* cv_mat_ones(int rows, int cols, int type, CError* error);
+CvMatrix* cv_mat_eye(int rows, int cols, int type, CError* error);
+CvMatrix* cv_mat_from_buffer(int rows, int cols, int type, const uint8_t* buf,
+                             CError* error);
 
 bool cv_mat_valid(CvMatrix* cmat);
 
 // The caller owns the returned CvMatrix
-CvMatrix* cv_mat_roi(CvMatrix* cmat, Rect crect);
+CvMatrix* cv_mat_roi(CvMatrix* cmat, Rect crect, CError* error);
 
-void cv_mat_logic_and(CvMatrix* image, const CvMatrix* const mask);
-void cv_mat_flip(CvMatrix* image, int code);
+void cv_mat_logic_and(CvMatrix* image, const CvMatrix* const mask,
+                      CError* error);
+void cv_mat_flip(CvMatrix* image, int code, CError* error);
 
 // The caller owns the returned data CvMatrix
-CvMatrix* cv_imread(const char* const filename, int flags);
+CvMatrix* cv_imread(const char* const filename, int flags, CError* error);
 
 int cv_mat_rows(const CvMatrix* const cmat);
 int cv_mat_cols(const CvMatrix* const cmat);
@@ -100,85 +123,303 @@ size_t cv_mat_total(const CvMatrix* const cmat);
 size_t cv_mat_elem_size(const CvMatrix* const cmat);
 size_t cv_mat_elem_size1(const CvMatrix* const cmat);
 size_t cv_mat_step1(const CvMatrix* const cmat, int i);
+int cv_mat_dims(const CvMatrix* const cmat);
+int cv_mat_size_nd(const CvMatrix* const cmat, int i);
+
+// The caller owns the returned CvMatrix. `cv_mat_clone` copies the data while
+// `cv_mat_share` only copies the header and bumps the reference count.
+CvMatrix* cv_mat_clone(const CvMatrix* const cmat, CError* error);
+CvMatrix* cv_mat_share(const CvMatrix* const cmat);
+// Like `cv_mat_share`, with `cn` channels and `rows` rows (0 keeps them).
+CvMatrix* cv_mat_reshape(const CvMatrix* const cmat, int cn, int rows,
+                         CError* error);
+// False if the Mat points to user data that OpenCV doesn't reference count.
+bool cv_mat_is_refcounted(const CvMatrix* const cmat);
+bool cv_mat_is_continuous(const CvMatrix* const cmat);
+// True if both have the same size, type and bytes.
+bool cv_mat_eq(const CvMatrix* const ca, const CvMatrix* const cb, CError* error);
+
+// `cmask` can be NULL to copy or set every element.
+void cv_mat_copy_to(const CvMatrix* const csrc, CvMatrix* cdst,
+                    const CvMatrix* const cmask, CError* error);
+void cv_mat_set_to(CvMatrix* cmat, Scalar value, const CvMatrix* const cmask,
+                   CError* error);
 
 // Free a Mat object
 void cv_mat_drop(CvMatrix* cmat);
 
 void cv_vec_drop(Vec* vec, unsigned int depth);
 
+// =============================================================================
+//  SparseMat
+// =============================================================================
+typedef struct _CSparseMat CSparseMat;
+CSparseMat* cv_sparse_mat_new(int ndims, const int* sizes, int type,
+                              CError* error);
+CSparseMat* cv_sparse_mat_from_mat(const CvMatrix* const cmat, CError* error);
+CSparseMat* cv_sparse_mat_clone(const CSparseMat* const csm, CError* error);
+void cv_sparse_mat_drop(CSparseMat* csm);
+int cv_sparse_mat_dims(const CSparseMat* const csm);
+int cv_sparse_mat_size(const CSparseMat* const csm, int i);
+int cv_sparse_mat_type(const CSparseMat* const csm);
+size_t cv_sparse_mat_nzcount(const CSparseMat* const csm);
+// Returns NULL if the element doesn't exist and `create` is false.
+uint8_t* cv_sparse_mat_ptr(CSparseMat* csm, const int* idx, bool create,
+                           CError* error);
+void cv_sparse_mat_erase(CSparseMat* csm, const int* idx, CError* error);
+// `indices` must hold `nzcount * dims` ints and `values` `nzcount` elements.
+void cv_sparse_mat_nonzeros(const CSparseMat* const csm, int* indices,
+                            uint8_t* values);
+void cv_sparse_mat_to_dense(const CSparseMat* const csm, CvMatrix* cdst,
+                            CError* error);
+
 // =============================================================================
 //  core array
 // =============================================================================
-void cv_in_range(CvMatrix* cmat, Scalar lowerb, Scalar upperb, CvMatrix* dst);
+void cv_in_range(CvMatrix* cmat, Scalar lowerb, Scalar upperb, CvMatrix* dst,
+                 CError* error);
 void cv_min_max_loc(const CvMatrix* const cmat, double* min, double* max,
                     Point2i* minLoc, Point2i* maxLoc,
-                    const CvMatrix* const cmask);
-void cv_mix_channels(CvMatrix* cmat, size_t nsrcs, CvMatrix* dst, size_t ndsts,
-                     const int* from_to, size_t npairs);
+                    const CvMatrix* const cmask, CError* error);
+// The destinations must be allocated; their data is written in place.
+void cv_mix_channels(const CvMatrix* const* srcs, size_t nsrcs,
+                     const CvMatrix* const* dsts, size_t ndsts,
+                     const int* from_to, size_t npairs, CError* error);
+// Assigns the channels of `src` to the `ndsts` matrices of `dsts`.
+void cv_split(const CvMatrix* const src, CvMatrix* const* dsts, size_t ndsts,
+              CError* error);
+void cv_merge(const CvMatrix* const* srcs, size_t nsrcs, CvMatrix* dst,
+              CError* error);
+void cv_extract_channel(const CvMatrix* const src, CvMatrix* dst, int coi,
+                        CError* error);
+void cv_insert_channel(const CvMatrix* const src, CvMatrix* dst, int coi,
+                       CError* error);
 void cv_normalize(CvMatrix* csrc, CvMatrix* cdst, double alpha, double beta,
-                  int norm_type);
+                  int norm_type, CError* error);
 void cv_bitwise_and(const CvMatrix* const src1, const CvMatrix* const src2,
-                    CvMatrix* dst);
-void cv_bitwise_not(const CvMatrix* const src, CvMatrix* const dst);
+                    CvMatrix* dst, CError* error);
+void cv_bitwise_not(const CvMatrix* const src, CvMatrix* const dst,
+                    CError* error);
 void cv_bitwise_or(const CvMatrix* const src1, const CvMatrix* const src2,
-                   CvMatrix* dst);
+                   CvMatrix* dst, CError* error);
 void cv_bitwise_xor(const CvMatrix* const src1, const CvMatrix* const src2,
-                    CvMatrix* dst);
-int cv_count_non_zero(const CvMatrix* const src);
+                    CvMatrix* dst, CError* error);
+int cv_count_non_zero(const CvMatrix* const src, CError* error);
+// `cmask` can be NULL to use every element.
+Scalar cv_sum(const CvMatrix* const src, CError* error);
+Scalar cv_mean(const CvMatrix* const src, const CvMatrix* const cmask,
+               CError* error);
+void cv_mean_std_dev(const CvMatrix* const src, Scalar* mean, Scalar* stddev,
+                     const CvMatrix* const cmask, CError* error);
+double cv_norm(const CvMatrix* const src, int norm_type,
+               const CvMatrix* const cmask, CError* error);
+double cv_norm_between(const CvMatrix* const src1, const CvMatrix* const src2,
+                       int norm_type, CError* error);
+void cv_reduce(const CvMatrix* const src, CvMatrix* dst, int dim, int rtype,
+               int dtype, CError* error);
+void cv_add(const CvMatrix* const src1, const CvMatrix* const src2,
+            CvMatrix* dst, CError* error);
+void cv_add_scalar(const CvMatrix* const src, Scalar value, CvMatrix* dst,
+                   CError* error);
+void cv_subtract(const CvMatrix* const src1, const CvMatrix* const src2,
+                 CvMatrix* dst, CError* error);
+void cv_subtract_scalar(const CvMatrix* const src, Scalar value, CvMatrix* dst,
+                        CError* error);
+void cv_multiply(const CvMatrix* const src1, const CvMatrix* const src2,
+                 CvMatrix* dst, CError* error);
+void cv_multiply_scalar(const CvMatrix* const src, Scalar value, CvMatrix* dst,
+                        CError* error);
+void cv_divide(const CvMatrix* const src1, const CvMatrix* const src2,
+               CvMatrix* dst, CError* error);
+void cv_divide_scalar(const CvMatrix* const src, Scalar value, CvMatrix* dst,
+                      CError* error);
+void cv_add_weighted(const CvMatrix* const src1, double alpha,
+                     const CvMatrix* const src2, double beta, double gamma,
+                     CvMatrix* dst, CError* error);
+void cv_absdiff(const CvMatrix* const src1, const CvMatrix* const src2,
+                CvMatrix* dst, CError* error);
+void cv_scale_add(const CvMatrix* const src1, double alpha,
+                  const CvMatrix* const src2, CvMatrix* dst, CError* error);
+void cv_mat_convert_to(const CvMatrix* const src, CvMatrix* dst, int rtype,
+                       double alpha, double beta, CError* error);
+void cv_transpose(const CvMatrix* const src, CvMatrix* dst, CError* error);
+void cv_repeat(const CvMatrix* const src, int ny, int nx, CvMatrix* dst,
+               CError* error);
+void cv_hconcat(const CvMatrix* const* srcs, size_t nsrcs, CvMatrix* dst,
+                CError* error);
+void cv_vconcat(const CvMatrix* const* srcs, size_t nsrcs, CvMatrix* dst,
+                CError* error);
+void cv_rotate(const CvMatrix* const src, CvMatrix* dst, int code,
+               CError* error);
+// Formats the matrix with `cv::format`. The returned string must be released
+// with `cv_string_drop`.
+char* cv_mat_format(const CvMatrix* const cmat, int fmt, CError* error);
+
+// =============================================================================
+//  Comparison
+// =============================================================================
+void cv_compare(const CvMatrix* const src1, const CvMatrix* const src2,
+                CvMatrix* dst, int cmpop, CError* error);
+void cv_min(const CvMatrix* const src1, const CvMatrix* const src2,
+            CvMatrix* dst, CError* error);
+void cv_max(const CvMatrix* const src1, const CvMatrix* const src2,
+            CvMatrix* dst, CError* error);
+// Returns true if every element is in range; otherwise `pos` is set to the
+// first element that isn't.
+bool cv_check_range(const CvMatrix* const src, Point2i* pos, double min_val,
+                    double max_val, CError* error);
+
+// =============================================================================
+//  Discrete transforms
+// =============================================================================
+void cv_dft(const CvMatrix* const src, CvMatrix* dst, int flags,
+            int nonzero_rows, CError* error);
+void cv_dct(const CvMatrix* const src, CvMatrix* dst, int flags,
+            CError* error);
+void cv_mul_spectrums(const CvMatrix* const src1, const CvMatrix* const src2,
+                      CvMatrix* dst, int flags, bool conj, CError* error);
+int cv_get_optimal_dft_size(int size);
+void cv_copy_make_border(const CvMatrix* const src, CvMatrix* dst, int top,
+                         int bottom, int left, int right, int border_type,
+                         Scalar value, CError* error);
+void cv_magnitude(const CvMatrix* const x, const CvMatrix* const y,
+                  CvMatrix* dst, CError* error);
+void cv_phase(const CvMatrix* const x, const CvMatrix* const y, CvMatrix* dst,
+              bool angle_in_degrees, CError* error);
+void cv_cart_to_polar(const CvMatrix* const x, const CvMatrix* const y,
+                      CvMatrix* magnitude, CvMatrix* angle,
+                      bool angle_in_degrees, CError* error);
+
+// =============================================================================
+//  Random numbers
+// =============================================================================
+typedef struct _CRng CRng;
+
+// The caller owns the returned CRng.
+CRng* cv_rng_new(uint64_t state, CError* error);
+void cv_rng_drop(CRng* crng);
+uint64_t cv_rng_state(const CRng* const crng);
+unsigned cv_rng_next(CRng* crng, CError* error);
+int cv_rng_uniform_int(CRng* crng, int a, int b, CError* error);
+double cv_rng_uniform_double(CRng* crng, double a, double b, CError* error);
+double cv_rng_gaussian(CRng* crng, double sigma, CError* error);
+void cv_rng_fill(CRng* crng, CvMatrix* cmat, int dist_type, Scalar a,
+                 Scalar b, bool saturate_range, CError* error);
+void cv_set_rng_seed(int seed);
+void cv_randu(CvMatrix* cmat, Scalar low, Scalar high, CError* error);
+void cv_randn(CvMatrix* cmat, Scalar mean, Scalar stddev, CError* error);
+// `crng` can be NULL to use the default generator of the thread.
+void cv_rand_shuffle(CvMatrix* cmat, double iter_factor, CRng* crng,
+                     CError* error);
+
+// =============================================================================
+//  Linalg
+// =============================================================================
+// `csrc3` can be NULL, it is then ignored whatever `beta` is.
+void cv_gemm(const CvMatrix* const src1, const CvMatrix* const src2,
+             double alpha, const CvMatrix* const csrc3, double beta,
+             CvMatrix* dst, int flags, CError* error);
+// Returns 0 if the matrix is singular, see `cv::invert`.
+double cv_invert(const CvMatrix* const src, CvMatrix* dst, int method,
+                 CError* error);
+// Returns false if the matrix is singular, see `cv::solve`.
+bool cv_solve(const CvMatrix* const src1, const CvMatrix* const src2,
+              CvMatrix* dst, int method, CError* error);
+void cv_svd_compute(const CvMatrix* const src, CvMatrix* w, CvMatrix* u,
+                    CvMatrix* vt, int flags, CError* error);
+void cv_eigen(const CvMatrix* const src, CvMatrix* eigenvalues,
+              CvMatrix* eigenvectors, CError* error);
+void cv_eigen_non_symmetric(const CvMatrix* const src, CvMatrix* eigenvalues,
+                            CvMatrix* eigenvectors, CError* error);
+double cv_determinant(const CvMatrix* const src, CError* error);
+Scalar cv_trace(const CvMatrix* const src, CError* error);
 
 // =============================================================================
 //  Imgproc
 // =============================================================================
 void cv_line(CvMatrix* cmat, Point2i pt1, Point2i pt2, Scalar color,
-             int thickness, int linetype, int shift);
+             int thickness, int linetype, int shift, CError* error);
 void cv_rectangle(CvMatrix* cmat, Rect crect, Scalar color, int thickness,
-                  int linetype);
+                  int linetype, CError* error);
 void cv_ellipse(CvMatrix* cmat, Point2i center, Size2i axes, double angle,
                 double start_angle, double end_angle, Scalar color,
-                int thickness, int linetype, int shift);
+                int thickness, int linetype, int shift, CError* error);
 
-void cv_cvt_color(CvMatrix* cmat, CvMatrix* output, int code);
-void cv_pyr_down(CvMatrix* cmat, CvMatrix* output);
+void cv_cvt_color(CvMatrix* cmat, CvMatrix* output, int code, CError* error);
+void cv_pyr_down(CvMatrix* cmat, CvMatrix* output, CError* error);
 void cv_resize(CvMatrix* from, CvMatrix* to, Size2i dsize, double fx, double fy,
-               int interpolation);
-void cv_calc_hist(const CvMatrix* const cimages, int nimages,
-                  const int* channels, CvMatrix* mask, CvMatrix* hist, int dims,
-                  const int* hist_size, const float** ranges);
-void cv_calc_back_project(const CvMatrix* images, int nimages,
-                          const int* channels, CvMatrix* hist,
-                          CvMatrix* back_project, const float** ranges);
+               int interpolation, CError* error);
+void cv_calc_hist(const CvMatrix* const* cimages, size_t nimages,
+                  const int* channels, const CvMatrix* const cmask,
+                  CvMatrix* chist, int dims, const int* hist_size,
+                  const float* ranges, CError* error);
+void cv_calc_hist_sparse(const CvMatrix* const* cimages, size_t nimages,
+                         const int* channels, const CvMatrix* const cmask,
+                         CSparseMat* chist, int dims, const int* hist_size,
+                         const float* ranges, CError* error);
+void cv_calc_back_project(const CvMatrix* const* cimages, size_t nimages,
+                          const int* channels, const CvMatrix* const chist,
+                          CvMatrix* cback_project, int dims,
+                          const float* ranges, double scale, CError* error);
+double cv_compare_hist(const CvMatrix* const chist1,
+                       const CvMatrix* const chist2, int method,
+                       CError* error);
+void cv_equalize_hist(const CvMatrix* const csrc, CvMatrix* cdst,
+                      CError* error);
+
+typedef struct _CCLAHE CCLAHE;
+CCLAHE* cv_clahe_new(double clip_limit, Size2i tile_grid_size,
+                     CError* error);
+void cv_clahe_drop(CCLAHE* cclahe);
+void cv_clahe_apply(CCLAHE* cclahe, const CvMatrix* const csrc, CvMatrix* cdst,
+                    CError* error);
+
+RotatedRect cv_min_area_rect(const Point2f* points, size_t npoints,
+                             CError* error);
+RotatedRect cv_fit_ellipse(const Point2f* points, size_t npoints,
+                           CError* error);
+// `points` must hold 4 elements.
+void cv_box_points(RotatedRect rect, Point2f* points, CError* error);
+int cv_rotated_rectangle_intersection(RotatedRect rect1, RotatedRect rect2,
+                                      VecPoint2f* region, CError* error);
 
 // =============================================================================
 //  Imgcodecs
 // =============================================================================
-CvMatrix* cv_imdecode(const uint8_t* const buffer, size_t len, int flag);
+CvMatrix* cv_imdecode(const uint8_t* const buffer, size_t len, int flag,
+                      CError* error);
 ImencodeResult cv_imencode(const char* const ext, const CvMatrix* const cmat,
-                           const int* const flag_ptr, size_t flag_size);
+                           const int* const flag_ptr, size_t flag_size,
+                           CError* error);
+void cv_imencode_result_drop(ImencodeResult* result);
 
 // =============================================================================
 //   Highgui: high-level GUI
 // =============================================================================
-void cv_named_window(const char* const winname, int flags);
-void cv_destroy_window(const char* const winname);
-void cv_imshow(const char* const winname, CvMatrix* mat);
-int cv_wait_key(int delay_in_millis);
+void cv_named_window(const char* const winname, int flags, CError* error);
+void cv_destroy_window(const char* const winname, CError* error);
+void cv_imshow(const char* const winname, CvMatrix* mat, CError* error);
+int cv_wait_key(int delay_in_millis, CError* error);
 
 typedef void (*MouseCallback)(int e, int x, int y, int flags, void* data);
 void cv_set_mouse_callback(const char* const winname, MouseCallback onMouse,
-                           void* userdata);
+                           void* userdata, CError* error);
 
 // =============================================================================
 //   VideoIO
 // =============================================================================
 typedef struct _CVideoCapture CVideoCapture;
 
-CVideoCapture* cv_videocapture_new(int index);
-CVideoCapture* cv_videocapture_from_file(const char* const filename);
+CVideoCapture* cv_videocapture_new(int index, CError* error);
+CVideoCapture* cv_videocapture_from_file(const char* const filename,
+                                         CError* error);
 bool cv_videocapture_is_opened(const CVideoCapture* const ccap);
-bool cv_videocapture_read(CVideoCapture* ccap, CvMatrix* cmat);
+bool cv_videocapture_read(CVideoCapture* ccap, CvMatrix* cmat,
+                          CError* error);
 void cv_videocapture_drop(CVideoCapture* ccap);
-bool cv_videocapture_set(CVideoCapture* ccap, int property, double value);
-double cv_videocapture_get(CVideoCapture* ccap, int property);
+bool cv_videocapture_set(CVideoCapture* ccap, int property, double value,
+                         CError* error);
+double cv_videocapture_get(CVideoCapture* ccap, int property, CError* error);
 
 typedef struct _CVideoWriter CVideoWriter;
 
@@ -186,30 +427,35 @@ int cv_fourcc(char c1, char c2, char c3, char c4);
 
 CVideoWriter* cv_videowriter_default();
 CVideoWriter* cv_videowriter_new(const char* const path, int fourcc, double fps,
-                                 Size2i frame_size, bool is_color);
+                                 Size2i frame_size, bool is_color,
+                                 CError* error);
 void cv_videowriter_drop(CVideoWriter* writer);
 bool cv_videowriter_open(CVideoWriter* writer, const char* const path,
                          int fourcc, double fps, Size2i frame_size,
-                         bool is_color);
+                         bool is_color, CError* error);
 bool cv_videowriter_is_opened(CVideoWriter* writer);
-void cv_videowriter_write(CVideoWriter* writer, CvMatrix* cmat);
-bool cv_videowriter_set(CVideoWriter* writer, int property, double value);
-double cv_videowriter_get(CVideoWriter* writer, int property);
+void cv_videowriter_write(CVideoWriter* writer, CvMatrix* cmat,
+                          CError* error);
+bool cv_videowriter_set(CVideoWriter* writer, int property, double value,
+                        CError* error);
+double cv_videowriter_get(CVideoWriter* writer, int property, CError* error);
 
 // =============================================================================
 //   CascadeClassifier
 // =============================================================================
 typedef struct _CCascadeClassifier CCascadeClassifier;
 CCascadeClassifier* cv_cascade_classifier_new();
-CCascadeClassifier* cv_cascade_classifier_from_path(const char* const path);
-bool cv_cascade_classifier_load(CCascadeClassifier* cc, const char* const path);
+CCascadeClassifier* cv_cascade_classifier_from_path(const char* const path,
+                                                    CError* error);
+bool cv_cascade_classifier_load(CCascadeClassifier* cc, const char* const path,
+                                CError* error);
 void cv_cascade_classifier_drop(CCascadeClassifier* cc);
 
 // vec_of_rect is dynamically allocated, the caller should take ownership of it.
 void cv_cascade_classifier_detect(CCascadeClassifier* cc, CvMatrix* cmat,
                                   VecRect* vec_of_rect, double scale_factor,
                                   int min_neighbors, int flags, Size2i min_size,
-                                  Size2i max_size);
+                                  Size2i max_size, CError* error);
 
 typedef struct _SvmDetector SvmDetector;
 SvmDetector* cv_hog_default_people_detector();
@@ -219,10 +465,11 @@ void cv_hog_detector_drop(SvmDetector*);
 typedef struct _HogDescriptor HogDescriptor;
 HogDescriptor* cv_hog_new();
 void cv_hog_drop(HogDescriptor*);
-void cv_hog_set_svm_detector(HogDescriptor*, SvmDetector*);
+void cv_hog_set_svm_detector(HogDescriptor*, SvmDetector*, CError* error);
 void cv_hog_detect(HogDescriptor*, CvMatrix*, VecRect* vec_detected,
                    VecDouble* vec_weight, Size2i win_stride, Size2i padding,
-                   double scale, double final_threshold, bool use_means_shift);
+                   double scale, double final_threshold, bool use_means_shift,
+                   CError* error);
 
 // =============================================================================
 //   VideoTrack
@@ -231,7 +478,7 @@ typedef struct _CTermCriteria CTermCriteria;
 CTermCriteria* cv_term_criteria_new(int type, int count, double epsilon);
 void cv_term_criteria_drop(CTermCriteria* c_criteria);
 RotatedRect cv_camshift(CvMatrix* back_project_image, Rect window,
-                        CTermCriteria* term_criteria);
+                        CTermCriteria* term_criteria, CError* error);
 
 // =============================================================================
 //   MSER
@@ -245,9 +492,53 @@ CMSER* cv_mser_new(int delta,
                    int max_evolution,
                    double area_threshold,
                    double min_margin,
-                   int edge_blur_size);
+                   int edge_blur_size,
+                   CError* error);
 void cv_mser_drop(CMSER* cmser);
-void cv_mser_detect_regions(CMSER* cmser, CvMatrix* image, VecPoints* msers, VecRect* bboxes);
+void cv_mser_detect_regions(CMSER* cmser, CvMatrix* image, VecPoints* msers, VecRect* bboxes,
+                            CError* error);
+
+// =============================================================================
+//   Persistence
+// =============================================================================
+typedef struct _CFileStorage CFileStorage;
+typedef struct _CFileNode CFileNode;
+typedef struct _CFileNodeIter CFileNodeIter;
+
+CFileStorage* cv_file_storage_new(const char* source, int flags, CError* error);
+void cv_file_storage_drop(CFileStorage* cfs);
+bool cv_file_storage_is_opened(const CFileStorage* cfs);
+// Closes the storage. In memory mode the written content is returned, otherwise
+// an empty string; either way it must be released with `cv_string_drop`.
+char* cv_file_storage_release(CFileStorage* cfs, CError* error);
+void cv_file_storage_start_struct(CFileStorage* cfs, const char* name, bool seq, bool flow,
+                                  CError* error);
+void cv_file_storage_end_struct(CFileStorage* cfs, CError* error);
+void cv_file_storage_write_int(CFileStorage* cfs, const char* name, int value, CError* error);
+void cv_file_storage_write_real(CFileStorage* cfs, const char* name, double value,
+                                CError* error);
+void cv_file_storage_write_string(CFileStorage* cfs, const char* name, const char* value,
+                                  CError* error);
+void cv_file_storage_write_mat(CFileStorage* cfs, const char* name, const CvMatrix* cmat,
+                               CError* error);
+CFileNode* cv_file_storage_root(const CFileStorage* cfs);
+
+void cv_file_node_drop(CFileNode* cnode);
+int cv_file_node_type(const CFileNode* cnode);
+size_t cv_file_node_size(const CFileNode* cnode);
+char* cv_file_node_name(const CFileNode* cnode, CError* error);
+CFileNode* cv_file_node_get(const CFileNode* cnode, const char* key, CError* error);
+CFileNode* cv_file_node_child(const CFileNode* cnode, size_t index, CError* error);
+int cv_file_node_int(const CFileNode* cnode, CError* error);
+double cv_file_node_real(const CFileNode* cnode, CError* error);
+char* cv_file_node_string(const CFileNode* cnode, CError* error);
+void cv_file_node_mat(const CFileNode* cnode, CvMatrix* dst, CError* error);
+
+// The iterator only refers to the storage, so it may outlive `cnode`.
+CFileNodeIter* cv_file_node_iter_new(const CFileNode* cnode);
+void cv_file_node_iter_drop(CFileNodeIter* citer);
+// Returns the current element and advances the iterator.
+CFileNode* cv_file_node_iter_next(CFileNodeIter* citer, CError* error);
 
 EXTERN_C_END
 
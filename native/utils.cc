@@ -1,5 +1,8 @@
 #include "utils.h"
 
+#include <cstdlib>
+#include <cstring>
+
 void vec_rect_cxx_to_c(const std::vector<cv::Rect>& cxx_vec_rect, VecRect* vr) {
     size_t num = cxx_vec_rect.size();
     vr->size = num;
@@ -40,3 +43,89 @@ void vec_points_cxx_to_c(const std::vector<std::vector<cv::Point>> &cxx_vec_poin
         vec_point_cxx_to_c(cxx_vec_points[i], &vps->array[i]);
     }
 }
+
+void vec_point2f_cxx_to_c(const std::vector<cv::Point2f>& cxx_vec_point,
+                          VecPoint2f* vp) {
+    size_t num = cxx_vec_point.size();
+    vp->size = num;
+    vp->array = (Point2f*) malloc(num * sizeof(Point2f));
+    for (size_t i = 0; i < num; i++) {
+        vp->array[i].x = cxx_vec_point[i].x;
+        vp->array[i].y = cxx_vec_point[i].y;
+    }
+}
+
+std::vector<cv::Point2f> vec_point2f_c_to_cxx(const Point2f* points, size_t n) {
+    std::vector<cv::Point2f> result;
+    for (size_t i = 0; i < n; i++) {
+        result.push_back(cv::Point2f(points[i].x, points[i].y));
+    }
+    return result;
+}
+
+RotatedRect rotated_rect_cxx_to_c(const cv::RotatedRect& rr) {
+    RotatedRect c_rr;
+    c_rr.center.x = rr.center.x;
+    c_rr.center.y = rr.center.y;
+    c_rr.size.width = rr.size.width;
+    c_rr.size.height = rr.size.height;
+    c_rr.angle = rr.angle;
+    return c_rr;
+}
+
+cv::RotatedRect rotated_rect_c_to_cxx(const RotatedRect& rr) {
+    return cv::RotatedRect(cv::Point2f(rr.center.x, rr.center.y),
+                           cv::Size2f(rr.size.width, rr.size.height), rr.angle);
+}
+
+Scalar scalar_cxx_to_c(const cv::Scalar& s) {
+    Scalar result;
+    result.v0 = s[0];
+    result.v1 = s[1];
+    result.v2 = s[2];
+    result.v3 = s[3];
+    return result;
+}
+
+cv::Scalar scalar_c_to_cxx(const Scalar& s) {
+    return cv::Scalar(s.v0, s.v1, s.v2, s.v3);
+}
+
+std::vector<cv::Mat> mat_vector(const CvMatrix* const* cmats, size_t n) {
+    std::vector<cv::Mat> mats;
+    for (size_t i = 0; i < n; i++) {
+        mats.push_back(*reinterpret_cast<const cv::Mat*>(cmats[i]));
+    }
+    return mats;
+}
+
+std::vector<const float*> hist_ranges(const float* ranges, int dims) {
+    std::vector<const float*> result;
+    for (int i = 0; i < dims; i++) {
+        result.push_back(ranges + 2 * i);
+    }
+    return result;
+}
+
+static char* copy_string(const char* s) {
+    size_t len = ::strlen(s);
+    char* copy = (char*) malloc(len + 1);
+    ::memcpy(copy, s, len + 1);
+    return copy;
+}
+
+char* string_cxx_to_c(const std::string& s) {
+    return copy_string(s.c_str());
+}
+
+void cv_error_set(CError* error, int code, const char* func, const char* file,
+                  int line, const char* msg) {
+    if (error == nullptr) {
+        return;
+    }
+    error->code = code;
+    error->line = line;
+    error->func = copy_string(func);
+    error->file = copy_string(file);
+    error->msg = copy_string(msg);
+}
@@ -13,5 +13,48 @@ void vec_rect_cxx_to_c(const std::vector<cv::Rect>& cxx_vec_rect, VecRect* vr);
 void vec_double_cxx_to_c(const std::vector<double>& cxx_vec, VecDouble* v);
 void vec_point_cxx_to_c(const std::vector<cv::Point>& cxx_vec_point, VecPoint* vp);
 void vec_points_cxx_to_c(const std::vector<std::vector<cv::Point>> &cxx_vec_points, VecPoints* vps);
+void vec_point2f_cxx_to_c(const std::vector<cv::Point2f>& cxx_vec_point, VecPoint2f* vp);
+std::vector<cv::Point2f> vec_point2f_c_to_cxx(const Point2f* points, size_t n);
+
+RotatedRect rotated_rect_cxx_to_c(const cv::RotatedRect& rr);
+cv::RotatedRect rotated_rect_c_to_cxx(const RotatedRect& rr);
+
+Scalar scalar_cxx_to_c(const cv::Scalar& s);
+cv::Scalar scalar_c_to_cxx(const Scalar& s);
+
+// Copies `s` into a string allocated with `malloc`, to be released with
+// `cv_string_drop`.
+char* string_cxx_to_c(const std::string& s);
+
+// Copies the headers of `n` matrices into a vector; the data is shared.
+std::vector<cv::Mat> mat_vector(const CvMatrix* const* cmats, size_t n);
+
+// Turns `dims` consecutive `[lower, upper)` pairs into the per-dimension range
+// pointers taken by `cv::calcHist`.
+std::vector<const float*> hist_ranges(const float* ranges, int dims);
+
+// =============================================================================
+//   Error handling
+// =============================================================================
+
+void cv_error_set(CError* error, int code, const char* func, const char* file,
+                  int line, const char* msg);
+
+// Runs `f` and records any C++ exception it throws into `error`, so that no
+// exception ever unwinds through the C interface.
+template <typename F>
+void cv_try(CError* error, F f) {
+    try {
+        f();
+    } catch (const cv::Exception& e) {
+        cv_error_set(error, e.code, e.func.c_str(), e.file.c_str(), e.line,
+                     e.err.c_str());
+    } catch (const std::exception& e) {
+        cv_error_set(error, cv::Error::StsError, "", "", 0, e.what());
+    } catch (...) {
+        cv_error_set(error, cv::Error::StsError, "", "", 0,
+                     "unknown C++ exception");
+    }
+}
 
 #endif  // UTILS_H_
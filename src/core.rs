@@ -5,16 +5,26 @@ use errors::*;
 use failure::Error as Error;
 use std::os::raw::{c_char, c_double, c_int, c_uchar, c_void};
 use num;
-use std::ffi::CString;
+use std::any::Any;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Add, Deref, Div, Index, IndexMut, Mul, Neg, Sub};
+use std::ptr;
 use std::slice;
+use std::sync::Arc;
+
+pub mod linalg;
 
 /// Opaque data struct for C bindings
 #[derive(Clone, Copy, Debug)]
 pub enum CMat {}
 unsafe impl Send for CMat {}
 impl CMat {
-    pub fn new() -> *mut CMat {
+    pub(crate) fn new() -> *mut CMat {
         unsafe { cv_mat_new() }
     }
 }
@@ -38,25 +48,114 @@ pub struct Mat {
 
     /// Channels of this mat
     pub channels: i32,
+
+    /// Rust memory adopted by [from_vec](struct.Mat.html#method.from_vec),
+    /// released after the C++ object is destroyed. It is shared by the
+    /// headers created with [share](struct.Mat.html#method.share).
+    buffer: Option<Arc<OwnedBuffer>>,
 }
 
 // TODO(benzh): Should consider Unique<T>,
 // https://github.com/rust-lang/rust/issues/27730
 unsafe impl Send for Mat {}
 
-/// A 4-element struct that is widely used to pass pixel values.
-#[derive(Default, Debug, Clone, Copy)]
+/// Type-erased `Vec<T>` that backs the data of a `Mat`.
+struct OwnedBuffer {
+    _data: Box<dyn Any + Send>,
+}
+
+impl fmt::Debug for OwnedBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OwnedBuffer")
+    }
+}
+
+/// A read-only `Mat` that borrows its data, either from a Rust slice (see
+/// [Mat::from_slice](struct.Mat.html#method.from_slice)) or from another
+/// matrix (see [Mat::roi](struct.Mat.html#method.roi)). The borrow checker
+/// makes sure the data outlives the matrix and isn't written meanwhile.
+#[derive(Debug)]
+pub struct MatRef<'a> {
+    mat: Mat,
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Deref for MatRef<'a> {
+    type Target = Mat;
+
+    fn deref(&self) -> &Mat {
+        &self.mat
+    }
+}
+
+/// A `Mat` that mutably borrows its data from another matrix, see
+/// [Mat::roi_mut](struct.Mat.html#method.roi_mut). It derefs to `Mat` for
+/// reading; writes go through the methods below, so that the header can't be
+/// moved out of the borrow.
+#[derive(Debug)]
+pub struct MatMut<'a> {
+    mat: Mat,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> Deref for MatMut<'a> {
+    type Target = Mat;
+
+    fn deref(&self) -> &Mat {
+        &self.mat
+    }
+}
+
+impl<'a> MatMut<'a> {
+    /// See [Mat::as_mut_slice](struct.Mat.html#method.as_mut_slice).
+    pub fn as_mut_slice<T: DataType>(&mut self) -> Result<&mut [T], Error> {
+        self.mat.as_mut_slice::<T>()
+    }
+
+    /// See [Mat::row_mut](struct.Mat.html#method.row_mut).
+    pub fn row_mut<T: DataType>(&mut self, i: usize) -> Result<&mut [T], Error> {
+        self.mat.row_mut::<T>(i)
+    }
+
+    /// See [Mat::rows_iter_mut](struct.Mat.html#method.rows_iter_mut).
+    pub fn rows_iter_mut<T: DataType>(&mut self) -> Result<RowsMut<'_, T>, Error> {
+        self.mat.rows_iter_mut::<T>()
+    }
+
+    /// See [Mat::pixels_mut](struct.Mat.html#method.pixels_mut).
+    pub fn pixels_mut<T: DataType>(&mut self) -> Result<PixelsMut<'_, T>, Error> {
+        self.mat.pixels_mut::<T>()
+    }
+
+    /// See [Mat::par_rows_mut](struct.Mat.html#method.par_rows_mut).
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut<'b, T: DataType + Send + 'b>(
+        &'b mut self,
+    ) -> Result<impl ::rayon::iter::IndexedParallelIterator<Item = &'b mut [T]>, Error> {
+        self.mat.par_rows_mut::<T>()
+    }
+
+    /// See [Mat::set_to](struct.Mat.html#method.set_to).
+    pub fn set_to<S: Into<Scalar>>(&mut self, value: S, mask: Option<&Mat>) -> Result<(), Error> {
+        self.mat.set_to(value, mask)
+    }
+}
+
+/// A 4-element struct that is widely used to pass pixel values, like
+/// `cv::Scalar`.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub struct Scalar {
-    v0: i32,
-    v1: i32,
-    v2: i32,
-    v3: i32,
+    v0: f64,
+    v1: f64,
+    v2: f64,
+    v3: f64,
 }
 
 impl Scalar {
     /// Creates a new scalar object.
-    pub fn new(v0: i32, v1: i32, v2: i32, v3: i32) -> Self {
+    pub fn new(v0: f64, v1: f64, v2: f64, v3: f64) -> Self {
         Scalar {
             v0: v0,
             v1: v1,
@@ -66,7 +165,7 @@ impl Scalar {
     }
 
     /// Creates a new scalar object with all value being the same.
-    pub fn all(v: i32) -> Self {
+    pub fn all(v: f64) -> Self {
         Scalar {
             v0: v,
             v1: v,
@@ -76,101 +175,456 @@ impl Scalar {
     }
 }
 
-/// 2D integer points specified by its coordinates `x` and `y`.
-#[derive(Default, Debug, Clone, Copy)]
+impl Index<usize> for Scalar {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        match i {
+            0 => &self.v0,
+            1 => &self.v1,
+            2 => &self.v2,
+            3 => &self.v3,
+            _ => panic!("index {} is out of range for Scalar", i),
+        }
+    }
+}
+
+impl IndexMut<usize> for Scalar {
+    fn index_mut(&mut self, i: usize) -> &mut f64 {
+        match i {
+            0 => &mut self.v0,
+            1 => &mut self.v1,
+            2 => &mut self.v2,
+            3 => &mut self.v3,
+            _ => panic!("index {} is out of range for Scalar", i),
+        }
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for Scalar {
+    fn from(v: (f64, f64, f64, f64)) -> Self {
+        Scalar::new(v.0, v.1, v.2, v.3)
+    }
+}
+
+impl From<(f64, f64, f64)> for Scalar {
+    fn from(v: (f64, f64, f64)) -> Self {
+        Scalar::new(v.0, v.1, v.2, 0.0)
+    }
+}
+
+impl From<[f64; 4]> for Scalar {
+    fn from(v: [f64; 4]) -> Self {
+        Scalar::new(v[0], v[1], v[2], v[3])
+    }
+}
+
+impl From<[f64; 3]> for Scalar {
+    fn from(v: [f64; 3]) -> Self {
+        Scalar::new(v[0], v[1], v[2], 0.0)
+    }
+}
+
+impl From<f64> for Scalar {
+    /// Same as `cv::Scalar(v)`: the other components are zero.
+    fn from(v: f64) -> Self {
+        Scalar::new(v, 0.0, 0.0, 0.0)
+    }
+}
+
+impl From<Scalar> for [f64; 4] {
+    fn from(s: Scalar) -> Self {
+        [s.v0, s.v1, s.v2, s.v3]
+    }
+}
+
+/// An opaque color for the drawing functions. OpenCV stores color images
+/// as BGR(A), so the channels are kept in that order whichever constructor
+/// is used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    scalar: Scalar,
+}
+
+impl Color {
+    /// Creates a color from its blue, green and red components.
+    pub fn bgr(b: u8, g: u8, r: u8) -> Self {
+        Color::bgra(b, g, r, 255)
+    }
+
+    /// Creates a color from its red, green and blue components.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color::bgra(b, g, r, 255)
+    }
+
+    /// Creates a color from its blue, green, red and alpha components.
+    pub fn bgra(b: u8, g: u8, r: u8, a: u8) -> Self {
+        Color {
+            scalar: Scalar::new(b as f64, g as f64, r as f64, a as f64),
+        }
+    }
+
+    /// Creates a color from its red, green, blue and alpha components.
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color::bgra(b, g, r, a)
+    }
+
+    /// Creates a gray level, for single-channel images.
+    pub fn gray(v: u8) -> Self {
+        Color::bgr(v, v, v)
+    }
+
+    /// Black
+    pub fn black() -> Self {
+        Color::bgr(0, 0, 0)
+    }
+
+    /// White
+    pub fn white() -> Self {
+        Color::bgr(255, 255, 255)
+    }
+
+    /// Blue
+    pub fn blue() -> Self {
+        Color::bgr(255, 0, 0)
+    }
+
+    /// Green
+    pub fn green() -> Self {
+        Color::bgr(0, 255, 0)
+    }
+
+    /// Red
+    pub fn red() -> Self {
+        Color::bgr(0, 0, 255)
+    }
+
+    /// Cyan, the default color of the simple drawing functions
+    pub fn cyan() -> Self {
+        Color::bgr(255, 255, 0)
+    }
+
+    /// Magenta
+    pub fn magenta() -> Self {
+        Color::bgr(255, 0, 255)
+    }
+
+    /// Yellow
+    pub fn yellow() -> Self {
+        Color::bgr(0, 255, 255)
+    }
+}
+
+impl From<Color> for Scalar {
+    fn from(c: Color) -> Self {
+        c.scalar
+    }
+}
+
+/// 2D points specified by its coordinates `x` and `y`, like `cv::Point_`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
-pub struct Point2i {
+pub struct Point2<T> {
     /// x coordinate
-    pub x: i32,
+    pub x: T,
 
     /// y coordinate
-    pub y: i32,
+    pub y: T,
+}
+
+/// 2D integer points.
+pub type Point2i = Point2<i32>;
+
+/// 2D floating points.
+pub type Point2f = Point2<f32>;
+
+/// 2D double precision floating points.
+pub type Point2d = Point2<f64>;
+
+impl<T> Point2<T> {
+    /// Creates a new `Point2`.
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x: x, y: y }
+    }
 }
 
-impl Point2i {
-    /// Creats a new `Point2i`.
-    pub fn new(x: i32, y: i32) -> Self {
-        Point2i { x: x, y: y }
+impl<T: num::Num + Copy> Point2<T> {
+    /// Dot product of the two points seen as vectors.
+    pub fn dot(&self, other: Point2<T>) -> T {
+        self.x * other.x + self.y * other.y
     }
 }
 
-/// 2D floating points specified by its coordinates `x` and `y`.
-#[derive(Default, Debug, Clone, Copy)]
+impl<T: num::NumCast + Copy> Point2<T> {
+    /// Converts the coordinates to another type, see
+    /// [NumCast](https://docs.rs/num/0.1/num/trait.NumCast.html). Floats are
+    /// truncated, and `None` is returned if a coordinate doesn't fit.
+    pub fn cast<U: num::NumCast>(&self) -> Option<Point2<U>> {
+        Some(Point2::new(U::from(self.x)?, U::from(self.y)?))
+    }
+}
+
+/// 3D points specified by its coordinates `x`, `y` and `z`, like
+/// `cv::Point3_`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
-pub struct Point2f {
+pub struct Point3<T> {
     /// x coordinate
-    pub x: f32,
+    pub x: T,
 
     /// y coordinate
-    pub y: f32,
+    pub y: T,
+
+    /// z coordinate
+    pub z: T,
+}
+
+/// 3D integer points.
+pub type Point3i = Point3<i32>;
+
+/// 3D floating points.
+pub type Point3f = Point3<f32>;
+
+/// 3D double precision floating points.
+pub type Point3d = Point3<f64>;
+
+impl<T> Point3<T> {
+    /// Creates a new `Point3`.
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x: x, y: y, z: z }
+    }
+}
+
+impl<T: num::Num + Copy> Point3<T> {
+    /// Dot product of the two points seen as vectors.
+    pub fn dot(&self, other: Point3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Cross product of the two points seen as vectors.
+    pub fn cross(&self, other: Point3<T>) -> Point3<T> {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
 }
 
-impl Point2f {
-    /// Creats a new `Point2f`.
-    pub fn new(x: f32, y: f32) -> Self {
-        Point2f { x: x, y: y }
+impl<T: num::NumCast + Copy> Point3<T> {
+    /// Converts the coordinates to another type, see
+    /// [Point2::cast](struct.Point2.html#method.cast).
+    pub fn cast<U: num::NumCast>(&self) -> Option<Point3<U>> {
+        Some(Point3::new(U::from(self.x)?, U::from(self.y)?, U::from(self.z)?))
     }
 }
 
-/// `Size2i` struct is used for specifying the size (`width` and `height` as
-/// `i32`) of an image or rectangle.
-#[derive(Default, Debug, Clone, Copy)]
+/// `Size2` struct is used for specifying the size (`width` and `height`) of
+/// an image or rectangle, like `cv::Size_`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
-pub struct Size2i {
+pub struct Size2<T> {
     /// width
-    pub width: i32,
+    pub width: T,
 
     /// height
-    pub height: i32,
+    pub height: T,
 }
 
-impl Size2i {
-    /// Creates a new `Size2i` object with `width` and `height`
-    pub fn new(width: i32, height: i32) -> Self {
-        Size2i {
+/// Sizes in `i32`.
+pub type Size2i = Size2<i32>;
+
+/// Sizes in `f32`.
+pub type Size2f = Size2<f32>;
+
+/// Sizes in `f64`.
+pub type Size2d = Size2<f64>;
+
+impl<T> Size2<T> {
+    /// Creates a new `Size2` object with `width` and `height`
+    pub fn new(width: T, height: T) -> Self {
+        Size2 {
             width: width,
             height: height,
         }
     }
 }
 
-/// `Size2f` struct is used for specifying the size (`width` and `height` as
-/// `f32`) of an image or rectangle.
-#[derive(Default, Debug, Clone, Copy)]
-#[repr(C)]
-pub struct Size2f {
-    /// width
-    pub width: f32,
+impl<T: num::Num + Copy + PartialOrd> Size2<T> {
+    /// `width * height`
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
 
-    /// height
-    pub height: f32,
+    /// True if the width or the height is not positive.
+    pub fn empty(&self) -> bool {
+        self.width <= T::zero() || self.height <= T::zero()
+    }
 }
 
-/// The `Rect` defines a rectangle in integer.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+impl<T: num::NumCast + Copy> Size2<T> {
+    /// Converts the dimensions to another type, see
+    /// [Point2::cast](struct.Point2.html#method.cast).
+    pub fn cast<U: num::NumCast>(&self) -> Option<Size2<U>> {
+        Some(Size2::new(U::from(self.width)?, U::from(self.height)?))
+    }
+}
+
+/// The `Rect2` defines a rectangle by its left-top corner and its size, like
+/// `cv::Rect_`. The right and bottom edges are excluded.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
-pub struct Rect {
+pub struct Rect2<T> {
     /// x coordinate of the left-top corner
-    pub x: i32,
+    pub x: T,
     /// y coordinate of the left-top corner
-    pub y: i32,
+    pub y: T,
     /// width of this rectangle
-    pub width: i32,
+    pub width: T,
     /// height of this rectangle
-    pub height: i32,
+    pub height: T,
 }
 
-impl Rect {
-    /// Creates a new `Rect` with (x, y, width, height) parameters.
-    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
-        Rect {
+/// Rectangles in integer.
+pub type Rect = Rect2<i32>;
+
+/// Rectangles in integer, same as `Rect`.
+pub type Rect2i = Rect2<i32>;
+
+/// Rectangles in float.
+pub type Rect2f = Rect2<f32>;
+
+/// Rectangles in double precision float.
+pub type Rect2d = Rect2<f64>;
+
+impl<T> Rect2<T> {
+    /// Creates a new `Rect2` with (x, y, width, height) parameters.
+    pub fn new(x: T, y: T, width: T, height: T) -> Self {
+        Rect2 {
             x: x,
             y: y,
             width: width,
             height: height,
         }
     }
+}
+
+impl<T: num::Num + Copy + PartialOrd> Rect2<T> {
+    /// Creates the rectangle spanning from `tl` (included) to `br`
+    /// (excluded).
+    pub fn from_points(tl: Point2<T>, br: Point2<T>) -> Self {
+        Rect2::new(tl.x, tl.y, br.x - tl.x, br.y - tl.y)
+    }
+
+    /// The left-top corner.
+    pub fn tl(&self) -> Point2<T> {
+        Point2::new(self.x, self.y)
+    }
+
+    /// The bottom-right corner, just outside of the rectangle.
+    pub fn br(&self) -> Point2<T> {
+        Point2::new(self.x + self.width, self.y + self.height)
+    }
+
+    /// The center of the rectangle, rounded towards the left-top corner for
+    /// integers.
+    pub fn center(&self) -> Point2<T> {
+        let two = T::one() + T::one();
+        Point2::new(self.x + self.width / two, self.y + self.height / two)
+    }
+
+    /// Size of the rectangle.
+    pub fn size(&self) -> Size2<T> {
+        Size2::new(self.width, self.height)
+    }
+
+    /// `width * height`
+    pub fn area(&self) -> T {
+        self.width * self.height
+    }
+
+    /// True if the width or the height is not positive.
+    pub fn empty(&self) -> bool {
+        self.width <= T::zero() || self.height <= T::zero()
+    }
+
+    /// Checks whether the point is inside the rectangle.
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        self.x <= p.x && p.x < self.x + self.width && self.y <= p.y && p.y < self.y + self.height
+    }
+
+    /// The overlapping part of both rectangles, or an empty rectangle at the
+    /// origin if they don't overlap.
+    pub fn intersection(&self, other: &Rect2<T>) -> Rect2<T> {
+        let tl = Point2::new(max(self.x, other.x), max(self.y, other.y));
+        let br = Point2::new(
+            min(self.x + self.width, other.x + other.width),
+            min(self.y + self.height, other.y + other.height),
+        );
+        let rect = Rect2::from_points(tl, br);
+        if rect.empty() {
+            Rect2::new(T::zero(), T::zero(), T::zero(), T::zero())
+        } else {
+            rect
+        }
+    }
+
+    /// The smallest rectangle containing both rectangles. Empty rectangles
+    /// are ignored.
+    pub fn union(&self, other: &Rect2<T>) -> Rect2<T> {
+        if self.empty() {
+            return *other;
+        }
+        if other.empty() {
+            return *self;
+        }
+        let tl = Point2::new(min(self.x, other.x), min(self.y, other.y));
+        let br = Point2::new(
+            max(self.x + self.width, other.x + other.width),
+            max(self.y + self.height, other.y + other.height),
+        );
+        Rect2::from_points(tl, br)
+    }
+
+    /// Restricts the rectangle to an image of the given size.
+    pub fn clamp_to(&self, size: Size2<T>) -> Rect2<T> {
+        self.intersection(&Rect2::new(T::zero(), T::zero(), size.width, size.height))
+    }
+}
+
+impl<T: num::Num + num::NumCast + Copy + PartialOrd> Rect2<T> {
+    /// Intersection over union of the two rectangles, between 0 (disjoint)
+    /// and 1 (identical).
+    pub fn iou(&self, other: &Rect2<T>) -> f64 {
+        let to_f64 = |v: T| <f64 as num::NumCast>::from(v).unwrap_or(0.0);
+        let inter = to_f64(self.intersection(other).area());
+        let union = to_f64(self.area()) + to_f64(other.area()) - inter;
+        if union > 0.0 {
+            inter / union
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<T: num::NumCast + Copy> Rect2<T> {
+    /// Converts the coordinates to another type, see
+    /// [Point2::cast](struct.Point2.html#method.cast).
+    pub fn cast<U: num::NumCast>(&self) -> Option<Rect2<U>> {
+        Some(Rect2::new(
+            U::from(self.x)?,
+            U::from(self.y)?,
+            U::from(self.width)?,
+            U::from(self.height)?,
+        ))
+    }
+}
 
+impl Rect {
     /// Scales the rectangle by the specified ratio.
     pub fn scale(&self, ratio: f32) -> Rect {
         let new_x = ((1.0 - ratio) * (self.width as f32) / 2.0) as i32 + self.x;
@@ -197,15 +651,6 @@ impl Rect {
     }
 }
 
-/// The `Rect2f` are rectangles in float.
-#[derive(Default, Debug, Clone, Copy)]
-pub struct Rect2f {
-    pub x: f32,
-    pub y: f32,
-    pub width: f32,
-    pub height: f32,
-}
-
 impl Rect2f {
     /// Normalize the rectangle according to the image. This will restore the
     /// Rect in absolute pixel numbers.
@@ -219,9 +664,194 @@ impl Rect2f {
     }
 }
 
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+// Operators
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Point2<T>;
+
+    fn add(self, other: Point2<T>) -> Point2<T> {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Point2<T>;
+
+    fn sub(self, other: Point2<T>) -> Point2<T> {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Point2<T>;
+
+    fn mul(self, k: T) -> Point2<T> {
+        Point2::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Point2<T>;
+
+    fn neg(self) -> Point2<T> {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, k: T) -> Point3<T> {
+        Point3::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Point3<T>;
+
+    fn neg(self) -> Point3<T> {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Size2<T> {
+    type Output = Size2<T>;
+
+    fn add(self, other: Size2<T>) -> Size2<T> {
+        Size2::new(self.width + other.width, self.height + other.height)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Size2<T> {
+    type Output = Size2<T>;
+
+    fn sub(self, other: Size2<T>) -> Size2<T> {
+        Size2::new(self.width - other.width, self.height - other.height)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Size2<T> {
+    type Output = Size2<T>;
+
+    fn mul(self, k: T) -> Size2<T> {
+        Size2::new(self.width * k, self.height * k)
+    }
+}
+
+/// Shifts the rectangle.
+impl<T: Add<Output = T>> Add<Point2<T>> for Rect2<T> {
+    type Output = Rect2<T>;
+
+    fn add(self, p: Point2<T>) -> Rect2<T> {
+        Rect2::new(self.x + p.x, self.y + p.y, self.width, self.height)
+    }
+}
+
+/// Shifts the rectangle.
+impl<T: Sub<Output = T>> Sub<Point2<T>> for Rect2<T> {
+    type Output = Rect2<T>;
+
+    fn sub(self, p: Point2<T>) -> Rect2<T> {
+        Rect2::new(self.x - p.x, self.y - p.y, self.width, self.height)
+    }
+}
+
+/// Grows the rectangle, keeping its left-top corner.
+impl<T: Add<Output = T>> Add<Size2<T>> for Rect2<T> {
+    type Output = Rect2<T>;
+
+    fn add(self, s: Size2<T>) -> Rect2<T> {
+        Rect2::new(self.x, self.y, self.width + s.width, self.height + s.height)
+    }
+}
+
+/// Shrinks the rectangle, keeping its left-top corner.
+impl<T: Sub<Output = T>> Sub<Size2<T>> for Rect2<T> {
+    type Output = Rect2<T>;
+
+    fn sub(self, s: Size2<T>) -> Rect2<T> {
+        Rect2::new(self.x, self.y, self.width - s.width, self.height - s.height)
+    }
+}
+
+/// Scales the position and the size of the rectangle.
+impl<T: Mul<Output = T> + Copy> Mul<T> for Rect2<T> {
+    type Output = Rect2<T>;
+
+    fn mul(self, k: T) -> Rect2<T> {
+        Rect2::new(self.x * k, self.y * k, self.width * k, self.height * k)
+    }
+}
+
+// Lossless conversions
+
+macro_rules! impl_geometry_from {
+    ($($from:ty => $to:ty),*) => {
+        $(
+            impl From<Point2<$from>> for Point2<$to> {
+                fn from(p: Point2<$from>) -> Self {
+                    Point2::new(p.x.into(), p.y.into())
+                }
+            }
+
+            impl From<Point3<$from>> for Point3<$to> {
+                fn from(p: Point3<$from>) -> Self {
+                    Point3::new(p.x.into(), p.y.into(), p.z.into())
+                }
+            }
+
+            impl From<Size2<$from>> for Size2<$to> {
+                fn from(s: Size2<$from>) -> Self {
+                    Size2::new(s.width.into(), s.height.into())
+                }
+            }
+
+            impl From<Rect2<$from>> for Rect2<$to> {
+                fn from(r: Rect2<$from>) -> Self {
+                    Rect2::new(r.x.into(), r.y.into(), r.width.into(), r.height.into())
+                }
+            }
+        )*
+    }
+}
+
+impl_geometry_from!(i32 => f64, f32 => f64);
+
 #[repr(C)]
 #[derive(Debug, Clone)]
-pub struct CVec<T: Sized + NestedVec> {
+pub(crate) struct CVec<T: Sized + NestedVec> {
     array: *mut T,
     size: usize,
 }
@@ -236,7 +866,7 @@ where
         .collect()
 }
 
-pub trait Unpack {
+pub(crate) trait Unpack {
     type Out;
     fn unpack(&self) -> Self::Out;
 }
@@ -255,7 +885,7 @@ impl<T: Copy> Unpack for T {
     }
 }
 
-pub trait NestedVec {
+pub(crate) trait NestedVec {
     const LEVEL: u32;
 }
 
@@ -290,9 +920,108 @@ impl<T: NestedVec> Drop for CVec<T> {
     }
 }
 
-/// Line type
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub enum LineTypes {
+/// A C++ exception caught by the native wrappers. `msg` is null when no
+/// exception occurred; the strings are allocated by C and freed on drop.
+#[repr(C)]
+#[derive(Debug)]
+pub(crate) struct CError {
+    code: c_int,
+    line: c_int,
+    func: *mut c_char,
+    file: *mut c_char,
+    msg: *mut c_char,
+}
+
+impl Default for CError {
+    fn default() -> Self {
+        CError {
+            code: 0,
+            line: 0,
+            func: ::std::ptr::null_mut(),
+            file: ::std::ptr::null_mut(),
+            msg: ::std::ptr::null_mut(),
+        }
+    }
+}
+
+impl CError {
+    fn into_result(self) -> Result<(), Error> {
+        if self.msg.is_null() {
+            return Ok(());
+        }
+        let string = |s: *mut c_char| {
+            if s.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+            }
+        };
+        Err(CvError::OpenCvException {
+            code: self.code,
+            func: string(self.func),
+            file: string(self.file),
+            line: self.line,
+            message: string(self.msg),
+        }.into())
+    }
+}
+
+impl Drop for CError {
+    fn drop(&mut self) {
+        extern "C" {
+            fn cv_error_drop(error: *mut CError);
+        }
+        unsafe { cv_error_drop(self) }
+    }
+}
+
+/// Calls a native function taking a trailing `CError*` and turns any C++
+/// exception it reported into a `CvError::OpenCvException`.
+pub(crate) fn cv_try<T, F>(f: F) -> Result<T, Error>
+where
+    F: FnOnce(*mut CError) -> T,
+{
+    let mut error = CError::default();
+    let value = f(&mut error);
+    error.into_result().map(|_| value)
+}
+
+/// Takes ownership of a string allocated by the native wrapper.
+pub(crate) fn take_string(s: *mut c_char) -> String {
+    extern "C" {
+        fn cv_string_drop(s: *mut c_char);
+    }
+    let string = unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned();
+    unsafe { cv_string_drop(s) };
+    string
+}
+
+/// Like [cv_try](fn.cv_try.html), for native constructors returning a new
+/// `CMat`. The matrix is released if an exception occurred.
+pub(crate) fn cv_try_new_mat<F>(f: F) -> Result<Mat, Error>
+where
+    F: FnOnce(*mut CError) -> *mut CMat,
+{
+    let mut error = CError::default();
+    let mat = Mat::from_raw(f(&mut error));
+    error.into_result().map(|_| mat)
+}
+
+/// Like [cv_try](fn.cv_try.html), for native functions writing their result
+/// into an output `CMat`. The dimensions are read once the call returned.
+pub(crate) fn cv_try_mat<F>(f: F) -> Result<Mat, Error>
+where
+    F: FnOnce(*mut CMat, *mut CError),
+{
+    let m = CMat::new();
+    let result = cv_try(|e| f(m, e));
+    let mat = Mat::from_raw(m);
+    result.map(|_| mat)
+}
+
+/// Line type
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LineTypes {
     /// Default type
     Filled = -1,
 
@@ -308,9 +1037,18 @@ pub enum LineTypes {
 
 extern "C" {
     fn cv_mat_new() -> *mut CMat;
-    fn cv_mat_new_with_size(rows: c_int, cols: c_int, t: i32) -> *mut CMat;
-    fn cv_mat_zeros(rows: c_int, cols: c_int, t: i32) -> *mut CMat;
-    fn cv_mat_from_buffer(rows: c_int, cols: c_int, t: i32, buffer: *const c_uchar) -> *mut CMat;
+    fn cv_mat_new_with_size(rows: c_int, cols: c_int, t: i32, error: *mut CError) -> *mut CMat;
+    fn cv_mat_zeros(rows: c_int, cols: c_int, t: i32, error: *mut CError) -> *mut CMat;
+    fn cv_mat_zeros_nd(ndims: c_int, sizes: *const c_int, t: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_mat_ones(rows: c_int, cols: c_int, t: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_mat_eye(rows: c_int, cols: c_int, t: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_mat_from_buffer(
+        rows: c_int,
+        cols: c_int,
+        t: i32,
+        buffer: *const c_uchar,
+        error: *mut CError,
+    ) -> *mut CMat;
     fn cv_mat_is_valid(mat: *mut CMat) -> bool;
     fn cv_mat_rows(cmat: *const CMat) -> c_int;
     fn cv_mat_cols(cmat: *const CMat) -> c_int;
@@ -319,12 +1057,22 @@ extern "C" {
     fn cv_mat_data(cmat: *const CMat) -> *const c_uchar;
     fn cv_mat_total(cmat: *const CMat) -> usize;
     fn cv_mat_step1(cmat: *const CMat, i: c_int) -> usize;
+    fn cv_mat_dims(cmat: *const CMat) -> c_int;
+    fn cv_mat_size_nd(cmat: *const CMat, i: c_int) -> c_int;
     fn cv_mat_elem_size(cmat: *const CMat) -> usize;
     fn cv_mat_elem_size1(cmat: *const CMat) -> usize;
     fn cv_mat_type(cmat: *const CMat) -> c_int;
-    fn cv_mat_roi(cmat: *const CMat, rect: Rect) -> *mut CMat;
-    fn cv_mat_logic_and(cimage: *mut CMat, cmask: *const CMat);
-    fn cv_mat_flip(src: *mut CMat, code: c_int);
+    fn cv_mat_roi(cmat: *const CMat, rect: Rect, error: *mut CError) -> *mut CMat;
+    fn cv_mat_logic_and(cimage: *mut CMat, cmask: *const CMat, error: *mut CError);
+    fn cv_mat_flip(src: *mut CMat, code: c_int, error: *mut CError);
+    fn cv_mat_clone(cmat: *const CMat, error: *mut CError) -> *mut CMat;
+    fn cv_mat_share(cmat: *const CMat) -> *mut CMat;
+    fn cv_mat_reshape(cmat: *const CMat, cn: c_int, rows: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_mat_is_refcounted(cmat: *const CMat) -> bool;
+    fn cv_mat_is_continuous(cmat: *const CMat) -> bool;
+    fn cv_mat_eq(a: *const CMat, b: *const CMat, error: *mut CError) -> bool;
+    fn cv_mat_copy_to(src: *const CMat, dst: *mut CMat, mask: *const CMat, error: *mut CError);
+    fn cv_mat_set_to(cmat: *mut CMat, value: Scalar, mask: *const CMat, error: *mut CError);
     fn cv_mat_drop(mat: *mut CMat);
 }
 
@@ -340,6 +1088,42 @@ pub enum FlipCode {
     XYAxis,
 }
 
+/// A flag to specify how to rotate the image, see
+/// [Mat::rotate](struct.Mat.html#method.rotate)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RotateFlags {
+    /// Rotate 90 degrees clockwise
+    Rotate90Clockwise = 0,
+    /// Rotate 180 degrees
+    Rotate180 = 1,
+    /// Rotate 270 degrees clockwise
+    Rotate90CounterClockwise = 2,
+}
+
+/// Checks that `len` elements of `T` are exactly what a `rows` x `cols` matrix
+/// of `cv_type` holds.
+fn check_buffer<T: DataType>(rows: i32, cols: i32, cv_type: CvType, len: usize) -> Result<(), Error> {
+    if T::DEPTH != cv_type.depth() || (T::CHANNELS != 1 && T::CHANNELS != cv_type.channels()) {
+        return Err(CvError::ElementTypeMismatch {
+            mat_depth: cv_type.depth(),
+            mat_channels: cv_type.channels(),
+            depth: T::DEPTH,
+            channels: T::CHANNELS,
+        }.into());
+    }
+    if rows < 0 || cols < 0 {
+        return Err(CvError::InvalidSize { rows: rows, cols: cols }.into());
+    }
+    let expected = (rows as usize) * (cols as usize) * ((cv_type.channels() / T::CHANNELS) as usize);
+    if len != expected {
+        return Err(CvError::BufferSizeMismatch {
+            expected: expected,
+            actual: len,
+        }.into());
+    }
+    Ok(())
+}
+
 impl Mat {
     #[inline]
     /// Creates a `Mat` object from raw `CMat` pointer. This will read the rows
@@ -351,6 +1135,7 @@ impl Mat {
             cols: unsafe { cv_mat_cols(raw) },
             depth: unsafe { cv_mat_depth(raw) },
             channels: unsafe { cv_mat_channels(raw) },
+            buffer: None,
         }
     }
 
@@ -360,46 +1145,111 @@ impl Mat {
         Mat::from_raw(m)
     }
 
-    /// Creates a new `Mat` from buffer. Note that internally opencv function
-    /// won't take ownership of the Mat, but when we call `drop`, it will
-    /// deallocate the memory. To prevent double-freeing, you must `mem::forget`
-    /// it after use.
+    /// Creates a new `Mat` from buffer. Note that OpenCV won't take ownership
+    /// of the buffer and nothing ties the lifetime of the returned `Mat` to
+    /// it, so the `Mat` must not be used after the buffer is gone.
+    #[deprecated(note = "use Mat::from_slice_copy, Mat::from_slice or Mat::from_vec instead")]
+    pub fn from_buffer(rows: i32, cols: i32, cv_type: i32, buf: &Vec<u8>) -> Result<Mat, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_from_buffer(rows, cols, cv_type, buf.as_ptr(), e) })
+    }
+
+    /// Creates a new `Mat` holding a copy of `data`.
     ///
-    /// The following example shows how to get the data from an image and create
-    /// a new image with the data (also forgets it).
+    /// `T` follows the same rules as in
+    /// [as_slice](struct.Mat.html#method.as_slice), and `data` must hold
+    /// exactly `rows * cols` elements of `cv_type`.
     ///
     /// ```rust,ignore
-    /// let buffer = image.data();
-    /// let size = image.size();
-    /// let s = (size.width * size.height * 3) as usize;
-    ///
-    /// let mut vec = Vec::with_capacity(s);
-    /// unsafe {
-    ///   vec.set_len(s);
-    ///   copy(buffer, vec.as_mut_ptr(), s);
-    /// }
-    /// let new_image = Mat::from_buffer(
-    ///   size.height, size.width, CvType::Cv8UC3 as i32, &vec);
+    /// let pixels = vec![0u8; 640 * 480 * 3];
+    /// let image = Mat::from_slice_copy(480, 640, CvType::Cv8UC3, &pixels)?;
+    /// ```
+    pub fn from_slice_copy<T: DataType>(rows: i32, cols: i32, cv_type: CvType, data: &[T]) -> Result<Mat, Error> {
+        check_buffer::<T>(rows, cols, cv_type, data.len())?;
+        let mut mat = Mat::with_size(rows, cols, cv_type as i32)?;
+        mat.as_mut_slice::<T>()?.copy_from_slice(data);
+        Ok(mat)
+    }
+
+    /// Creates a new `Mat` that uses `data` in place, without copying it. The
+    /// returned [MatRef](struct.MatRef.html) can't outlive `data`.
     ///
-    ///  // . . . use new_image here, such as new_image.show(..) . . .
+    /// See [from_slice_copy](struct.Mat.html#method.from_slice_copy) for the
+    /// requirements on `data`.
+    pub fn from_slice<'a, T: DataType>(
+        rows: i32,
+        cols: i32,
+        cv_type: CvType,
+        data: &'a [T],
+    ) -> Result<MatRef<'a>, Error> {
+        check_buffer::<T>(rows, cols, cv_type, data.len())?;
+        let mat = cv_try_new_mat(|e| unsafe {
+            cv_mat_from_buffer(rows, cols, cv_type as i32, data.as_ptr() as *const u8, e)
+        })?;
+        Ok(MatRef {
+            mat: mat,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Creates a new `Mat` that takes ownership of `data` without copying
+    /// it. The vector is freed when the `Mat` is dropped.
     ///
-    /// ::std::mem::forget(new_image);
-    /// ```
-    pub fn from_buffer(rows: i32, cols: i32, cv_type: i32, buf: &Vec<u8>) -> Mat {
-        let raw = unsafe { cv_mat_from_buffer(rows, cols, cv_type, buf.as_ptr()) };
-        Mat::from_raw(raw)
+    /// See [from_slice_copy](struct.Mat.html#method.from_slice_copy) for the
+    /// requirements on `data`.
+    pub fn from_vec<T: DataType + Send + 'static>(
+        rows: i32,
+        cols: i32,
+        cv_type: CvType,
+        data: Vec<T>,
+    ) -> Result<Mat, Error> {
+        check_buffer::<T>(rows, cols, cv_type, data.len())?;
+        let mut mat = cv_try_new_mat(|e| unsafe {
+            cv_mat_from_buffer(rows, cols, cv_type as i32, data.as_ptr() as *const u8, e)
+        })?;
+        mat.buffer = Some(Arc::new(OwnedBuffer { _data: Box::new(data) }));
+        Ok(mat)
     }
 
     /// Create an empty `Mat` with specific size (rows, cols and types).
-    pub fn with_size(rows: i32, cols: i32, t: i32) -> Self {
-        let m = unsafe { cv_mat_new_with_size(rows, cols, t) };
-        Mat::from_raw(m)
+    pub fn with_size(rows: i32, cols: i32, t: i32) -> Result<Self, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_new_with_size(rows, cols, t, e) })
     }
 
     /// Create an empty `Mat` with specific size (rows, cols and types).
-    pub fn zeros(rows: i32, cols: i32, t: i32) -> Self {
-        let m = unsafe { cv_mat_zeros(rows, cols, t) };
-        Mat::from_raw(m)
+    pub fn zeros(rows: i32, cols: i32, t: i32) -> Result<Self, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_zeros(rows, cols, t, e) })
+    }
+
+    /// Creates a `Mat` with every element set to 1. Only the first channel is
+    /// set for multi-channel types, like `cv::Mat::ones`.
+    pub fn ones(rows: i32, cols: i32, cv_type: CvType) -> Result<Mat, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_ones(rows, cols, cv_type as c_int, e) })
+    }
+
+    /// Creates an identity matrix: ones on the diagonal and zeros elsewhere,
+    /// the matrix doesn't have to be square.
+    pub fn eye(rows: i32, cols: i32, cv_type: CvType) -> Result<Mat, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_eye(rows, cols, cv_type as c_int, e) })
+    }
+
+    /// Creates an N-dimensional `Mat` filled with zeros, e.g. for the 3-D
+    /// histogram of a color image.
+    ///
+    /// `rows` and `cols` are -1 when there are more than two dimensions; use
+    /// [sizes](struct.Mat.html#method.sizes) and
+    /// [at_nd](struct.Mat.html#method.at_nd) instead.
+    pub fn new_nd(sizes: &[i32], cv_type: CvType) -> Result<Mat, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_zeros_nd(sizes.len() as c_int, sizes.as_ptr(), cv_type as c_int, e) })
+    }
+
+    /// Returns the number of dimensions, 2 for images.
+    pub fn dims(&self) -> i32 {
+        unsafe { cv_mat_dims(self.inner) }
+    }
+
+    /// Returns the size of each dimension, `[rows, cols]` for images.
+    pub fn sizes(&self) -> Vec<i32> {
+        (0..self.dims()).map(|i| unsafe { cv_mat_size_nd(self.inner, i) }).collect()
     }
 
     /// Returns the raw data (as a uchar pointer)
@@ -451,45 +1301,136 @@ impl Mat {
     }
 
     /// Return a region of interest from a `Mat` specfied by a `Rect`.
-    pub fn roi(&self, rect: Rect) -> Mat {
-        let cmat = unsafe { cv_mat_roi(self.inner, rect) };
-        Mat::from_raw(cmat)
+    ///
+    /// The region refers to the data of `self`, which stays borrowed while
+    /// the region is alive. Use [roi_mut](struct.Mat.html#method.roi_mut) to
+    /// write to it, or [try_clone](struct.Mat.html#method.try_clone) to keep
+    /// a copy.
+    pub fn roi(&self, rect: Rect) -> Result<MatRef<'_>, Error> {
+        let mat = cv_try_new_mat(|e| unsafe { cv_mat_roi(self.inner, rect, e) })?;
+        Ok(MatRef {
+            mat: mat,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Like [roi](struct.Mat.html#method.roi), returning a region that can
+    /// be written to while `self` is mutably borrowed.
+    pub fn roi_mut(&mut self, rect: Rect) -> Result<MatMut<'_>, Error> {
+        let mat = cv_try_new_mat(|e| unsafe { cv_mat_roi(self.inner, rect, e) })?;
+        Ok(MatMut {
+            mat: mat,
+            _marker: PhantomData,
+        })
     }
 
     /// Apply a mask to myself.
     // TODO(benzh): Find the right reference in OpenCV for this one. Provide a
     // shortcut for `image &= mask`
-    pub fn logic_and(&mut self, mask: Mat) {
-        unsafe {
-            cv_mat_logic_and(self.inner, mask.inner);
-        }
+    pub fn logic_and(&mut self, mask: Mat) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_mat_logic_and(self.inner, mask.inner, e) })
     }
 
     /// Flips an image around vertical, horizontal, or both axes.
-    pub fn flip(&mut self, code: FlipCode) {
+    pub fn flip(&mut self, code: FlipCode) -> Result<(), Error> {
         let code = match code {
             FlipCode::XAxis => 0,
             FlipCode::YAxis => 1,
             FlipCode::XYAxis => -1,
         };
-        unsafe {
-            cv_mat_flip(self.inner, code);
+        cv_try(|e| unsafe { cv_mat_flip(self.inner, code, e) })
+    }
+
+    /// Deep copy of the matrix, see `cv::Mat::clone`. Use
+    /// [share](struct.Mat.html#method.share) to only copy the header.
+    pub fn try_clone(&self) -> Result<Mat, Error> {
+        cv_try_new_mat(|e| unsafe { cv_mat_clone(self.inner, e) })
+    }
+
+    /// Returns a new `Mat` header that refers to the same data as `self`,
+    /// like assigning one `cv::Mat` to another in C++. Writes through either
+    /// matrix are visible in both, and the data lives as long as one of them.
+    ///
+    /// If `self` borrows its data from Rust (see
+    /// [from_slice](struct.Mat.html#method.from_slice)), nothing could keep
+    /// that data alive, so the data is copied instead, which panics like
+    /// [clone](struct.Mat.html#impl-Clone) if OpenCV fails to copy it.
+    ///
+    /// # Safety
+    ///
+    /// Both matrices own the same pixels, so the borrow checker can't tell
+    /// when they alias. The caller must not write to the data through one of
+    /// them (e.g. with [as_mut_slice](struct.Mat.html#method.as_mut_slice) or
+    /// [set_to](struct.Mat.html#method.set_to)) while a slice or iterator
+    /// borrowed from the other is alive. Prefer
+    /// [roi](struct.Mat.html#method.roi) and
+    /// [reshape](struct.Mat.html#method.reshape), which borrow `self`.
+    pub unsafe fn share(&self) -> Mat {
+        if self.buffer.is_none() && !unsafe { cv_mat_is_refcounted(self.inner) } {
+            return self.clone();
         }
+        let mut mat = Mat::from_raw(unsafe { cv_mat_share(self.inner) });
+        mat.buffer = self.buffer.clone();
+        mat
+    }
+
+    /// Returns a new `Mat` header for the same data with `cn` channels and
+    /// `rows` rows; `0` keeps the current value. Like a
+    /// [roi](struct.Mat.html#method.roi), it borrows `self`, and the matrix
+    /// must be continuous unless only the channels change.
+    ///
+    /// This is the way to look at a multi-channel matrix as a single-channel
+    /// one, e.g. for [min_max_loc](struct.Mat.html#method.min_max_loc).
+    pub fn reshape(&self, cn: i32, rows: i32) -> Result<MatRef<'_>, Error> {
+        let mat = cv_try_new_mat(|e| unsafe { cv_mat_reshape(self.inner, cn, rows, e) })?;
+        Ok(MatRef {
+            mat: mat,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Copies the matrix to `dst`, which is reallocated if its size or type
+    /// don't match.
+    pub fn copy_to(&self, dst: &mut Mat) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_mat_copy_to(self.inner, dst.inner, ptr::null(), e) })?;
+        dst.refresh();
+        Ok(())
+    }
+
+    /// Copies the elements of the matrix to `dst` where `mask` is non-zero.
+    /// `mask` must be a `Cv8UC1` matrix of the same size as `self`.
+    pub fn copy_to_masked(&self, dst: &mut Mat, mask: &Mat) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_mat_copy_to(self.inner, dst.inner, mask.inner, e) })?;
+        dst.refresh();
+        Ok(())
+    }
+
+    /// Sets all elements, or only those where `mask` is non-zero, to `value`.
+    pub fn set_to<S: Into<Scalar>>(&mut self, value: S, mask: Option<&Mat>) -> Result<(), Error> {
+        let mask = mask.map_or(ptr::null(), |m| m.inner as *const CMat);
+        cv_try(|e| unsafe { cv_mat_set_to(self.inner, value.into(), mask, e) })
+    }
+
+    /// Reads the dimensions again after the C++ object has been reallocated
+    /// in place.
+    fn refresh(&mut self) {
+        self.rows = unsafe { cv_mat_rows(self.inner) };
+        self.cols = unsafe { cv_mat_cols(self.inner) };
+        self.depth = unsafe { cv_mat_depth(self.inner) };
+        self.channels = unsafe { cv_mat_channels(self.inner) };
     }
 
     /// Calls out to highgui to show the image, the duration is specified by
     /// `delay`.
     pub fn show(&self, name: &str, delay: i32) -> Result<(), Error> {
         extern "C" {
-            fn cv_imshow(name: *const c_char, cmat: *mut CMat);
-            fn cv_wait_key(delay_ms: c_int) -> c_int;
+            fn cv_imshow(name: *const c_char, cmat: *mut CMat, error: *mut CError);
+            fn cv_wait_key(delay_ms: c_int, error: *mut CError) -> c_int;
         }
 
         let s = CString::new(name)?;
-        unsafe {
-            cv_imshow((&s).as_ptr(), self.inner);
-            cv_wait_key(delay);
-        }
+        cv_try(|e| unsafe { cv_imshow((&s).as_ptr(), self.inner, e) })?;
+        cv_try(|e| unsafe { cv_wait_key(delay, e) })?;
         Ok(())
     }
 
@@ -501,7 +1442,10 @@ impl Mat {
     }
 }
 
+/// Values that can be read from the raw bytes of an element, see
+/// [at](struct.Mat.html#method.at).
 pub trait FromBytes {
+    /// Reads the value from the start of `bytes`.
     fn from_bytes(bytes: &[u8]) -> Self;
 }
 
@@ -651,159 +1595,1294 @@ impl Mat {
     }
 }
 
-impl Drop for Mat {
-    fn drop(&mut self) {
-        unsafe {
-            cv_mat_drop(self.inner);
-        }
+/// Rust types that can be viewed in place as the elements of a `Mat`.
+///
+/// This trait is `unsafe` to implement: the implementor promises that the type
+/// has exactly the memory layout of `CHANNELS` consecutive values of OpenCV
+/// depth `DEPTH`.
+pub unsafe trait DataType: Copy {
+    /// The OpenCV depth of each channel, i.e. the value returned by
+    /// `cv::Mat::depth()` (`CV_8U` is 0, `CV_64F` is 6).
+    const DEPTH: i32;
+
+    /// The number of channels packed in one value of this type.
+    const CHANNELS: i32;
+
+    /// Returns the `CvType` of a matrix whose elements are of this type.
+    fn cv_type() -> CvType {
+        CvType::from_depth_and_channels(Self::DEPTH, Self::CHANNELS).expect("DataType without a matching CvType")
     }
 }
 
-/// Here is the `CvType` in an easy-to-read table.
-///
-/// |        | C1 | C2 | C3 | C4 | C(5) | C(6) | C(7) | C(8) |
-/// |--------|----|----|----|----|------|------|------|------|
-/// | CV_8U  |  0 |  8 | 16 | 24 |   32 |   40 |   48 |   56 |
-/// | CV_8S  |  1 |  9 | 17 | 25 |   33 |   41 |   49 |   57 |
-/// | CV_16U |  2 | 10 | 18 | 26 |   34 |   42 |   50 |   58 |
-/// | CV_16S |  3 | 11 | 19 | 27 |   35 |   43 |   51 |   59 |
-/// | CV_32S |  4 | 12 | 20 | 28 |   36 |   44 |   52 |   60 |
-/// | CV_32F |  5 | 13 | 21 | 29 |   37 |   45 |   53 |   61 |
-/// | CV_64F |  6 | 14 | 22 | 30 |   38 |   46 |   54 |   62 |
-#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
-pub enum CvType {
-    /// 8 bit unsigned (like `uchar`), single channel (grey image)
-    Cv8UC1 = 0,
+macro_rules! impl_data_type {
+    ($($t:ty => $depth:expr),*) => {
+        $(
+            unsafe impl DataType for $t {
+                const DEPTH: i32 = $depth;
+                const CHANNELS: i32 = 1;
+            }
 
-    /// 8 bit signed (like `schar`), single channel (grey image)
-    Cv8SC1 = 1,
+            unsafe impl DataType for [$t; 2] {
+                const DEPTH: i32 = $depth;
+                const CHANNELS: i32 = 2;
+            }
 
-    /// 16 bit unsigned (like `ushort`), single channel (grey image)
-    Cv16UC1 = 2,
+            unsafe impl DataType for [$t; 3] {
+                const DEPTH: i32 = $depth;
+                const CHANNELS: i32 = 3;
+            }
 
-    /// 16 bit signed (like `short`), single channel (grey image)
-    Cv16SC1 = 3,
+            unsafe impl DataType for [$t; 4] {
+                const DEPTH: i32 = $depth;
+                const CHANNELS: i32 = 4;
+            }
+        )*
+    }
+}
 
-    /// 32 bit signed (like `int`), single channel (grey image)
-    Cv32SC1 = 4,
+impl_data_type!(u8 => 0, i8 => 1, u16 => 2, i16 => 3, i32 => 4, f32 => 5, f64 => 6);
+
+/// Two 8 bit unsigned channels, like `cv::Vec2b`.
+pub type Vec2b = [u8; 2];
+/// Three 8 bit unsigned channels (a BGR pixel), like `cv::Vec3b`.
+pub type Vec3b = [u8; 3];
+/// Four 8 bit unsigned channels (a BGRA pixel), like `cv::Vec4b`.
+pub type Vec4b = [u8; 4];
+/// Two 16 bit unsigned channels, like `cv::Vec2w`.
+pub type Vec2w = [u16; 2];
+/// Three 16 bit unsigned channels, like `cv::Vec3w`.
+pub type Vec3w = [u16; 3];
+/// Four 16 bit unsigned channels, like `cv::Vec4w`.
+pub type Vec4w = [u16; 4];
+/// Two 16 bit signed channels, like `cv::Vec2s`.
+pub type Vec2s = [i16; 2];
+/// Three 16 bit signed channels, like `cv::Vec3s`.
+pub type Vec3s = [i16; 3];
+/// Four 16 bit signed channels, like `cv::Vec4s`.
+pub type Vec4s = [i16; 4];
+/// Two 32 bit signed channels, like `cv::Vec2i`.
+pub type Vec2i = [i32; 2];
+/// Three 32 bit signed channels, like `cv::Vec3i`.
+pub type Vec3i = [i32; 3];
+/// Four 32 bit signed channels, like `cv::Vec4i`.
+pub type Vec4i = [i32; 4];
+/// Two 32 bit float channels, like `cv::Vec2f`.
+pub type Vec2f = [f32; 2];
+/// Three 32 bit float channels, like `cv::Vec3f`.
+pub type Vec3f = [f32; 3];
+/// Four 32 bit float channels, like `cv::Vec4f`.
+pub type Vec4f = [f32; 4];
+/// Two 64 bit float channels, like `cv::Vec2d`.
+pub type Vec2d = [f64; 2];
+/// Three 64 bit float channels, like `cv::Vec3d`.
+pub type Vec3d = [f64; 3];
+/// Four 64 bit float channels, like `cv::Vec4d`.
+pub type Vec4d = [f64; 4];
 
-    /// 32 bit float (like `float`), single channel (grey image)
-    Cv32FC1 = 5,
+impl Mat {
+    /// Returns true if the matrix elements are stored continuously without
+    /// gaps at the end of each row. Matrices created by OpenCV are continuous,
+    /// while a [roi](struct.Mat.html#method.roi) usually isn't.
+    pub fn is_continuous(&self) -> bool {
+        unsafe { cv_mat_is_continuous(self.inner) }
+    }
 
-    /// 32 bit float (like `double`), single channel (grey image)
-    Cv64FC1 = 6,
+    /// Returns the whole matrix as a slice of `T`.
+    ///
+    /// `T` must either be a primitive matching the depth of the matrix (the
+    /// slice then contains every channel of every element, i.e. `u8` for a
+    /// `Cv8UC3` image), or an array matching both depth and channels (`[u8; 3]`
+    /// for a `Cv8UC3` image). The matrix must also be continuous; use
+    /// [row](struct.Mat.html#method.row) otherwise.
+    pub fn as_slice<T: DataType>(&self) -> Result<&[T], Error> {
+        let len = self.slice_len::<T>()?;
+        let data = self.data();
+        if data.is_null() {
+            return Ok(&[]);
+        }
+        Ok(unsafe { slice::from_raw_parts(data as *const T, len) })
+    }
 
-    /// 8 bit, two channel (rarelly seen)
-    Cv8UC2 = 8,
+    /// Returns the whole matrix as a mutable slice of `T`. See
+    /// [as_slice](struct.Mat.html#method.as_slice).
+    pub fn as_mut_slice<T: DataType>(&mut self) -> Result<&mut [T], Error> {
+        let len = self.slice_len::<T>()?;
+        let data = self.data() as *mut u8;
+        if data.is_null() {
+            return Ok(&mut []);
+        }
+        Ok(unsafe { slice::from_raw_parts_mut(data as *mut T, len) })
+    }
 
-    /// 8 bit unsigned (like `uchar`), three channels (RGB image)
-    Cv8UC3 = 16,
+    /// Returns the `i`-th row of the matrix as a slice of `T`. Unlike
+    /// [as_slice](struct.Mat.html#method.as_slice), this works on matrices that
+    /// are not continuous.
+    pub fn row<T: DataType>(&self, i: usize) -> Result<&[T], Error> {
+        let (ptr, len) = self.row_ptr::<T>(i)?;
+        Ok(unsafe { slice::from_raw_parts(ptr as *const T, len) })
+    }
 
-    /// 8 bit signed (like `schar`), three channels (RGB image)
-    Cv8SC3 = 17,
+    /// Returns the `i`-th row of the matrix as a mutable slice of `T`. See
+    /// [row](struct.Mat.html#method.row).
+    pub fn row_mut<T: DataType>(&mut self, i: usize) -> Result<&mut [T], Error> {
+        let (ptr, len) = self.row_ptr::<T>(i)?;
+        Ok(unsafe { slice::from_raw_parts_mut(ptr as *mut T, len) })
+    }
 
-    /// 16 bit unsigned (like `ushort`), three channels (RGB image)
-    Cv16UC3 = 18,
+    /// Returns the element at `idx`, which has one index per dimension (see
+    /// [sizes](struct.Mat.html#method.sizes)). `T` must match both the depth
+    /// and the channels of the matrix, which must not be empty.
+    pub fn at_nd<T: DataType>(&self, idx: &[i32]) -> Result<&T, Error> {
+        let ptr = self.nd_ptr::<T>(idx)?;
+        Ok(unsafe { &*(ptr as *const T) })
+    }
 
-    /// 16 bit signed (like `short`), three channels (RGB image)
-    Cv16SC3 = 19,
+    /// Returns the element at `idx` mutably. See
+    /// [at_nd](struct.Mat.html#method.at_nd).
+    pub fn at_nd_mut<T: DataType>(&mut self, idx: &[i32]) -> Result<&mut T, Error> {
+        let ptr = self.nd_ptr::<T>(idx)?;
+        Ok(unsafe { &mut *(ptr as *mut T) })
+    }
 
-    /// 32 bit signed (like `int`), three channels (RGB image)
-    Cv32SC3 = 20,
+    fn nd_ptr<T: DataType>(&self, idx: &[i32]) -> Result<*const u8, Error> {
+        let layout = self.check_data_type::<T>()?;
+        if T::CHANNELS != layout.channels {
+            return Err(CvError::ElementTypeMismatch {
+                mat_depth: layout.depth,
+                mat_channels: layout.channels,
+                depth: T::DEPTH,
+                channels: T::CHANNELS,
+            }.into());
+        }
+        if self.dims() == 0 || self.data().is_null() {
+            return Err(CvError::EmptyMat.into());
+        }
+        let sizes = self.sizes();
+        if idx.len() != sizes.len() {
+            return Err(CvError::DimensionMismatch {
+                dims: sizes.len(),
+                indices: idx.len(),
+            }.into());
+        }
+        let mut offset = 0;
+        for (i, (&index, &size)) in idx.iter().zip(sizes.iter()).enumerate() {
+            if index < 0 || index >= size {
+                return Err(CvError::IndexOutOfRange {
+                    index: index as usize,
+                    bound: size as usize,
+                }.into());
+            }
+            offset += index as usize * self.step1(i as c_int) * self.elem_size1();
+        }
+        Ok(unsafe { self.data().offset(offset as isize) })
+    }
 
-    /// 32 bit float (like `float`), three channels (RGB image)
-    Cv32FC3 = 21,
+    /// Reads the size and type from the C++ object. Unlike the public fields,
+    /// which can be changed from safe code, it can be trusted for bounds checks.
+    pub(crate) fn layout(&self) -> Layout {
+        Layout {
+            rows: unsafe { cv_mat_rows(self.inner) },
+            cols: unsafe { cv_mat_cols(self.inner) },
+            depth: unsafe { cv_mat_depth(self.inner) },
+            channels: unsafe { cv_mat_channels(self.inner) },
+        }
+    }
 
-    /// 32 bit float (like `double`), three channels (RGB image)
-    Cv64FC3 = 22,
-}
+    /// Checks that `T` can be used to view the elements of this matrix.
+    fn check_data_type<T: DataType>(&self) -> Result<Layout, Error> {
+        let layout = self.layout();
+        if T::DEPTH != layout.depth || (T::CHANNELS != 1 && T::CHANNELS != layout.channels) {
+            return Err(CvError::ElementTypeMismatch {
+                mat_depth: layout.depth,
+                mat_channels: layout.channels,
+                depth: T::DEPTH,
+                channels: T::CHANNELS,
+            }.into());
+        }
+        Ok(layout)
+    }
 
-/// This struct represents a rotated (i.e. not up-right) rectangle. Each
-/// rectangle is specified by the center point (mass center), length of each
-/// side (represented by `Size2f`) and the rotation angle in degrees.
-#[derive(Default, Debug, Clone, Copy)]
-#[repr(C)]
-pub struct RotatedRect {
-    center: Point2f,
-    size: Size2f,
-    angle: f32,
-}
+    fn slice_len<T: DataType>(&self) -> Result<usize, Error> {
+        let layout = self.check_data_type::<T>()?;
+        if !self.is_continuous() {
+            return Err(CvError::NotContinuous.into());
+        }
+        Ok(self.total() * (layout.channels / T::CHANNELS) as usize)
+    }
 
-impl RotatedRect {
-    /// Return 4 vertices of the rectangle.
-    pub fn points(&self) -> [Point2f; 4] {
-        let angle = self.angle * ::std::f32::consts::PI / 180.0;
+    fn row_ptr<T: DataType>(&self, i: usize) -> Result<(*const u8, usize), Error> {
+        let layout = self.check_data_type::<T>()?;
+        let rows = if layout.rows > 0 { layout.rows as usize } else { 0 };
+        if i >= rows {
+            return Err(CvError::IndexOutOfRange { index: i, bound: rows }.into());
+        }
+        let offset = i * self.step1(0) * self.elem_size1();
+        Ok((unsafe { self.data().offset(offset as isize) }, layout.row_len::<T>()))
+    }
 
-        let b = angle.cos() * 0.5;
-        let a = angle.sin() * 0.5;
+    /// Returns an iterator over the rows of the matrix as slices of `T`, see
+    /// [row](struct.Mat.html#method.row).
+    pub fn rows_iter<T: DataType>(&self) -> Result<Rows<'_, T>, Error> {
+        let cursor = self.row_cursor::<T>()?;
+        Ok(Rows {
+            cursor: cursor,
+            _marker: PhantomData,
+        })
+    }
 
-        let mut pts: [Point2f; 4] = [Point2f::default(); 4];
-        pts[0].x = self.center.x - a * self.size.height - b * self.size.width;
-        pts[0].y = self.center.y + b * self.size.height - a * self.size.width;
-        pts[1].x = self.center.x + a * self.size.height - b * self.size.width;
-        pts[1].y = self.center.y - b * self.size.height - a * self.size.width;
+    /// Returns an iterator over the rows of the matrix as mutable slices of
+    /// `T`, see [row](struct.Mat.html#method.row).
+    pub fn rows_iter_mut<T: DataType>(&mut self) -> Result<RowsMut<'_, T>, Error> {
+        let cursor = self.row_cursor::<T>()?;
+        Ok(RowsMut {
+            cursor: cursor,
+            _marker: PhantomData,
+        })
+    }
 
-        pts[2].x = 2.0 * self.center.x - pts[0].x;
-        pts[2].y = 2.0 * self.center.y - pts[0].y;
-        pts[3].x = 2.0 * self.center.x - pts[1].x;
-        pts[3].y = 2.0 * self.center.y - pts[1].y;
-        pts
+    /// Returns an iterator over all the elements of the matrix, row by row.
+    ///
+    /// `T` follows the same rules as in
+    /// [as_slice](struct.Mat.html#method.as_slice): use `Vec3b` to get the
+    /// pixels of a `Cv8UC3` image, or `u8` to get each channel separately. The
+    /// matrix doesn't have to be continuous.
+    pub fn pixels<T: DataType>(&self) -> Result<Pixels<'_, T>, Error> {
+        Ok(Pixels {
+            rows: self.rows_iter()?,
+            current: [].iter(),
+        })
     }
 
-    /// Return the minimal up-right rectangle containing the rotated rectangle
-    pub fn bounding_rect(&self) -> Rect {
-        let pt = self.points();
-        let x = pt.iter().map(|p| p.x).fold(0. / 0., f32::min).floor() as i32;
-        let y = pt.iter().map(|p| p.y).fold(0. / 0., f32::min).floor() as i32;
+    /// Returns an iterator over mutable references to all the elements of the
+    /// matrix, see [pixels](struct.Mat.html#method.pixels).
+    pub fn pixels_mut<T: DataType>(&mut self) -> Result<PixelsMut<'_, T>, Error> {
+        Ok(PixelsMut {
+            rows: self.rows_iter_mut()?,
+            current: [].iter_mut(),
+        })
+    }
 
-        let width = pt.iter().map(|p| p.x).fold(0. / 0., f32::max).ceil() as i32 - x + 1;
-        let height = pt.iter().map(|p| p.y).fold(0. / 0., f32::max).ceil() as i32 - y + 1;
-        Rect::new(x, y, width, height)
+    /// Like [pixels](struct.Mat.html#method.pixels), also yielding the row
+    /// and column of each element. The column counts values of `T`, i.e.
+    /// channels when `T` is a primitive.
+    pub fn enumerate_pixels<T: DataType>(&self) -> Result<EnumeratePixels<'_, T>, Error> {
+        Ok(EnumeratePixels {
+            rows: self.rows_iter()?.enumerate(),
+            current: None,
+        })
+    }
+
+    /// Returns a parallel iterator over the rows of the matrix as mutable
+    /// slices of `T`, so that a kernel can be applied to each row on the
+    /// `rayon` thread pool. Requires the `rayon` feature.
+    ///
+    /// ```rust,ignore
+    /// image.par_rows_mut::<Vec3b>()?.for_each(|row| {
+    ///     for pixel in row {
+    ///         pixel.swap(0, 2);
+    ///     }
+    /// });
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_rows_mut<'a, T: DataType + Send + 'a>(
+        &'a mut self,
+    ) -> Result<impl ::rayon::iter::IndexedParallelIterator<Item = &'a mut [T]>, Error> {
+        Ok(ParRowsMut {
+            rows: self.rows_iter_mut()?,
+        })
+    }
+
+    fn row_cursor<T: DataType>(&self) -> Result<RowCursor, Error> {
+        let layout = self.check_data_type::<T>()?;
+        let data = self.data();
+        let rows = if layout.rows > 0 && !data.is_null() {
+            layout.rows as usize
+        } else {
+            0
+        };
+        Ok(RowCursor {
+            data: data,
+            step: self.step1(0) * self.elem_size1(),
+            len: layout.row_len::<T>(),
+            row: 0,
+            rows: rows,
+        })
     }
 }
 
-// =============================================================================
-// core array
-// =============================================================================
-extern "C" {
-    fn cv_in_range(cmat: *const CMat, lowerb: Scalar, upperb: Scalar, dst: *mut CMat);
-    fn cv_min_max_loc(
-        cmat: *const CMat,
-        min: *mut f64,
-        max: *mut f64,
-        min_loc: *mut Point2i,
-        max_loc: *mut Point2i,
-        cmask: *const CMat,
-    );
-    fn cv_mix_channels(
-        cmat: *const CMat,
-        nsrcs: isize,
-        dst: *mut CMat,
-        ndsts: isize,
-        from_to: *const i32,
-        npairs: isize,
-    );
-    fn cv_normalize(csrc: *const CMat, cdst: *mut CMat, alpha: c_double, beta: c_double, norm_type: c_int);
+/// Size and type of a matrix, see [layout](struct.Mat.html#method.layout).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Layout {
+    pub rows: i32,
+    pub cols: i32,
+    pub depth: i32,
+    pub channels: i32,
+}
 
-    fn cv_bitwise_and(src1: *const CMat, src2: *const CMat, dst: *mut CMat);
-    fn cv_bitwise_not(src: *const CMat, dst: *mut CMat);
-    fn cv_bitwise_or(src1: *const CMat, src2: *const CMat, dst: *mut CMat);
-    fn cv_bitwise_xor(src1: *const CMat, src2: *const CMat, dst: *mut CMat);
-    fn cv_count_non_zero(src: *const CMat) -> i32;
+impl Layout {
+    /// Number of `T` in one row of the matrix.
+    fn row_len<T: DataType>(&self) -> usize {
+        (self.cols * self.channels / T::CHANNELS) as usize
+    }
 }
 
-/// Normalization type. Please refer to [OpenCV's
-/// documentation](http://docs.cv.org/trunk/d2/de8/group__core__array.html).
-#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
-pub enum NormTypes {
-    /// Normalized using `max`
-    NormInf = 1,
-    /// Normalized using L1 distance
-    NormL1 = 2,
-    /// Normalized using L2 distance
-    NormL2 = 4,
-    /// Normalized using L2 sqr distance
-    NormL2Sqr = 5,
+/// Position of the row iterators: the rows are `step` bytes apart and hold
+/// `len` values each.
+#[derive(Debug, Clone, Copy)]
+struct RowCursor {
+    data: *const u8,
+    step: usize,
+    len: usize,
+    row: usize,
+    rows: usize,
+}
+
+impl Iterator for RowCursor {
+    type Item = (*const u8, usize);
+
+    fn next(&mut self) -> Option<(*const u8, usize)> {
+        if self.row >= self.rows {
+            return None;
+        }
+        let ptr = unsafe { self.data.offset((self.row * self.step) as isize) };
+        self.row += 1;
+        Some((ptr, self.len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.rows - self.row;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for RowCursor {
+    fn next_back(&mut self) -> Option<(*const u8, usize)> {
+        if self.row >= self.rows {
+            return None;
+        }
+        self.rows -= 1;
+        let ptr = unsafe { self.data.offset((self.rows * self.step) as isize) };
+        Some((ptr, self.len))
+    }
+}
+
+impl RowCursor {
+    /// Splits the remaining rows into the first `index` ones and the rest.
+    #[cfg(feature = "rayon")]
+    fn split_at(self, index: usize) -> (RowCursor, RowCursor) {
+        let mid = self.row + index;
+        (RowCursor { rows: mid, ..self }, RowCursor { row: mid, ..self })
+    }
+}
+
+/// Iterator over the rows of a [Mat](struct.Mat.html), see
+/// [rows_iter](struct.Mat.html#method.rows_iter).
+#[derive(Debug)]
+pub struct Rows<'a, T: 'a> {
+    cursor: RowCursor,
+    _marker: PhantomData<&'a [T]>,
+}
+
+impl<'a, T: 'a> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        self.cursor
+            .next()
+            .map(|(ptr, len)| unsafe { slice::from_raw_parts(ptr as *const T, len) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cursor.size_hint()
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Rows<'a, T> {}
+
+/// Iterator over the mutable rows of a [Mat](struct.Mat.html), see
+/// [rows_iter_mut](struct.Mat.html#method.rows_iter_mut).
+#[derive(Debug)]
+pub struct RowsMut<'a, T: 'a> {
+    cursor: RowCursor,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: 'a> Iterator for RowsMut<'a, T> {
+    type Item = &'a mut [T];
+
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        // Rows never overlap, so each slice is handed out exactly once.
+        self.cursor
+            .next()
+            .map(|(ptr, len)| unsafe { slice::from_raw_parts_mut(ptr as *mut T, len) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cursor.size_hint()
+    }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for RowsMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut [T]> {
+        self.cursor
+            .next_back()
+            .map(|(ptr, len)| unsafe { slice::from_raw_parts_mut(ptr as *mut T, len) })
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for RowsMut<'a, T> {}
+
+/// The rows are handed out once each, like the elements of a
+/// `slice::IterMut`, so they can be sent to another thread.
+unsafe impl<'a, T: Send + 'a> Send for RowsMut<'a, T> {}
+
+/// Parallel iterator over the mutable rows of a [Mat](struct.Mat.html), see
+/// [par_rows_mut](struct.Mat.html#method.par_rows_mut). Rayon splits it into
+/// ranges of rows.
+#[cfg(feature = "rayon")]
+#[derive(Debug)]
+struct ParRowsMut<'a, T: 'a> {
+    rows: RowsMut<'a, T>,
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Send + 'a> ::rayon::iter::ParallelIterator for ParRowsMut<'a, T> {
+    type Item = &'a mut [T];
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: ::rayon::iter::plumbing::UnindexedConsumer<Self::Item>,
+    {
+        ::rayon::iter::plumbing::bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.rows.len())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Send + 'a> ::rayon::iter::IndexedParallelIterator for ParRowsMut<'a, T> {
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: ::rayon::iter::plumbing::Consumer<Self::Item>,
+    {
+        ::rayon::iter::plumbing::bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ::rayon::iter::plumbing::ProducerCallback<Self::Item>,
+    {
+        callback.callback(self.rows)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Send + 'a> ::rayon::iter::plumbing::Producer for RowsMut<'a, T> {
+    type Item = &'a mut [T];
+    type IntoIter = RowsMut<'a, T>;
+
+    fn into_iter(self) -> RowsMut<'a, T> {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (RowsMut<'a, T>, RowsMut<'a, T>) {
+        let (left, right) = self.cursor.split_at(index);
+        (
+            RowsMut {
+                cursor: left,
+                _marker: PhantomData,
+            },
+            RowsMut {
+                cursor: right,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+/// Iterator over the elements of a [Mat](struct.Mat.html), see
+/// [pixels](struct.Mat.html#method.pixels).
+#[derive(Debug)]
+pub struct Pixels<'a, T: 'a> {
+    rows: Rows<'a, T>,
+    current: slice::Iter<'a, T>,
+}
+
+impl<'a, T: 'a> Iterator for Pixels<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(pixel) = self.current.next() {
+                return Some(pixel);
+            }
+            self.current = self.rows.next()?.iter();
+        }
+    }
+}
+
+/// Iterator over the mutable elements of a [Mat](struct.Mat.html), see
+/// [pixels_mut](struct.Mat.html#method.pixels_mut).
+#[derive(Debug)]
+pub struct PixelsMut<'a, T: 'a> {
+    rows: RowsMut<'a, T>,
+    current: slice::IterMut<'a, T>,
+}
+
+impl<'a, T: 'a> Iterator for PixelsMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        loop {
+            if let Some(pixel) = self.current.next() {
+                return Some(pixel);
+            }
+            self.current = self.rows.next()?.iter_mut();
+        }
+    }
+}
+
+/// Iterator over the elements of a [Mat](struct.Mat.html) with their row and
+/// column, see [enumerate_pixels](struct.Mat.html#method.enumerate_pixels).
+#[derive(Debug)]
+pub struct EnumeratePixels<'a, T: 'a> {
+    rows: iter::Enumerate<Rows<'a, T>>,
+    current: Option<(usize, iter::Enumerate<slice::Iter<'a, T>>)>,
+}
+
+impl<'a, T: 'a> Iterator for EnumeratePixels<'a, T> {
+    type Item = (usize, usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, usize, &'a T)> {
+        loop {
+            if let Some((row, ref mut pixels)) = self.current {
+                if let Some((col, pixel)) = pixels.next() {
+                    return Some((row, col, pixel));
+                }
+            }
+            let (row, pixels) = self.rows.next()?;
+            self.current = Some((row, pixels.iter().enumerate()));
+        }
+    }
+}
+
+/// A `Mat` whose element type is known at compile time, similar to OpenCV's
+/// `Mat_<T>`. For example, `TypedMat<Vec3b>` is a `Cv8UC3` image and
+/// `TypedMat<f32>` a `Cv32FC1` matrix.
+///
+/// It dereferences to [Mat](struct.Mat.html), so all the read-only `Mat`
+/// operations are available as well.
+#[derive(Debug)]
+pub struct TypedMat<T: DataType> {
+    mat: Mat,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DataType> TypedMat<T> {
+    /// Creates a new matrix of the given size with all elements set to zero.
+    pub fn new(rows: i32, cols: i32) -> Result<Self, Error> {
+        Ok(TypedMat {
+            mat: Mat::zeros(rows, cols, T::cv_type() as i32)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Converts an untyped `Mat`, checking that both its depth and its number
+    /// of channels match `T`.
+    pub fn from_mat(mat: Mat) -> Result<Self, Error> {
+        let layout = mat.layout();
+        if layout.depth != T::DEPTH || layout.channels != T::CHANNELS {
+            return Err(CvError::ElementTypeMismatch {
+                mat_depth: layout.depth,
+                mat_channels: layout.channels,
+                depth: T::DEPTH,
+                channels: T::CHANNELS,
+            }.into());
+        }
+        Ok(TypedMat {
+            mat: mat,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the underlying untyped `Mat`.
+    pub fn into_mat(self) -> Mat {
+        self.mat
+    }
+
+    /// Returns the `CvType` of this matrix.
+    pub fn cv_type(&self) -> CvType {
+        T::cv_type()
+    }
+
+    /// Returns the element at (`row`, `col`), or an error if it is out of
+    /// bounds.
+    pub fn get(&self, row: usize, col: usize) -> Result<T, Error> {
+        let bound = self.mat.layout().cols as usize;
+        self.mat
+            .row::<T>(row)?
+            .get(col)
+            .cloned()
+            .ok_or_else(|| CvError::IndexOutOfRange { index: col, bound: bound }.into())
+    }
+
+    /// Sets the element at (`row`, `col`), or returns an error if it is out
+    /// of bounds.
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<(), Error> {
+        let bound = self.mat.layout().cols as usize;
+        match self.mat.row_mut::<T>(row)?.get_mut(col) {
+            Some(elem) => {
+                *elem = value;
+                Ok(())
+            }
+            None => Err(CvError::IndexOutOfRange { index: col, bound: bound }.into()),
+        }
+    }
+
+    /// Returns all elements as a slice. See
+    /// [Mat::as_slice](struct.Mat.html#method.as_slice).
+    pub fn as_slice(&self) -> Result<&[T], Error> {
+        self.mat.as_slice::<T>()
+    }
+
+    /// Returns all elements as a mutable slice. See
+    /// [Mat::as_mut_slice](struct.Mat.html#method.as_mut_slice).
+    pub fn as_mut_slice(&mut self) -> Result<&mut [T], Error> {
+        self.mat.as_mut_slice::<T>()
+    }
+
+    /// Returns the `i`-th row as a slice. See
+    /// [Mat::row](struct.Mat.html#method.row).
+    pub fn row(&self, i: usize) -> Result<&[T], Error> {
+        self.mat.row::<T>(i)
+    }
+
+    /// Returns the `i`-th row as a mutable slice. See
+    /// [Mat::row_mut](struct.Mat.html#method.row_mut).
+    pub fn row_mut(&mut self, i: usize) -> Result<&mut [T], Error> {
+        self.mat.row_mut::<T>(i)
+    }
+}
+
+impl<T: DataType> Deref for TypedMat<T> {
+    type Target = Mat;
+
+    fn deref(&self) -> &Mat {
+        &self.mat
+    }
+}
+
+impl<T: DataType> From<TypedMat<T>> for Mat {
+    fn from(typed: TypedMat<T>) -> Mat {
+        typed.mat
+    }
+}
+
+impl<T: DataType> TryFrom<Mat> for TypedMat<T> {
+    type Error = Error;
+
+    fn try_from(mat: Mat) -> Result<Self, Error> {
+        TypedMat::from_mat(mat)
+    }
+}
+
+impl Drop for Mat {
+    fn drop(&mut self) {
+        unsafe {
+            cv_mat_drop(self.inner);
+        }
+    }
+}
+
+impl Clone for Mat {
+    /// Like [try_clone](struct.Mat.html#method.try_clone).
+    ///
+    /// # Panics
+    ///
+    /// Panics if OpenCV fails to copy the matrix, e.g. when it runs out of
+    /// memory.
+    fn clone(&self) -> Mat {
+        self.try_clone().expect("failed to clone Mat")
+    }
+}
+
+impl PartialEq for Mat {
+    /// Exact comparison: the size, the type and the bytes of every element
+    /// must match, so `0.0` and `-0.0` differ while identical NaNs are equal.
+    /// Use [approx_eq](struct.Mat.html#method.approx_eq) for floating point
+    /// results. Matrices that OpenCV fails to iterate compare unequal.
+    fn eq(&self, other: &Mat) -> bool {
+        cv_try(|e| unsafe { cv_mat_eq(self.inner, other.inner, e) }).unwrap_or(false)
+    }
+}
+
+/// Serialized form of a [Mat](struct.Mat.html): its size, type and the
+/// elements in row-major order as raw bytes.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct MatData {
+    rows: i32,
+    cols: i32,
+    #[serde(rename = "type")]
+    cv_type: CvType,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl MatData {
+    fn into_mat(self) -> Result<Mat, Error> {
+        if self.rows < 0 || self.cols < 0 {
+            return Err(CvError::InvalidSize {
+                rows: self.rows,
+                cols: self.cols,
+            }.into());
+        }
+        let depth_size = [1, 1, 2, 2, 4, 4, 8][self.cv_type.depth() as usize];
+        let expected = (self.rows as usize) * (self.cols as usize) * (self.cv_type.channels() as usize) * depth_size;
+        if self.data.len() != expected {
+            return Err(CvError::BufferSizeMismatch {
+                expected: expected,
+                actual: self.data.len(),
+            }.into());
+        }
+        let mat = Mat::with_size(self.rows, self.cols, self.cv_type as i32)?;
+        if expected > 0 {
+            unsafe { slice::from_raw_parts_mut(mat.data() as *mut u8, expected) }.copy_from_slice(&self.data);
+        }
+        Ok(mat)
+    }
+}
+
+/// Serializes the elements with `serialize_bytes`, which is compact in binary
+/// formats.
+#[cfg(feature = "serde")]
+struct MatBytes<'a>(&'a [u8]);
+
+#[cfg(feature = "serde")]
+impl<'a> ::serde::Serialize for MatBytes<'a> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Serialized as `{rows, cols, type, data}`, `data` holding the elements in
+/// row-major order as raw bytes in native byte order. Only 2-dimensional
+/// matrices are supported.
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Mat {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error as SerError, SerializeStruct};
+
+        if self.dims() > 2 {
+            return Err(S::Error::custom("only 2-dimensional matrices can be serialized"));
+        }
+        let cv_type = self.cv_type().map_err(S::Error::custom)?;
+        let copy;
+        let mat = if self.is_continuous() {
+            self
+        } else {
+            copy = self.try_clone().map_err(S::Error::custom)?;
+            &copy
+        };
+        let len = mat.total() * mat.elem_size();
+        let data = if len == 0 {
+            &[][..]
+        } else {
+            unsafe { slice::from_raw_parts(mat.data(), len) }
+        };
+
+        let mut state = serializer.serialize_struct("Mat", 4)?;
+        state.serialize_field("rows", &self.rows)?;
+        state.serialize_field("cols", &self.cols)?;
+        state.serialize_field("type", &cv_type)?;
+        state.serialize_field("data", &MatBytes(data))?;
+        state.end()
+    }
+}
+
+/// Fails if the size is negative or `data` doesn't hold exactly
+/// `rows * cols` elements of `type`.
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for Mat {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Mat, D::Error> {
+        use serde::de::Error as DeError;
+
+        MatData::deserialize(deserializer)?.into_mat().map_err(D::Error::custom)
+    }
+}
+
+/// Here is the `CvType` in an easy-to-read table.
+///
+/// |        | C1 | C2 | C3 | C4 | C(5) | C(6) | C(7) | C(8) |
+/// |--------|----|----|----|----|------|------|------|------|
+/// | CV_8U  |  0 |  8 | 16 | 24 |   32 |   40 |   48 |   56 |
+/// | CV_8S  |  1 |  9 | 17 | 25 |   33 |   41 |   49 |   57 |
+/// | CV_16U |  2 | 10 | 18 | 26 |   34 |   42 |   50 |   58 |
+/// | CV_16S |  3 | 11 | 19 | 27 |   35 |   43 |   51 |   59 |
+/// | CV_32S |  4 | 12 | 20 | 28 |   36 |   44 |   52 |   60 |
+/// | CV_32F |  5 | 13 | 21 | 29 |   37 |   45 |   53 |   61 |
+/// | CV_64F |  6 | 14 | 22 | 30 |   38 |   46 |   54 |   62 |
+#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CvType {
+    /// 8 bit unsigned (like `uchar`), single channel (grey image)
+    Cv8UC1 = 0,
+
+    /// 8 bit signed (like `schar`), single channel (grey image)
+    Cv8SC1 = 1,
+
+    /// 16 bit unsigned (like `ushort`), single channel (grey image)
+    Cv16UC1 = 2,
+
+    /// 16 bit signed (like `short`), single channel (grey image)
+    Cv16SC1 = 3,
+
+    /// 32 bit signed (like `int`), single channel (grey image)
+    Cv32SC1 = 4,
+
+    /// 32 bit float (like `float`), single channel (grey image)
+    Cv32FC1 = 5,
+
+    /// 32 bit float (like `double`), single channel (grey image)
+    Cv64FC1 = 6,
+
+    /// 8 bit, two channel (rarelly seen)
+    Cv8UC2 = 8,
+
+    /// 8 bit signed (like `schar`), two channels
+    Cv8SC2 = 9,
+
+    /// 16 bit unsigned (like `ushort`), two channels
+    Cv16UC2 = 10,
+
+    /// 16 bit signed (like `short`), two channels
+    Cv16SC2 = 11,
+
+    /// 32 bit signed (like `int`), two channels
+    Cv32SC2 = 12,
+
+    /// 32 bit float (like `float`), two channels (complex numbers)
+    Cv32FC2 = 13,
+
+    /// 64 bit float (like `double`), two channels (complex numbers)
+    Cv64FC2 = 14,
+
+    /// 8 bit unsigned (like `uchar`), three channels (RGB image)
+    Cv8UC3 = 16,
+
+    /// 8 bit signed (like `schar`), three channels (RGB image)
+    Cv8SC3 = 17,
+
+    /// 16 bit unsigned (like `ushort`), three channels (RGB image)
+    Cv16UC3 = 18,
+
+    /// 16 bit signed (like `short`), three channels (RGB image)
+    Cv16SC3 = 19,
+
+    /// 32 bit signed (like `int`), three channels (RGB image)
+    Cv32SC3 = 20,
+
+    /// 32 bit float (like `float`), three channels (RGB image)
+    Cv32FC3 = 21,
+
+    /// 32 bit float (like `double`), three channels (RGB image)
+    Cv64FC3 = 22,
+
+    /// 8 bit unsigned (like `uchar`), four channels (RGBA image)
+    Cv8UC4 = 24,
+
+    /// 8 bit signed (like `schar`), four channels (RGBA image)
+    Cv8SC4 = 25,
+
+    /// 16 bit unsigned (like `ushort`), four channels (RGBA image)
+    Cv16UC4 = 26,
+
+    /// 16 bit signed (like `short`), four channels (RGBA image)
+    Cv16SC4 = 27,
+
+    /// 32 bit signed (like `int`), four channels (RGBA image)
+    Cv32SC4 = 28,
+
+    /// 32 bit float (like `float`), four channels (RGBA image)
+    Cv32FC4 = 29,
+
+    /// 64 bit float (like `double`), four channels (RGBA image)
+    Cv64FC4 = 30,
+}
+
+impl CvType {
+    /// Builds the type from a depth (`CV_8U` is 0, `CV_64F` is 6) and a number
+    /// of channels. Returns `None` if there is no such `CvType`.
+    pub fn from_depth_and_channels(depth: i32, channels: i32) -> Option<CvType> {
+        if depth < 0 || depth > 6 || channels < 1 {
+            return None;
+        }
+        num::FromPrimitive::from_i32(depth + ((channels - 1) << 3))
+    }
+
+    /// Returns the depth of this type, i.e. `CV_MAT_DEPTH`.
+    pub fn depth(&self) -> i32 {
+        (*self as i32) & 7
+    }
+
+    /// Returns the number of channels of this type, i.e. `CV_MAT_CN`.
+    pub fn channels(&self) -> i32 {
+        ((*self as i32) >> 3) + 1
+    }
+}
+
+/// This struct represents a rotated (i.e. not up-right) rectangle. Each
+/// rectangle is specified by the center point (mass center), length of each
+/// side (represented by `Size2f`) and the rotation angle in degrees.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(C)]
+pub struct RotatedRect {
+    /// The rectangle mass center
+    pub center: Point2f,
+    /// Width and height of the rectangle
+    pub size: Size2f,
+    /// The clockwise rotation angle in degrees
+    pub angle: f32,
+}
+
+impl RotatedRect {
+    /// Creates a new `RotatedRect`.
+    pub fn new(center: Point2f, size: Size2f, angle: f32) -> Self {
+        RotatedRect {
+            center: center,
+            size: size,
+            angle: angle,
+        }
+    }
+
+    /// Return 4 vertices of the rectangle.
+    pub fn points(&self) -> [Point2f; 4] {
+        let angle = self.angle * ::std::f32::consts::PI / 180.0;
+
+        let b = angle.cos() * 0.5;
+        let a = angle.sin() * 0.5;
+
+        let mut pts: [Point2f; 4] = [Point2f::default(); 4];
+        pts[0].x = self.center.x - a * self.size.height - b * self.size.width;
+        pts[0].y = self.center.y + b * self.size.height - a * self.size.width;
+        pts[1].x = self.center.x + a * self.size.height - b * self.size.width;
+        pts[1].y = self.center.y - b * self.size.height - a * self.size.width;
+
+        pts[2].x = 2.0 * self.center.x - pts[0].x;
+        pts[2].y = 2.0 * self.center.y - pts[0].y;
+        pts[3].x = 2.0 * self.center.x - pts[1].x;
+        pts[3].y = 2.0 * self.center.y - pts[1].y;
+        pts
+    }
+
+    /// Return the minimal up-right rectangle containing the rotated rectangle
+    pub fn bounding_rect(&self) -> Rect {
+        let pt = self.points();
+        let x = pt.iter().map(|p| p.x).fold(0. / 0., f32::min).floor() as i32;
+        let y = pt.iter().map(|p| p.y).fold(0. / 0., f32::min).floor() as i32;
+
+        let width = pt.iter().map(|p| p.x).fold(0. / 0., f32::max).ceil() as i32 - x + 1;
+        let height = pt.iter().map(|p| p.y).fold(0. / 0., f32::max).ceil() as i32 - y + 1;
+        Rect::new(x, y, width, height)
+    }
+}
+
+// =============================================================================
+// SparseMat
+// =============================================================================
+/// Opaque data struct for C bindings
+#[derive(Clone, Copy, Debug)]
+pub enum CSparseMat {}
+
+extern "C" {
+    fn cv_sparse_mat_new(ndims: c_int, sizes: *const c_int, t: c_int, error: *mut CError) -> *mut CSparseMat;
+    fn cv_sparse_mat_from_mat(cmat: *const CMat, error: *mut CError) -> *mut CSparseMat;
+    fn cv_sparse_mat_clone(csm: *const CSparseMat, error: *mut CError) -> *mut CSparseMat;
+    fn cv_sparse_mat_drop(csm: *mut CSparseMat);
+    fn cv_sparse_mat_dims(csm: *const CSparseMat) -> c_int;
+    fn cv_sparse_mat_size(csm: *const CSparseMat, i: c_int) -> c_int;
+    fn cv_sparse_mat_type(csm: *const CSparseMat) -> c_int;
+    fn cv_sparse_mat_nzcount(csm: *const CSparseMat) -> usize;
+    fn cv_sparse_mat_ptr(csm: *mut CSparseMat, idx: *const c_int, create: bool, error: *mut CError) -> *mut c_uchar;
+    fn cv_sparse_mat_erase(csm: *mut CSparseMat, idx: *const c_int, error: *mut CError);
+    fn cv_sparse_mat_nonzeros(csm: *const CSparseMat, indices: *mut c_int, values: *mut c_uchar);
+    fn cv_sparse_mat_to_dense(csm: *const CSparseMat, cdst: *mut CMat, error: *mut CError);
+}
+
+/// Like [cv_try_new_mat](fn.cv_try_new_mat.html), for `SparseMat`.
+fn cv_try_new_sparse_mat<F>(f: F) -> Result<SparseMat, Error>
+where
+    F: FnOnce(*mut CError) -> *mut CSparseMat,
+{
+    let mut error = CError::default();
+    let mat = SparseMat { inner: f(&mut error) };
+    error.into_result().map(|_| mat)
+}
+
+/// N-dimensional sparse array that only stores its non-zero elements, see
+/// `cv::SparseMat`. This is useful for histograms with many bins, most of
+/// which are empty.
+#[derive(Debug)]
+pub struct SparseMat {
+    /// Pointer to the actual C/C++ data structure
+    pub inner: *mut CSparseMat,
+}
+
+unsafe impl Send for SparseMat {}
+
+impl SparseMat {
+    /// Creates an empty sparse array with the given dimensions.
+    pub fn new(sizes: &[i32], cv_type: CvType) -> Result<SparseMat, Error> {
+        cv_try_new_sparse_mat(|e| unsafe { cv_sparse_mat_new(sizes.len() as c_int, sizes.as_ptr(), cv_type as c_int, e) })
+    }
+
+    /// Creates a sparse array from the non-zero elements of `mat`.
+    pub fn from_mat(mat: &Mat) -> Result<SparseMat, Error> {
+        cv_try_new_sparse_mat(|e| unsafe { cv_sparse_mat_from_mat(mat.inner, e) })
+    }
+
+    /// Deep copy of the array, see `cv::SparseMat::clone`.
+    pub fn try_clone(&self) -> Result<SparseMat, Error> {
+        cv_try_new_sparse_mat(|e| unsafe { cv_sparse_mat_clone(self.inner, e) })
+    }
+
+    /// Returns the number of dimensions.
+    pub fn dims(&self) -> i32 {
+        unsafe { cv_sparse_mat_dims(self.inner) }
+    }
+
+    /// Returns the size of each dimension.
+    pub fn sizes(&self) -> Vec<i32> {
+        (0..self.dims()).map(|i| unsafe { cv_sparse_mat_size(self.inner, i) }).collect()
+    }
+
+    /// Returns the type of the elements.
+    pub fn cv_type(&self) -> Result<CvType, Error> {
+        let t = unsafe { cv_sparse_mat_type(self.inner) };
+        num::FromPrimitive::from_i32(t).ok_or(CvError::EnumFromPrimitiveConversionError { value: t }.into())
+    }
+
+    /// Returns the number of stored (non-zero) elements.
+    pub fn nz_count(&self) -> usize {
+        unsafe { cv_sparse_mat_nzcount(self.inner) }
+    }
+
+    /// Returns the element at `idx`, or zero if it isn't stored.
+    pub fn get<T: DataType>(&self, idx: &[i32]) -> Result<T, Error> {
+        self.check_index::<T>(idx)?;
+        let ptr = cv_try(|e| unsafe { cv_sparse_mat_ptr(self.inner, idx.as_ptr(), false, e) })?;
+        if ptr.is_null() {
+            // `DataType` types are plain numbers, for which all zero bits is 0.
+            Ok(unsafe { mem::zeroed() })
+        } else {
+            Ok(unsafe { *(ptr as *const T) })
+        }
+    }
+
+    /// Stores `value` at `idx`.
+    pub fn set<T: DataType>(&mut self, idx: &[i32], value: T) -> Result<(), Error> {
+        self.check_index::<T>(idx)?;
+        let ptr = cv_try(|e| unsafe { cv_sparse_mat_ptr(self.inner, idx.as_ptr(), true, e) })?;
+        unsafe { *(ptr as *mut T) = value };
+        Ok(())
+    }
+
+    /// Removes the element at `idx`, which then reads as zero.
+    pub fn remove(&mut self, idx: &[i32]) -> Result<(), Error> {
+        let dims = self.dims() as usize;
+        if idx.len() != dims {
+            return Err(CvError::DimensionMismatch {
+                dims: dims,
+                indices: idx.len(),
+            }.into());
+        }
+        cv_try(|e| unsafe { cv_sparse_mat_erase(self.inner, idx.as_ptr(), e) })
+    }
+
+    /// Returns the stored elements with their indices, in no particular order.
+    pub fn nonzeros<T: DataType>(&self) -> Result<::std::vec::IntoIter<(Vec<i32>, T)>, Error> {
+        self.check_type::<T>()?;
+        let dims = self.dims() as usize;
+        let count = self.nz_count();
+        let mut indices = vec![0; count * dims];
+        let mut values = Vec::<T>::with_capacity(count);
+        unsafe {
+            cv_sparse_mat_nonzeros(self.inner, indices.as_mut_ptr(), values.as_mut_ptr() as *mut c_uchar);
+            values.set_len(count);
+        }
+        let nodes = indices
+            .chunks(dims.max(1))
+            .map(|idx| idx.to_vec())
+            .zip(values)
+            .collect::<Vec<_>>();
+        Ok(nodes.into_iter())
+    }
+
+    /// Converts to a dense `Mat` of the same dimensions.
+    pub fn to_dense(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_sparse_mat_to_dense(self.inner, m, e) })
+    }
+
+    fn check_type<T: DataType>(&self) -> Result<(), Error> {
+        let cv_type = self.cv_type()?;
+        if T::DEPTH != cv_type.depth() || T::CHANNELS != cv_type.channels() {
+            return Err(CvError::ElementTypeMismatch {
+                mat_depth: cv_type.depth(),
+                mat_channels: cv_type.channels(),
+                depth: T::DEPTH,
+                channels: T::CHANNELS,
+            }.into());
+        }
+        Ok(())
+    }
+
+    fn check_index<T: DataType>(&self, idx: &[i32]) -> Result<(), Error> {
+        self.check_type::<T>()?;
+        let sizes = self.sizes();
+        if idx.len() != sizes.len() {
+            return Err(CvError::DimensionMismatch {
+                dims: sizes.len(),
+                indices: idx.len(),
+            }.into());
+        }
+        for (&index, &size) in idx.iter().zip(sizes.iter()) {
+            if index < 0 || index >= size {
+                return Err(CvError::IndexOutOfRange {
+                    index: index as usize,
+                    bound: size as usize,
+                }.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SparseMat {
+    fn drop(&mut self) {
+        unsafe {
+            cv_sparse_mat_drop(self.inner);
+        }
+    }
+}
+
+impl Clone for SparseMat {
+    /// Like [try_clone](struct.SparseMat.html#method.try_clone).
+    ///
+    /// # Panics
+    ///
+    /// Panics if OpenCV fails to copy the array.
+    fn clone(&self) -> SparseMat {
+        self.try_clone().expect("failed to clone SparseMat")
+    }
+}
+
+// =============================================================================
+// core array
+// =============================================================================
+extern "C" {
+    fn cv_in_range(cmat: *const CMat, lowerb: Scalar, upperb: Scalar, dst: *mut CMat, error: *mut CError);
+    fn cv_min_max_loc(
+        cmat: *const CMat,
+        min: *mut f64,
+        max: *mut f64,
+        min_loc: *mut Point2i,
+        max_loc: *mut Point2i,
+        cmask: *const CMat,
+        error: *mut CError,
+    );
+    fn cv_mix_channels(
+        srcs: *const *const CMat,
+        nsrcs: usize,
+        dsts: *const *const CMat,
+        ndsts: usize,
+        from_to: *const c_int,
+        npairs: usize,
+        error: *mut CError,
+    );
+    fn cv_split(src: *const CMat, dsts: *const *mut CMat, ndsts: usize, error: *mut CError);
+    fn cv_merge(srcs: *const *const CMat, nsrcs: usize, dst: *mut CMat, error: *mut CError);
+    fn cv_extract_channel(src: *const CMat, dst: *mut CMat, coi: c_int, error: *mut CError);
+    fn cv_insert_channel(src: *const CMat, dst: *mut CMat, coi: c_int, error: *mut CError);
+    fn cv_normalize(
+        csrc: *const CMat,
+        cdst: *mut CMat,
+        alpha: c_double,
+        beta: c_double,
+        norm_type: c_int,
+        error: *mut CError,
+    );
+
+    fn cv_bitwise_and(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_bitwise_not(src: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_bitwise_or(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_bitwise_xor(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_count_non_zero(src: *const CMat, error: *mut CError) -> i32;
+    fn cv_sum(src: *const CMat, error: *mut CError) -> Scalar;
+    fn cv_mean(src: *const CMat, mask: *const CMat, error: *mut CError) -> Scalar;
+    fn cv_mean_std_dev(
+        src: *const CMat,
+        mean: *mut Scalar,
+        stddev: *mut Scalar,
+        mask: *const CMat,
+        error: *mut CError,
+    );
+    fn cv_norm(src: *const CMat, norm_type: c_int, mask: *const CMat, error: *mut CError) -> c_double;
+    fn cv_norm_between(src1: *const CMat, src2: *const CMat, norm_type: c_int, error: *mut CError) -> c_double;
+    fn cv_reduce(src: *const CMat, dst: *mut CMat, dim: c_int, rtype: c_int, dtype: c_int, error: *mut CError);
+
+    fn cv_add(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_add_scalar(src: *const CMat, value: Scalar, dst: *mut CMat, error: *mut CError);
+    fn cv_subtract(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_subtract_scalar(src: *const CMat, value: Scalar, dst: *mut CMat, error: *mut CError);
+    fn cv_multiply(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_multiply_scalar(src: *const CMat, value: Scalar, dst: *mut CMat, error: *mut CError);
+    fn cv_divide(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_divide_scalar(src: *const CMat, value: Scalar, dst: *mut CMat, error: *mut CError);
+    fn cv_add_weighted(
+        src1: *const CMat,
+        alpha: c_double,
+        src2: *const CMat,
+        beta: c_double,
+        gamma: c_double,
+        dst: *mut CMat,
+        error: *mut CError,
+    );
+    fn cv_absdiff(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_scale_add(src1: *const CMat, alpha: c_double, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_mat_convert_to(
+        src: *const CMat,
+        dst: *mut CMat,
+        rtype: c_int,
+        alpha: c_double,
+        beta: c_double,
+        error: *mut CError,
+    );
+    fn cv_transpose(src: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_repeat(src: *const CMat, ny: c_int, nx: c_int, dst: *mut CMat, error: *mut CError);
+    fn cv_hconcat(srcs: *const *const CMat, nsrcs: usize, dst: *mut CMat, error: *mut CError);
+    fn cv_vconcat(srcs: *const *const CMat, nsrcs: usize, dst: *mut CMat, error: *mut CError);
+    fn cv_rotate(src: *const CMat, dst: *mut CMat, code: c_int, error: *mut CError);
+}
+
+/// Normalization type. Please refer to [OpenCV's
+/// documentation](http://docs.cv.org/trunk/d2/de8/group__core__array.html).
+#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
+pub enum NormTypes {
+    /// Normalized using `max`
+    NormInf = 1,
+    /// Normalized using L1 distance
+    NormL1 = 2,
+    /// Normalized using L2 distance
+    NormL2 = 4,
+    /// Normalized using L2 sqr distance
+    NormL2Sqr = 5,
     /// Normalized using hamming distance
     NormHamming = 6,
     /// Normalized using hamming2 distance
@@ -814,14 +2893,27 @@ pub enum NormTypes {
     NormMinMax = 32,
 }
 
+/// Reduction operation used in [reduce](struct.Mat.html#method.reduce).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReduceTypes {
+    /// The sum over all rows/columns
+    Sum = 0,
+    /// The mean vector of all rows/columns
+    Avg = 1,
+    /// The maximum (column/row-wise) of all rows/columns
+    Max = 2,
+    /// The minimum (column/row-wise) of all rows/columns
+    Min = 3,
+}
+
 impl Mat {
     /// Checks if Mat elements lie between the elements of two other arrays
     /// (lowerb and upperb). The output Mat has the same size as `self` and
     /// CV_8U type.
-    pub fn in_range(&self, lowerb: Scalar, upperb: Scalar) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_in_range(self.inner, lowerb, upperb, m) }
-        Mat::from_raw(m)
+    pub fn in_range<S: Into<Scalar>>(&self, lowerb: S, upperb: S) -> Result<Mat, Error> {
+        let lowerb = lowerb.into();
+        let upperb = upperb.into();
+        cv_try_mat(|m, e| unsafe { cv_in_range(self.inner, lowerb, upperb, m, e) })
     }
 
     /// Finds the global minimum and maximum in an array.
@@ -835,12 +2927,12 @@ impl Mat {
     /// Mat::reshape first to reinterpret the array as single-channel. Or you
     /// may extract the particular channel using either extractImageCOI , or
     /// mixChannels, or split.
-    pub fn min_max_loc(&self, mask: Mat) -> (f64, f64, Point2i, Point2i) {
+    pub fn min_max_loc(&self, mask: Mat) -> Result<(f64, f64, Point2i, Point2i), Error> {
         let mut min = 0.0;
         let mut max = 0.0;
         let mut min_loc = Point2i::new(0, 0);
         let mut max_loc = Point2i::new(0, 0);
-        unsafe {
+        cv_try(|e| unsafe {
             cv_min_max_loc(
                 self.inner,
                 &mut min,
@@ -848,60 +2940,772 @@ impl Mat {
                 &mut min_loc,
                 &mut max_loc,
                 mask.inner,
+                e,
             )
+        })?;
+        Ok((min, max, min_loc, max_loc))
+    }
+
+    /// Copies channels from `srcs` to channels of `dsts`. Each `(from, to)`
+    /// pair copies channel `from` to channel `to`, where the channels of all
+    /// the sources (and of all the destinations) are numbered one after the
+    /// other, as in `cv::mixChannels`.
+    ///
+    /// The destinations must already be allocated with the size and depth of
+    /// the sources, since their number of channels can't be told from
+    /// `from_to`; an empty destination is an error.
+    ///
+    /// ```rust,ignore
+    /// // BGRA -> RGB + alpha
+    /// let mut rgb = Mat::with_size(bgra.rows, bgra.cols, CvType::Cv8UC3 as i32)?;
+    /// let mut alpha = Mat::with_size(bgra.rows, bgra.cols, CvType::Cv8UC1 as i32)?;
+    /// Mat::mix_channels(&[&bgra], &mut [&mut rgb, &mut alpha], &[(0, 2), (1, 1), (2, 0), (3, 3)])?;
+    /// ```
+    pub fn mix_channels(srcs: &[&Mat], dsts: &mut [&mut Mat], from_to: &[(usize, usize)]) -> Result<(), Error> {
+        if srcs.is_empty() {
+            return Err(CvError::EmptyInput.into());
+        }
+        let nsrc_channels = srcs.iter().map(|m| m.channels as usize).sum::<usize>();
+
+        let mut offset = 0;
+        for dst in dsts.iter() {
+            if !dst.is_valid() {
+                return Err(CvError::EmptyMat.into());
+            }
+            offset += dst.channels as usize;
+        }
+
+        let mut pairs = Vec::with_capacity(from_to.len() * 2);
+        for &(from, to) in from_to {
+            if from >= nsrc_channels {
+                return Err(CvError::IndexOutOfRange { index: from, bound: nsrc_channels }.into());
+            }
+            if to >= offset {
+                return Err(CvError::IndexOutOfRange { index: to, bound: offset }.into());
+            }
+            pairs.push(from as c_int);
+            pairs.push(to as c_int);
         }
-        (min, max, min_loc, max_loc)
+
+        let srcs = srcs.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        let dst_ptrs = dsts.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        cv_try(|e| unsafe {
+            cv_mix_channels(
+                srcs.as_ptr(),
+                srcs.len(),
+                dst_ptrs.as_ptr(),
+                dst_ptrs.len(),
+                pairs.as_ptr(),
+                from_to.len(),
+                e,
+            )
+        })
     }
 
-    /// Copy specified channels from `self` to the specified channels of output
-    /// `Mat`.
-    // TODO(benzh) Avoid using raw pointers but rather take a vec for `from_to`?
-    // The usage (self.depth) here is buggy, it should actually be the type!
-    pub fn mix_channels(&self, nsrcs: isize, ndsts: isize, from_to: *const i32, npairs: isize) -> Mat {
-        let m = Mat::with_size(self.rows, self.cols, self.depth);
-        unsafe {
-            cv_mix_channels(self.inner, nsrcs, m.inner, ndsts, from_to, npairs);
+    /// Splits the matrix into single-channel matrices, one per channel.
+    pub fn split(&self) -> Result<Vec<Mat>, Error> {
+        let channels = (0..self.channels).map(|_| CMat::new()).collect::<Vec<_>>();
+        let result = cv_try(|e| unsafe { cv_split(self.inner, channels.as_ptr(), channels.len(), e) });
+        let channels = channels.into_iter().map(Mat::from_raw).collect::<Vec<_>>();
+        result.map(|_| channels)
+    }
+
+    /// Merges single-channel (or multi-channel) matrices of the same size and
+    /// depth into one matrix, see `cv::merge`.
+    pub fn merge(mats: &[&Mat]) -> Result<Mat, Error> {
+        if mats.is_empty() {
+            return Err(CvError::EmptyInput.into());
         }
-        m
+        let srcs = mats.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        cv_try_mat(|m, e| unsafe { cv_merge(srcs.as_ptr(), srcs.len(), m, e) })
+    }
+
+    /// Returns the channel `i` as a single-channel matrix.
+    pub fn extract_channel(&self, i: usize) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_extract_channel(self.inner, m, i as c_int, e) })
+    }
+
+    /// Replaces the channel `i` with the single-channel matrix `src`, which
+    /// must have the same size and depth.
+    pub fn insert_channel(&mut self, src: &Mat, i: usize) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_insert_channel(src.inner, self.inner, i as c_int, e) })
     }
 
     /// Normalize the Mat according to the normalization type.
-    pub fn normalize(&self, alpha: f64, beta: f64, t: NormTypes) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_normalize(self.inner, m, alpha, beta, t as i32) }
-        Mat::from_raw(m)
+    pub fn normalize(&self, alpha: f64, beta: f64, t: NormTypes) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_normalize(self.inner, m, alpha, beta, t as i32, e) })
     }
 
     /// Computes bitwise conjunction between two Mat
-    pub fn and(&self, another: &Mat) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_bitwise_and(self.inner, another.inner, m) }
-        Mat::from_raw(m)
+    pub fn and(&self, another: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_bitwise_and(self.inner, another.inner, m, e) })
     }
 
     /// Computes bitwise disjunction between two Mat
-    pub fn or(&self, another: &Mat) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_bitwise_or(self.inner, another.inner, m) }
-        Mat::from_raw(m)
+    pub fn or(&self, another: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_bitwise_or(self.inner, another.inner, m, e) })
     }
 
     /// Computes bitwise "exclusive or" between two Mat
-    pub fn xor(&self, another: &Mat) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_bitwise_xor(self.inner, another.inner, m) }
-        Mat::from_raw(m)
+    pub fn xor(&self, another: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_bitwise_xor(self.inner, another.inner, m, e) })
     }
 
     /// Computes bitwise "exclusive or" between two Mat
-    pub fn not(&self) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_bitwise_not(self.inner, m) }
-        Mat::from_raw(m)
+    pub fn not(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_bitwise_not(self.inner, m, e) })
     }
 
     /// Counts non-zero array elements.
-    pub fn count_non_zero(&self) -> i32 {
-        unsafe { cv_count_non_zero(self.inner) }
+    pub fn count_non_zero(&self) -> Result<i32, Error> {
+        cv_try(|e| unsafe { cv_count_non_zero(self.inner, e) })
+    }
+
+    /// Calculates the sum of the elements, independently for each channel.
+    pub fn sum(&self) -> Result<Scalar, Error> {
+        cv_try(|e| unsafe { cv_sum(self.inner, e) })
+    }
+
+    /// Calculates the mean of the elements (selected by `mask` if given),
+    /// independently for each channel.
+    pub fn mean(&self, mask: Option<&Mat>) -> Result<Scalar, Error> {
+        let mask = mask.map_or(ptr::null(), |m| m.inner as *const CMat);
+        cv_try(|e| unsafe { cv_mean(self.inner, mask, e) })
+    }
+
+    /// Calculates the mean and the standard deviation of the elements
+    /// (selected by `mask` if given), independently for each channel.
+    pub fn mean_std_dev(&self, mask: Option<&Mat>) -> Result<(Scalar, Scalar), Error> {
+        let mask = mask.map_or(ptr::null(), |m| m.inner as *const CMat);
+        let mut mean = Scalar::default();
+        let mut stddev = Scalar::default();
+        cv_try(|e| unsafe { cv_mean_std_dev(self.inner, &mut mean, &mut stddev, mask, e) })?;
+        Ok((mean, stddev))
+    }
+
+    /// Calculates the absolute norm of the matrix (restricted to `mask` if
+    /// given).
+    pub fn norm(&self, norm_type: NormTypes, mask: Option<&Mat>) -> Result<f64, Error> {
+        let mask = mask.map_or(ptr::null(), |m| m.inner as *const CMat);
+        cv_try(|e| unsafe { cv_norm(self.inner, norm_type as c_int, mask, e) })
+    }
+
+    /// Calculates the norm of the difference between `self` and `other`.
+    pub fn norm_between(&self, other: &Mat, norm_type: NormTypes) -> Result<f64, Error> {
+        cv_try(|e| unsafe { cv_norm_between(self.inner, other.inner, norm_type as c_int, e) })
+    }
+
+    /// Reduces the matrix to a single row (`dim` 0) or a single column (`dim`
+    /// 1). `dtype` is the depth of the output, `None` keeps the depth of
+    /// `self`; `Sum` and `Avg` usually need a wider type.
+    pub fn reduce(&self, dim: i32, rtype: ReduceTypes, dtype: Option<CvType>) -> Result<Mat, Error> {
+        let dtype = dtype.map_or(-1, |t| t as c_int);
+        cv_try_mat(|m, e| unsafe { cv_reduce(self.inner, m, dim, rtype as c_int, dtype, e) })
+    }
+}
+
+// =============================================================================
+// Arithmetic
+// =============================================================================
+impl Mat {
+    /// Computes the weighted sum `self * alpha + other * beta + gamma`, with
+    /// saturation to the type of `self`.
+    pub fn add_weighted(&self, alpha: f64, other: &Mat, beta: f64, gamma: f64) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_add_weighted(self.inner, alpha, other.inner, beta, gamma, m, e) })
+    }
+
+    /// Computes the per-element absolute difference between two `Mat`.
+    pub fn abs_diff(&self, other: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_absdiff(self.inner, other.inner, m, e) })
+    }
+
+    /// Computes `self * alpha + other`, see `cv::scaleAdd`.
+    pub fn scale_add(&self, alpha: f64, other: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_scale_add(self.inner, alpha, other.inner, m, e) })
+    }
+
+    /// Converts the matrix to another depth, computing `self * alpha + beta`
+    /// with saturation. Only the depth of `rtype` is used; the number of
+    /// channels stays the same.
+    pub fn convert_to(&self, rtype: CvType, alpha: f64, beta: f64) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_mat_convert_to(self.inner, m, rtype as c_int, alpha, beta, e) })
+    }
+
+    /// Transposes the matrix.
+    pub fn transpose(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_transpose(self.inner, m, e) })
+    }
+
+    /// Shortcut for [transpose](struct.Mat.html#method.transpose), like
+    /// `cv::Mat::t`.
+    pub fn t(&self) -> Result<Mat, Error> {
+        self.transpose()
+    }
+
+    /// Fills a new matrix with `ny` copies of `self` vertically and `nx`
+    /// copies horizontally.
+    pub fn repeat(&self, ny: i32, nx: i32) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_repeat(self.inner, ny, nx, m, e) })
+    }
+
+    /// Concatenates matrices with the same number of rows side by side.
+    pub fn hconcat(mats: &[&Mat]) -> Result<Mat, Error> {
+        let srcs = mats.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        cv_try_mat(|m, e| unsafe { cv_hconcat(srcs.as_ptr(), srcs.len(), m, e) })
+    }
+
+    /// Concatenates matrices with the same number of columns one below the
+    /// other.
+    pub fn vconcat(mats: &[&Mat]) -> Result<Mat, Error> {
+        let srcs = mats.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        cv_try_mat(|m, e| unsafe { cv_vconcat(srcs.as_ptr(), srcs.len(), m, e) })
+    }
+
+    /// Rotates the matrix by a multiple of 90 degrees. Unlike
+    /// [flip](struct.Mat.html#method.flip), the result is a new matrix since
+    /// its size may change.
+    pub fn rotate(&self, code: RotateFlags) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_rotate(self.inner, m, code as c_int, e) })
+    }
+}
+
+macro_rules! impl_arithmetic_op {
+    ($op:ident, $method:ident, $cv_mat:ident, $cv_scalar:ident, $doc:expr) => {
+        #[doc = $doc]
+        impl<'a, 'b> $op<&'b Mat> for &'a Mat {
+            type Output = Result<Mat, Error>;
+
+            fn $method(self, other: &'b Mat) -> Result<Mat, Error> {
+                cv_try_mat(|m, e| unsafe { $cv_mat(self.inner, other.inner, m, e) })
+            }
+        }
+
+        #[doc = $doc]
+        impl<'a> $op<Scalar> for &'a Mat {
+            type Output = Result<Mat, Error>;
+
+            fn $method(self, value: Scalar) -> Result<Mat, Error> {
+                cv_try_mat(|m, e| unsafe { $cv_scalar(self.inner, value, m, e) })
+            }
+        }
+    };
+}
+
+impl_arithmetic_op!(Add, add, cv_add, cv_add_scalar, "Per-element saturating sum, see `cv::add`.");
+impl_arithmetic_op!(
+    Sub,
+    sub,
+    cv_subtract,
+    cv_subtract_scalar,
+    "Per-element saturating difference, see `cv::subtract`."
+);
+impl_arithmetic_op!(
+    Mul,
+    mul,
+    cv_multiply,
+    cv_multiply_scalar,
+    "Per-element saturating product (not a matrix product), see `cv::multiply`."
+);
+impl_arithmetic_op!(
+    Div,
+    div,
+    cv_divide,
+    cv_divide_scalar,
+    "Per-element division, see `cv::divide`. Division by zero gives zero."
+);
+
+// =============================================================================
+// Comparison
+// =============================================================================
+extern "C" {
+    fn cv_compare(src1: *const CMat, src2: *const CMat, dst: *mut CMat, cmpop: c_int, error: *mut CError);
+    fn cv_min(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_max(src1: *const CMat, src2: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_check_range(
+        src: *const CMat,
+        pos: *mut Point2i,
+        min_val: c_double,
+        max_val: c_double,
+        error: *mut CError,
+    ) -> bool;
+}
+
+/// Comparison operation used in [compare](struct.Mat.html#method.compare).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CmpTypes {
+    /// `src1` is equal to `src2`
+    Eq = 0,
+    /// `src1` is greater than `src2`
+    Gt = 1,
+    /// `src1` is greater than or equal to `src2`
+    Ge = 2,
+    /// `src1` is less than `src2`
+    Lt = 3,
+    /// `src1` is less than or equal to `src2`
+    Le = 4,
+    /// `src1` is not equal to `src2`
+    Ne = 5,
+}
+
+impl Mat {
+    /// Compares the elements of two `Mat` of the same size and type. The
+    /// output is a `Cv8U` matrix with the channels of `self`, holding 255
+    /// where the comparison is true and 0 elsewhere.
+    pub fn compare(&self, other: &Mat, op: CmpTypes) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_compare(self.inner, other.inner, m, op as c_int, e) })
+    }
+
+    /// Computes the per-element minimum of two `Mat` of the same size and
+    /// type.
+    pub fn min(&self, other: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_min(self.inner, other.inner, m, e) })
+    }
+
+    /// Computes the per-element maximum of two `Mat` of the same size and
+    /// type.
+    pub fn max(&self, other: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_max(self.inner, other.inner, m, e) })
+    }
+
+    /// Checks that every element lies in `[min_val, max_val)` and, for
+    /// floating point matrices, isn't NaN or infinite. Returns `None` if they
+    /// all do, or the position of the first element that doesn't, with `x`
+    /// counting channels as well as columns.
+    pub fn check_range(&self, min_val: f64, max_val: f64) -> Result<Option<Point2i>, Error> {
+        let mut pos = Point2i::default();
+        let in_range = cv_try(|e| unsafe { cv_check_range(self.inner, &mut pos, min_val, max_val, e) })?;
+        Ok(if in_range { None } else { Some(pos) })
+    }
+
+    /// Returns true if `self` and `other` have the same size and type and the
+    /// norm of their difference is at most `tolerance`, e.g. 1e-6 with
+    /// `NormTypes::NormInf` to allow for rounding errors in each element.
+    pub fn approx_eq(&self, other: &Mat, tolerance: f64, norm_type: NormTypes) -> Result<bool, Error> {
+        if self.sizes() != other.sizes() || self.cv_type()? != other.cv_type()? {
+            return Ok(false);
+        }
+        if self.total() == 0 {
+            return Ok(true);
+        }
+        Ok(self.norm_between(other, norm_type)? <= tolerance)
+    }
+}
+
+// =============================================================================
+// Discrete transforms
+// =============================================================================
+extern "C" {
+    fn cv_dft(src: *const CMat, dst: *mut CMat, flags: c_int, nonzero_rows: c_int, error: *mut CError);
+    fn cv_dct(src: *const CMat, dst: *mut CMat, flags: c_int, error: *mut CError);
+    fn cv_mul_spectrums(
+        src1: *const CMat,
+        src2: *const CMat,
+        dst: *mut CMat,
+        flags: c_int,
+        conj: bool,
+        error: *mut CError,
+    );
+    fn cv_get_optimal_dft_size(size: c_int) -> c_int;
+    fn cv_copy_make_border(
+        src: *const CMat,
+        dst: *mut CMat,
+        top: c_int,
+        bottom: c_int,
+        left: c_int,
+        right: c_int,
+        border_type: c_int,
+        value: Scalar,
+        error: *mut CError,
+    );
+    fn cv_magnitude(x: *const CMat, y: *const CMat, dst: *mut CMat, error: *mut CError);
+    fn cv_phase(x: *const CMat, y: *const CMat, dst: *mut CMat, angle_in_degrees: bool, error: *mut CError);
+    fn cv_cart_to_polar(
+        x: *const CMat,
+        y: *const CMat,
+        magnitude: *mut CMat,
+        angle: *mut CMat,
+        angle_in_degrees: bool,
+        error: *mut CError,
+    );
+}
+
+bit_flags! {
+    /// Flags of [dft](struct.Mat.html#method.dft),
+    /// [dct](struct.Mat.html#method.dct) and
+    /// [mul_spectrums](struct.Mat.html#method.mul_spectrums), combined with
+    /// `|`.
+    pub struct DftFlags {
+        /// Forward transform of the whole matrix.
+        const NONE = 0;
+        /// Performs an inverse transform.
+        const INVERSE = 1;
+        /// Divides the result by the number of elements.
+        const SCALE = 2;
+        /// Transforms every row independently.
+        const ROWS = 4;
+        /// Returns the full complex (2-channel) result of a real forward
+        /// transform instead of the packed CCS format.
+        const COMPLEX_OUTPUT = 16;
+        /// Returns a real (1-channel) result from an inverse transform of a
+        /// complex conjugate-symmetric input.
+        const REAL_OUTPUT = 32;
+        /// Treats a 2-channel input as complex.
+        const COMPLEX_INPUT = 64;
+    }
+}
+
+/// How [copy_make_border](struct.Mat.html#method.copy_make_border)
+/// extrapolates pixels outside of the image.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BorderTypes {
+    /// `iiiiii|abcdefgh|iiiiiii` with some specified `i`
+    Constant = 0,
+    /// `aaaaaa|abcdefgh|hhhhhhh`
+    Replicate = 1,
+    /// `fedcba|abcdefgh|hgfedcb`
+    Reflect = 2,
+    /// `cdefgh|abcdefgh|abcdefg`
+    Wrap = 3,
+    /// `gfedcb|abcdefgh|gfedcba`, OpenCV's default
+    Reflect101 = 4,
+}
+
+/// Returns the smallest size greater than or equal to `size` for which the
+/// DFT is fast, i.e. a product of 2, 3 and 5. Returns -1 if there's none.
+pub fn get_optimal_dft_size(size: i32) -> i32 {
+    unsafe { cv_get_optimal_dft_size(size) }
+}
+
+impl Mat {
+    /// Performs a forward or inverse discrete Fourier transform of a
+    /// floating point matrix, see `cv::dft`. Only the first `nonzero_rows`
+    /// rows of the input (or of the output with `DftFlags::INVERSE`) are
+    /// assumed to hold non-zero values; pass 0 to use every row.
+    pub fn dft(&self, flags: DftFlags, nonzero_rows: i32) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_dft(self.inner, m, flags.0, nonzero_rows, e) })
+    }
+
+    /// Performs an inverse discrete Fourier transform, i.e.
+    /// [dft](#method.dft) with `DftFlags::INVERSE`. The result isn't scaled
+    /// unless `DftFlags::SCALE` is set.
+    pub fn idft(&self, flags: DftFlags, nonzero_rows: i32) -> Result<Mat, Error> {
+        self.dft(flags | DftFlags::INVERSE, nonzero_rows)
+    }
+
+    /// Performs a forward or inverse discrete cosine transform of a
+    /// floating point matrix with an even size, see `cv::dct`. Only
+    /// `DftFlags::INVERSE` and `DftFlags::ROWS` are supported.
+    pub fn dct(&self, flags: DftFlags) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_dct(self.inner, m, flags.0, e) })
+    }
+
+    /// Performs an inverse discrete cosine transform, i.e. [dct](#method.dct)
+    /// with `DftFlags::INVERSE`.
+    pub fn idct(&self, flags: DftFlags) -> Result<Mat, Error> {
+        self.dct(flags | DftFlags::INVERSE)
+    }
+
+    /// Performs the per-element multiplication of two Fourier spectrums, in
+    /// the packed or complex format returned by [dft](#method.dft). With
+    /// `conj` the second spectrum is conjugated first, which gives the cross
+    /// power spectrum used by phase correlation. Only `DftFlags::ROWS` is
+    /// supported.
+    pub fn mul_spectrums(&self, other: &Mat, flags: DftFlags, conj: bool) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_mul_spectrums(self.inner, other.inner, m, flags.0, conj, e) })
+    }
+
+    /// Returns a copy of the matrix surrounded by a border of the given
+    /// widths, e.g. to pad an image to
+    /// [get_optimal_dft_size](fn.get_optimal_dft_size.html). `value` is only
+    /// used with `BorderTypes::Constant`.
+    pub fn copy_make_border(
+        &self,
+        top: i32,
+        bottom: i32,
+        left: i32,
+        right: i32,
+        border_type: BorderTypes,
+        value: Scalar,
+    ) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe {
+            cv_copy_make_border(self.inner, m, top, bottom, left, right, border_type as c_int, value, e)
+        })
+    }
+
+    /// Computes the per-element magnitude `sqrt(x^2 + y^2)` of two floating
+    /// point matrices, e.g. the channels of a complex spectrum.
+    pub fn magnitude(x: &Mat, y: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_magnitude(x.inner, y.inner, m, e) })
+    }
+
+    /// Computes the per-element angle `atan2(y, x)` of two floating point
+    /// matrices, in radians (0 to 2π) or degrees (0 to 360).
+    pub fn phase(x: &Mat, y: &Mat, angle_in_degrees: bool) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_phase(x.inner, y.inner, m, angle_in_degrees, e) })
+    }
+
+    /// Computes both the [magnitude](#method.magnitude) and the
+    /// [phase](#method.phase) of two floating point matrices, returned as
+    /// `(magnitude, angle)`.
+    pub fn cart_to_polar(x: &Mat, y: &Mat, angle_in_degrees: bool) -> Result<(Mat, Mat), Error> {
+        let (magnitude, angle) = (CMat::new(), CMat::new());
+        let result = cv_try(|e| unsafe { cv_cart_to_polar(x.inner, y.inner, magnitude, angle, angle_in_degrees, e) });
+        let (magnitude, angle) = (Mat::from_raw(magnitude), Mat::from_raw(angle));
+        result.map(|_| (magnitude, angle))
+    }
+}
+
+// =============================================================================
+// Random numbers
+// =============================================================================
+enum CRng {}
+
+extern "C" {
+    fn cv_rng_new(state: u64, error: *mut CError) -> *mut CRng;
+    fn cv_rng_drop(rng: *mut CRng);
+    fn cv_rng_state(rng: *const CRng) -> u64;
+    fn cv_rng_next(rng: *mut CRng, error: *mut CError) -> u32;
+    fn cv_rng_uniform_int(rng: *mut CRng, a: c_int, b: c_int, error: *mut CError) -> c_int;
+    fn cv_rng_uniform_double(rng: *mut CRng, a: c_double, b: c_double, error: *mut CError) -> c_double;
+    fn cv_rng_gaussian(rng: *mut CRng, sigma: c_double, error: *mut CError) -> c_double;
+    fn cv_rng_fill(
+        rng: *mut CRng,
+        mat: *mut CMat,
+        dist_type: c_int,
+        a: Scalar,
+        b: Scalar,
+        saturate_range: bool,
+        error: *mut CError,
+    );
+    fn cv_set_rng_seed(seed: c_int);
+    fn cv_randu(mat: *mut CMat, low: Scalar, high: Scalar, error: *mut CError);
+    fn cv_randn(mat: *mut CMat, mean: Scalar, stddev: Scalar, error: *mut CError);
+    fn cv_rand_shuffle(mat: *mut CMat, iter_factor: c_double, rng: *mut CRng, error: *mut CError);
+}
+
+/// Distribution used by [Rng::fill](struct.Rng.html#method.fill).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RngDistribution {
+    /// Uniform distribution in `[a, b)`
+    Uniform = 0,
+    /// Normal distribution with mean `a` and standard deviation `b`
+    Normal = 1,
+}
+
+/// Random number generator wrapping `cv::RNG`, a multiply-with-carry
+/// generator. The same seed always produces the same sequence, which makes
+/// it suitable for reproducible test inputs but not for cryptography.
+#[derive(Debug)]
+pub struct Rng {
+    inner: *mut CRng,
+}
+
+/// We can safely send the generator (a mutable pointer) to a different thread
+unsafe impl Send for Rng {}
+
+impl Rng {
+    /// Creates a generator with the given seed; 0 is replaced by OpenCV's
+    /// default seed.
+    pub fn new(seed: u64) -> Result<Rng, Error> {
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_rng_new(seed, e) });
+        let rng = Rng { inner: inner };
+        result.map(|_| rng)
+    }
+
+    /// Returns the current state, which can be passed to
+    /// [new](#method.new) to continue the sequence from here.
+    pub fn state(&self) -> u64 {
+        unsafe { cv_rng_state(self.inner) }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u32(&mut self) -> Result<u32, Error> {
+        cv_try(|e| unsafe { cv_rng_next(self.inner, e) })
+    }
+
+    /// Returns a uniformly distributed integer in `[a, b)`.
+    pub fn uniform_i32(&mut self, a: i32, b: i32) -> Result<i32, Error> {
+        cv_try(|e| unsafe { cv_rng_uniform_int(self.inner, a, b, e) })
+    }
+
+    /// Returns a uniformly distributed float in `[a, b)`.
+    pub fn uniform_f64(&mut self, a: f64, b: f64) -> Result<f64, Error> {
+        cv_try(|e| unsafe { cv_rng_uniform_double(self.inner, a, b, e) })
+    }
+
+    /// Returns a normally distributed float with mean 0 and standard
+    /// deviation `sigma`.
+    pub fn gaussian(&mut self, sigma: f64) -> Result<f64, Error> {
+        cv_try(|e| unsafe { cv_rng_gaussian(self.inner, sigma, e) })
+    }
+
+    /// Fills the allocated `mat` with random numbers, independently for each
+    /// channel. For `RngDistribution::Uniform`, `a` and `b` are the bounds and
+    /// `saturate_range` clamps them to the range of the `Mat` type first; for
+    /// `RngDistribution::Normal` they are the mean and the standard deviation.
+    pub fn fill<S: Into<Scalar>>(
+        &mut self,
+        mat: &mut Mat,
+        dist: RngDistribution,
+        a: S,
+        b: S,
+        saturate_range: bool,
+    ) -> Result<(), Error> {
+        let (a, b) = (a.into(), b.into());
+        cv_try(|e| unsafe { cv_rng_fill(self.inner, mat.inner, dist as c_int, a, b, saturate_range, e) })
+    }
+}
+
+impl Drop for Rng {
+    fn drop(&mut self) {
+        unsafe {
+            cv_rng_drop(self.inner);
+        }
+    }
+}
+
+/// Seeds the default generator of the current thread, used by
+/// [randu](struct.Mat.html#method.randu),
+/// [randn](struct.Mat.html#method.randn) and
+/// [rand_shuffle](struct.Mat.html#method.rand_shuffle) without an `Rng`.
+pub fn set_rng_seed(seed: i32) {
+    unsafe { cv_set_rng_seed(seed) }
+}
+
+impl Mat {
+    /// Fills the allocated matrix with uniformly distributed random numbers
+    /// in `[low, high)`, using the default generator of the thread (see
+    /// [set_rng_seed](fn.set_rng_seed.html)).
+    ///
+    /// ```rust,no_run
+    /// # use cv::*;
+    /// set_rng_seed(42);
+    /// let mut noise = Mat::zeros(480, 640, CvType::Cv8UC3 as i32).unwrap();
+    /// noise.randu(0.0, 256.0).unwrap();
+    /// ```
+    pub fn randu<S: Into<Scalar>>(&mut self, low: S, high: S) -> Result<(), Error> {
+        let (low, high) = (low.into(), high.into());
+        cv_try(|e| unsafe { cv_randu(self.inner, low, high, e) })
+    }
+
+    /// Fills the allocated matrix with normally distributed random numbers,
+    /// using the default generator of the thread. The values are saturated
+    /// to the type of the matrix.
+    pub fn randn<S: Into<Scalar>>(&mut self, mean: S, stddev: S) -> Result<(), Error> {
+        let (mean, stddev) = (mean.into(), stddev.into());
+        cv_try(|e| unsafe { cv_randn(self.inner, mean, stddev, e) })
+    }
+
+    /// Shuffles the elements in place by swapping `total() * iter_factor`
+    /// random pairs, drawn from `rng` or from the default generator of the
+    /// thread if `None`.
+    pub fn rand_shuffle(&mut self, iter_factor: f64, rng: Option<&mut Rng>) -> Result<(), Error> {
+        let rng = rng.map_or(ptr::null_mut(), |r| r.inner);
+        cv_try(|e| unsafe { cv_rand_shuffle(self.inner, iter_factor, rng, e) })
+    }
+}
+
+// =============================================================================
+// Formatting
+// =============================================================================
+extern "C" {
+    fn cv_mat_format(cmat: *const CMat, fmt: c_int, error: *mut CError) -> *mut c_char;
+}
+
+/// Output style of [format](struct.Mat.html#method.format), see
+/// `cv::Formatter::FormatType`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Formatter {
+    /// OpenCV's default style, `[1, 2;` with one row per line
+    Default = 0,
+
+    /// Matlab style
+    Matlab = 1,
+
+    /// Comma-separated values, one row per line
+    Csv = 2,
+
+    /// Nested Python lists, `[[1, 2],`
+    Python = 3,
+
+    /// NumPy array constructor, `array([[1, 2],` with the `dtype`
+    Numpy = 4,
+
+    /// C array initializer, `{1, 2, 3, 4}`
+    C = 5,
+}
+
+/// Number of rows and columns printed by `Display` before truncating.
+const DISPLAY_MAX_ELEMENTS: i32 = 16;
+
+impl Mat {
+    /// Formats all the elements of the matrix, see `cv::format`. Only
+    /// matrices with at most 2 dimensions can be formatted.
+    pub fn format(&self, formatter: Formatter) -> Result<String, Error> {
+        let mut s = ptr::null_mut();
+        let result = cv_try(|e| s = unsafe { cv_mat_format(self.inner, formatter as c_int, e) });
+        let string = take_string(s);
+        result.map(|_| string)
+    }
+
+    /// Returns a one-line summary of the matrix: its size, type and whether
+    /// it is continuous, e.g. `Mat 480x640 Cv8UC3 (continuous)`.
+    pub fn header(&self) -> String {
+        let sizes = if self.dims() > 2 {
+            self.sizes()
+        } else {
+            vec![self.rows, self.cols]
+        };
+        let sizes = sizes.iter().map(|s| s.to_string()).collect::<Vec<_>>().join("x");
+        let cv_type = match self.cv_type() {
+            Ok(t) => format!("{:?}", t),
+            Err(_) => format!("depth {} with {} channels", self.depth, self.channels),
+        };
+        let layout = if self.total() == 0 {
+            "empty"
+        } else if self.is_continuous() {
+            "continuous"
+        } else {
+            "not continuous"
+        };
+        format!("Mat {} {} ({})", sizes, cv_type, layout)
+    }
+}
+
+/// Prints the [header](struct.Mat.html#method.header) followed by the
+/// elements in the default style. Only the first 16 rows and columns are
+/// printed; use [format](struct.Mat.html#method.format) to get everything.
+/// If OpenCV fails to format the elements, the error is printed instead.
+impl fmt::Display for Mat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if self.total() == 0 || self.dims() > 2 {
+            return Ok(());
+        }
+
+        let rows = self.rows.min(DISPLAY_MAX_ELEMENTS);
+        let cols = self.cols.min(DISPLAY_MAX_ELEMENTS);
+        let truncated = rows < self.rows || cols < self.cols;
+        let content = if truncated {
+            self.roi(Rect::new(0, 0, cols, rows))
+                .and_then(|roi| roi.format(Formatter::Default))
+        } else {
+            self.format(Formatter::Default)
+        };
+        let content = match content {
+            Ok(content) => content,
+            // `fmt::Error` would make `to_string` panic, so print the failure.
+            Err(e) => return write!(f, "\n<failed to format the elements: {}>", e),
+        };
+        write!(f, "\n{}", content)?;
+        if truncated {
+            write!(
+                f,
+                "\n... showing {} of {} rows and {} of {} columns",
+                rows, self.rows, cols, self.cols
+            )?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,176 @@
+//! Linear algebra on floating point matrices, see [OpenCV
+//! core](https://docs.opencv.org/3.4/d2/de8/group__core__array.html).
+//!
+//! The functions work on single-channel `Cv32FC1` or `Cv64FC1` matrices, and
+//! `Mat::eye` / `Mat::ones` create the usual constant matrices:
+//!
+//! ```rust,no_run
+//! use cv::*;
+//! use cv::core::linalg::*;
+//!
+//! let a = Mat::from_slice_copy(2, 2, CvType::Cv64FC1, &[4.0f64, 7.0, 2.0, 6.0]).unwrap();
+//! let a_inv = invert(&a, DecompTypes::Lu).unwrap();
+//! let identity = matmul(&a, &a_inv).unwrap();
+//! ```
+
+use core::*;
+use errors::CvError;
+use failure::Error as Error;
+use std::os::raw::{c_double, c_int};
+use std::ptr;
+
+extern "C" {
+    fn cv_gemm(
+        src1: *const CMat,
+        src2: *const CMat,
+        alpha: c_double,
+        src3: *const CMat,
+        beta: c_double,
+        dst: *mut CMat,
+        flags: c_int,
+        error: *mut CError,
+    );
+    fn cv_invert(src: *const CMat, dst: *mut CMat, method: c_int, error: *mut CError) -> c_double;
+    fn cv_solve(src1: *const CMat, src2: *const CMat, dst: *mut CMat, method: c_int, error: *mut CError) -> bool;
+    fn cv_svd_compute(src: *const CMat, w: *mut CMat, u: *mut CMat, vt: *mut CMat, flags: c_int, error: *mut CError);
+    fn cv_eigen(src: *const CMat, eigenvalues: *mut CMat, eigenvectors: *mut CMat, error: *mut CError);
+    fn cv_eigen_non_symmetric(src: *const CMat, eigenvalues: *mut CMat, eigenvectors: *mut CMat, error: *mut CError);
+    fn cv_determinant(src: *const CMat, error: *mut CError) -> c_double;
+    fn cv_trace(src: *const CMat, error: *mut CError) -> Scalar;
+}
+
+/// Matrix decomposition used by [invert](fn.invert.html) and
+/// [solve](fn.solve.html).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DecompTypes {
+    /// Gaussian elimination with the optimal pivot element chosen.
+    Lu = 0,
+    /// Singular value decomposition; the system can be over-defined and/or
+    /// the matrix singular.
+    Svd = 1,
+    /// Eigenvalue decomposition; the matrix must be symmetric.
+    Eig = 2,
+    /// Cholesky factorization; the matrix must be symmetric and positive
+    /// definite.
+    Cholesky = 3,
+    /// QR factorization; the system can be over-defined and/or the matrix
+    /// singular.
+    Qr = 4,
+}
+
+bit_flags! {
+    /// Transposition flags of [gemm](fn.gemm.html), combined with `|`.
+    pub struct GemmFlags {
+        /// No transposition.
+        const NONE = 0;
+        /// Transposes `src1`.
+        const TRANSPOSE_SRC1 = 1;
+        /// Transposes `src2`.
+        const TRANSPOSE_SRC2 = 2;
+        /// Transposes `src3`.
+        const TRANSPOSE_SRC3 = 4;
+    }
+}
+
+/// Performs the generalized matrix multiplication `alpha * src1 * src2 + beta
+/// * src3`, with `src3` ignored if `None`. `flags` transposes the inputs.
+pub fn gemm(src1: &Mat, src2: &Mat, alpha: f64, src3: Option<&Mat>, beta: f64, flags: GemmFlags) -> Result<Mat, Error> {
+    let src3 = src3.map_or(ptr::null(), |m| m.inner as *const CMat);
+    cv_try_mat(|m, e| unsafe { cv_gemm(src1.inner, src2.inner, alpha, src3, beta, m, flags.0, e) })
+}
+
+/// Computes the matrix product `a * b`.
+pub fn matmul(a: &Mat, b: &Mat) -> Result<Mat, Error> {
+    gemm(a, b, 1.0, None, 0.0, GemmFlags::NONE)
+}
+
+/// Computes the inverse of `src`, or the pseudo-inverse with
+/// `DecompTypes::Svd`. Fails with `SingularMatrix` if `src` is singular,
+/// except with `DecompTypes::Svd` which always returns the pseudo-inverse.
+pub fn invert(src: &Mat, method: DecompTypes) -> Result<Mat, Error> {
+    let mut result = 0.0;
+    let dst = cv_try_mat(|m, e| unsafe { result = cv_invert(src.inner, m, method as c_int, e) })?;
+    if result == 0.0 && method != DecompTypes::Svd {
+        return Err(CvError::SingularMatrix.into());
+    }
+    Ok(dst)
+}
+
+/// Solves the linear system `src1 * x = src2` and returns `x`. With
+/// `DecompTypes::Svd` or `DecompTypes::Qr` the system can be over-defined,
+/// in which case the least squares solution is returned. Fails with
+/// `SingularMatrix` if `src1` is singular.
+pub fn solve(src1: &Mat, src2: &Mat, method: DecompTypes) -> Result<Mat, Error> {
+    let mut solved = false;
+    let dst = cv_try_mat(|m, e| unsafe { solved = cv_solve(src1.inner, src2.inner, m, method as c_int, e) })?;
+    if !solved {
+        return Err(CvError::SingularMatrix.into());
+    }
+    Ok(dst)
+}
+
+/// Singular value decomposition, see `cv::SVD`.
+#[derive(Debug, Clone, Copy)]
+pub struct SVD;
+
+impl SVD {
+    const NO_UV: c_int = 2;
+    const FULL_UV: c_int = 4;
+
+    fn compute_with_flags(src: &Mat, flags: c_int) -> Result<(Mat, Mat, Mat), Error> {
+        let (w, u, vt) = (CMat::new(), CMat::new(), CMat::new());
+        let result = cv_try(|e| unsafe { cv_svd_compute(src.inner, w, u, vt, flags, e) });
+        let (w, u, vt) = (Mat::from_raw(w), Mat::from_raw(u), Mat::from_raw(vt));
+        result.map(|_| (w, u, vt))
+    }
+
+    /// Decomposes `src` as `u * diag(w) * vt` and returns `(w, u, vt)`. The
+    /// singular values `w` are a column sorted in descending order; `u` and
+    /// `vt` are only as large as the rank allows, see
+    /// [compute_full](#method.compute_full).
+    pub fn compute(src: &Mat) -> Result<(Mat, Mat, Mat), Error> {
+        SVD::compute_with_flags(src, 0)
+    }
+
+    /// Like [compute](#method.compute), with square `u` and `vt` for
+    /// non-square matrices.
+    pub fn compute_full(src: &Mat) -> Result<(Mat, Mat, Mat), Error> {
+        SVD::compute_with_flags(src, SVD::FULL_UV)
+    }
+
+    /// Returns only the singular values, which is faster than
+    /// [compute](#method.compute).
+    pub fn values(src: &Mat) -> Result<Mat, Error> {
+        SVD::compute_with_flags(src, SVD::NO_UV).map(|(w, _, _)| w)
+    }
+}
+
+/// Computes the eigenvalues and eigenvectors of a symmetric matrix. Returns
+/// `(eigenvalues, eigenvectors)`: the eigenvalues are a column sorted in
+/// descending order and the eigenvectors the rows of the second matrix, in
+/// the same order.
+pub fn eigen(src: &Mat) -> Result<(Mat, Mat), Error> {
+    let (values, vectors) = (CMat::new(), CMat::new());
+    let result = cv_try(|e| unsafe { cv_eigen(src.inner, values, vectors, e) });
+    let (values, vectors) = (Mat::from_raw(values), Mat::from_raw(vectors));
+    result.map(|_| (values, vectors))
+}
+
+/// Like [eigen](fn.eigen.html), for a non-symmetric matrix. Only real
+/// eigenvalues are computed.
+pub fn eigen_non_symmetric(src: &Mat) -> Result<(Mat, Mat), Error> {
+    let (values, vectors) = (CMat::new(), CMat::new());
+    let result = cv_try(|e| unsafe { cv_eigen_non_symmetric(src.inner, values, vectors, e) });
+    let (values, vectors) = (Mat::from_raw(values), Mat::from_raw(vectors));
+    result.map(|_| (values, vectors))
+}
+
+/// Returns the determinant of a square matrix.
+pub fn determinant(src: &Mat) -> Result<f64, Error> {
+    cv_try(|e| unsafe { cv_determinant(src.inner, e) })
+}
+
+/// Returns the sum of the diagonal elements, independently for each channel.
+pub fn trace(src: &Mat) -> Result<Scalar, Error> {
+    cv_try(|e| unsafe { cv_trace(src.inner, e) })
+}
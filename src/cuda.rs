@@ -8,6 +8,7 @@ use super::errors::*;
 use super::objdetect::{CSvmDetector, HogParams, ObjectDetect, SvmDetector};
 use std::ffi::CString;
 use std::path::Path;
+use std::ptr;
 
 /// Opaque data struct for C/C++ cv::cuda::GpuMat bindings
 #[derive(Clone, Copy, Debug)]
@@ -32,9 +33,8 @@ pub struct GpuMat {
 extern "C" {
     fn cv_gpu_mat_default() -> *mut CGpuMat;
     fn cv_gpu_mat_drop(gpu_mat: *mut CGpuMat);
-    fn cv_gpu_mat_upload(gpu_mat: *mut CGpuMat, cpu_mat: *const CMat);
-    fn cv_mat_from_gpu_mat(gpu_mat: *mut CGpuMat) -> *mut CMat;
-    fn cv_gpu_mat_from_mat(mat: *mut CMat) -> *mut CGpuMat;
+    fn cv_gpu_mat_upload(gpu_mat: *mut CGpuMat, cpu_mat: *const CMat, error: *mut CError);
+    fn cv_gpu_mat_download(gpu_mat: *mut CGpuMat, cpu_mat: *mut CMat, error: *mut CError);
 }
 
 impl GpuMat {
@@ -58,11 +58,21 @@ impl GpuMat {
         }
     }
 
+    /// Creates a `GpuMat` holding a copy of `mat`.
+    pub fn from_mat(mat: &Mat) -> Result<GpuMat, Error> {
+        let mut gpu_mat = GpuMat::default();
+        gpu_mat.upload(mat)?;
+        Ok(gpu_mat)
+    }
+
     /// Uploads a normal `Mat`
-    pub fn upload(&mut self, mat: &Mat) {
-        unsafe {
-            cv_gpu_mat_upload(self.inner, mat.inner);
-        }
+    pub fn upload(&mut self, mat: &Mat) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_gpu_mat_upload(self.inner, mat.inner, e) })
+    }
+
+    /// Downloads the data to a normal `Mat`.
+    pub fn download(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_gpu_mat_download(self.inner, m, e) })
     }
 }
 
@@ -74,18 +84,6 @@ impl Drop for GpuMat {
     }
 }
 
-impl From<GpuMat> for Mat {
-    fn from(gpu_mat: GpuMat) -> Mat {
-        unsafe { Mat::from_raw(cv_mat_from_gpu_mat(gpu_mat.inner)) }
-    }
-}
-
-impl From<Mat> for GpuMat {
-    fn from(mat: Mat) -> GpuMat {
-        unsafe { GpuMat::from_raw(cv_gpu_mat_from_mat(mat.inner)) }
-    }
-}
-
 /// Opaque data struct for C bindings
 #[derive(Clone, Copy, Debug)]
 pub enum CGpuHog {}
@@ -113,15 +111,17 @@ extern "C" {
         block_stride: Size2i,
         cell_size: Size2i,
         nbins: i32,
+        error: *mut CError,
     ) -> *mut CGpuHog;
     fn cv_gpu_hog_drop(hog: *mut CGpuHog);
-    fn cv_gpu_hog_set_detector(hog: *mut CGpuHog, d: *const CSvmDetector);
-    fn cv_gpu_hog_detect(hog: *mut CGpuHog, mat: *mut CGpuMat, found: *mut CVec<Rect>);
+    fn cv_gpu_hog_set_detector(hog: *mut CGpuHog, d: *const CSvmDetector, error: *mut CError);
+    fn cv_gpu_hog_detect(hog: *mut CGpuHog, mat: *mut CGpuMat, found: *mut CVec<Rect>, error: *mut CError);
     fn cv_gpu_hog_detect_with_conf(
         hog: *mut CGpuHog,
         mat: *mut CGpuMat,
         found: *mut CVec<Rect>,
         conf: *mut CVec<c_double>,
+        error: *mut CError,
     );
 
     fn cv_gpu_hog_set_gamma_correction(hog: *mut CGpuHog, gamma: bool);
@@ -144,9 +144,9 @@ extern "C" {
 }
 
 impl ObjectDetect for GpuHog {
-    fn detect(&self, image: &Mat) -> Vec<(Rect, f64)> {
+    fn detect(&self, image: &Mat) -> Result<Vec<(Rect, f64)>, Error> {
         let mut gpu_mat = GpuMat::default();
-        gpu_mat.upload(image);
+        gpu_mat.upload(image)?;
         if self.return_score {
             self._detect_with_confidence(&gpu_mat)
         } else {
@@ -170,15 +170,23 @@ impl Default for GpuHog {
 
 impl GpuHog {
     /// Creates a new GpuHog detector.
-    pub fn new(win_size: Size2i, block_size: Size2i, block_stride: Size2i, cell_size: Size2i, nbins: i32) -> GpuHog {
-        let inner = unsafe { cv_gpu_hog_new(win_size, block_size, block_stride, cell_size, nbins) };
-        let mut params = HogParams::default();
-        GpuHog::update_params(inner, &mut params);
-        GpuHog {
+    pub fn new(
+        win_size: Size2i,
+        block_size: Size2i,
+        block_stride: Size2i,
+        cell_size: Size2i,
+        nbins: i32,
+    ) -> Result<GpuHog, Error> {
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_gpu_hog_new(win_size, block_size, block_stride, cell_size, nbins, e) });
+        let mut hog = GpuHog {
             inner: inner,
-            params: params,
+            params: HogParams::default(),
             return_score: false,
-        }
+        };
+        result?;
+        GpuHog::update_params(hog.inner, &mut hog.params);
+        Ok(hog)
     }
 
     /// Should or not return the detection score
@@ -187,16 +195,15 @@ impl GpuHog {
     }
 
     /// Creates a new GpuHog detector with parameters specified inside `params`.
-    pub fn with_params(params: HogParams) -> GpuHog {
-        let inner = unsafe {
-            cv_gpu_hog_new(
-                params.win_size,
-                params.block_size,
-                params.block_stride,
-                params.cell_size,
-                params.nbins,
-            )
-        };
+    pub fn with_params(params: HogParams) -> Result<GpuHog, Error> {
+        let mut hog = GpuHog::new(
+            params.win_size,
+            params.block_size,
+            params.block_stride,
+            params.cell_size,
+            params.nbins,
+        )?;
+        let inner = hog.inner;
         unsafe {
             cv_gpu_hog_set_gamma_correction(inner, params.gamma_correction);
             cv_gpu_hog_set_l2hys_threshold(inner, params.l2hys_threshold);
@@ -208,11 +215,8 @@ impl GpuHog {
             cv_gpu_hog_set_hit_threshold(inner, params.hit_threshold);
             cv_gpu_hog_set_group_threshold(inner, params.group_threshold);
         }
-        GpuHog {
-            inner: inner,
-            params: params,
-            return_score: false,
-        }
+        hog.params = params;
+        Ok(hog)
     }
 
     /// Updates the parameter inside this GpuHog detector.
@@ -228,34 +232,32 @@ impl GpuHog {
     }
 
     /// Sets the SVM detector.
-    pub fn set_svm_detector(&mut self, detector: SvmDetector) {
-        unsafe { cv_gpu_hog_set_detector(self.inner, detector.inner) }
+    pub fn set_svm_detector(&mut self, detector: SvmDetector) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_gpu_hog_set_detector(self.inner, detector.inner, e) })
     }
 
     /// Detects according to the SVM detector specified.
-    fn _detect(&self, mat: &GpuMat) -> Vec<(Rect, f64)> {
+    fn _detect(&self, mat: &GpuMat) -> Result<Vec<(Rect, f64)>, Error> {
         let mut found = CVec::<Rect>::default();
-        unsafe {
-            cv_gpu_hog_detect(self.inner, mat.inner, &mut found);
-        }
-        found
+        cv_try(|e| unsafe { cv_gpu_hog_detect(self.inner, mat.inner, &mut found, e) })?;
+        Ok(found
             .unpack()
             .into_iter()
             .map(|r| (r, 0f64))
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>())
     }
 
     /// Detects and returns the results with confidence (scores)
-    fn _detect_with_confidence(&self, mat: &GpuMat) -> Vec<(Rect, f64)> {
+    fn _detect_with_confidence(&self, mat: &GpuMat) -> Result<Vec<(Rect, f64)>, Error> {
         let mut found = CVec::<Rect>::default();
         let mut conf = CVec::<c_double>::default();
-        unsafe { cv_gpu_hog_detect_with_conf(self.inner, mat.inner, &mut found, &mut conf) }
+        cv_try(|e| unsafe { cv_gpu_hog_detect_with_conf(self.inner, mat.inner, &mut found, &mut conf, e) })?;
 
-        found
+        Ok(found
             .unpack()
             .into_iter()
             .zip(conf.unpack().into_iter())
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>())
     }
 }
 
@@ -279,9 +281,14 @@ pub struct GpuCascade {
 unsafe impl Send for GpuCascade {}
 
 extern "C" {
-    fn cv_gpu_cascade_new(filename: *const c_char) -> *mut CGpuCascade;
+    fn cv_gpu_cascade_new(filename: *const c_char, error: *mut CError) -> *mut CGpuCascade;
     fn cv_gpu_cascade_drop(cascade: *mut CGpuCascade);
-    fn cv_gpu_cascade_detect(cascade: *mut CGpuCascade, image: *const CGpuMat, objects: *mut CVec<Rect>);
+    fn cv_gpu_cascade_detect(
+        cascade: *mut CGpuCascade,
+        image: *const CGpuMat,
+        objects: *mut CVec<Rect>,
+        error: *mut CError,
+    );
 
     fn cv_gpu_cascade_set_find_largest_object(cascade: *mut CGpuCascade, value: bool);
     fn cv_gpu_cascade_set_max_num_objects(cascade: *mut CGpuCascade, max: c_int);
@@ -310,19 +317,17 @@ impl GpuCascade {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         if let Some(p) = path.as_ref().to_str() {
             let s = CString::new(p)?;
-            let inner = unsafe { cv_gpu_cascade_new((&s).as_ptr()) };
+            let inner = cv_try(|e| unsafe { cv_gpu_cascade_new((&s).as_ptr(), e) })?;
             return Ok(GpuCascade { inner: inner });
         }
         Err(CvError::InvalidPath {path: path.as_ref().to_path_buf()}.into())
     }
 
     /// Detects objects of different sizes in the input image.
-    pub fn detect_multiscale(&self, mat: &GpuMat) -> Vec<Rect> {
+    pub fn detect_multiscale(&self, mat: &GpuMat) -> Result<Vec<Rect>, Error> {
         let mut found = CVec::<Rect>::default();
-        unsafe {
-            cv_gpu_cascade_detect(self.inner, mat.inner, &mut found);
-        }
-        found.unpack()
+        cv_try(|e| unsafe { cv_gpu_cascade_detect(self.inner, mat.inner, &mut found, e) })?;
+        Ok(found.unpack())
     }
 
     /// Sets whether or not to find the only largest object.
@@ -405,13 +410,13 @@ impl GpuCascade {
 }
 
 impl ObjectDetect for GpuCascade {
-    fn detect(&self, image: &Mat) -> Vec<(Rect, f64)> {
+    fn detect(&self, image: &Mat) -> Result<Vec<(Rect, f64)>, Error> {
         let mut gpu_mat = GpuMat::default();
-        gpu_mat.upload(image);
-        self.detect_multiscale(&gpu_mat)
+        gpu_mat.upload(image)?;
+        Ok(self.detect_multiscale(&gpu_mat)?
             .into_iter()
             .map(|r| (r, 0.0))
-            .collect()
+            .collect())
     }
 }
 
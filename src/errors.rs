@@ -7,4 +7,29 @@ use std::path::PathBuf;
 pub enum CvError {
     #[fail(display = "invalid path: {:?}", path)] InvalidPath { path: PathBuf },
     #[fail(display = "failed to convert from primitive: {}", value)] EnumFromPrimitiveConversionError { value: i32 },
+    #[fail(display = "element type mismatch: mat has depth {} with {} channel(s), requested depth {} with {} channel(s)",
+           mat_depth, mat_channels, depth, channels)]
+    ElementTypeMismatch { mat_depth: i32, mat_channels: i32, depth: i32, channels: i32 },
+    #[fail(display = "mat data is not continuous")] NotContinuous,
+    #[fail(display = "index {} is out of range (bound {})", index, bound)] IndexOutOfRange { index: usize, bound: usize },
+    #[fail(display = "invalid matrix size: {} rows, {} cols", rows, cols)] InvalidSize { rows: i32, cols: i32 },
+    #[fail(display = "buffer holds {} elements, expected {}", actual, expected)] BufferSizeMismatch { expected: usize, actual: usize },
+    #[fail(display = "OpenCV error {} in {} ({}:{}): {}", code, func, file, line, message)]
+    OpenCvException { code: i32, func: String, file: String, line: i32, message: String },
+    #[fail(display = "failed to read image: {:?}", path)] ImageReadFailed { path: PathBuf },
+    #[fail(display = "failed to decode image")] ImageDecodeFailed,
+    #[fail(display = "failed to encode image as {}", ext)] ImageEncodeFailed { ext: String },
+    #[fail(display = "histogram has {} channel(s), {} size(s) and {} range(s)", channels, sizes, ranges)]
+    HistogramDimensionMismatch { channels: usize, sizes: usize, ranges: usize },
+    #[fail(display = "matrix has {} dimension(s), got {} indices", dims, indices)]
+    DimensionMismatch { dims: usize, indices: usize },
+    #[fail(display = "no CvType with depth {} and {} channel(s)", depth, channels)]
+    UnsupportedType { depth: i32, channels: i32 },
+    #[fail(display = "failed to open file storage: {}", source)] FileStorageOpenFailed { source: String },
+    #[fail(display = "file node {:?} not found", name)] MissingFileNode { name: String },
+    #[fail(display = "expected a file node of type {}, found {}", expected, found)]
+    UnexpectedFileNode { expected: String, found: String },
+    #[fail(display = "matrix is singular")] SingularMatrix,
+    #[fail(display = "no input matrix")] EmptyInput,
+    #[fail(display = "matrix is empty")] EmptyMat,
 }
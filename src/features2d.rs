@@ -1,5 +1,7 @@
 //! Provide the type that encapsulates all the parameters of the MSER extraction algorithm
+use failure::Error as Error;
 use super::core::*;
+use std::ptr;
 
 enum CMSER {}
 
@@ -14,6 +16,7 @@ extern "C" {
         area_threshold: f64,
         min_margin: f64,
         edge_blur_size: i32,
+        error: *mut CError,
     ) -> *mut CMSER;
     fn cv_mser_drop(cmser: *mut CMSER);
     fn cv_mser_detect_regions(
@@ -21,6 +24,7 @@ extern "C" {
         image: *const CMat,
         msers: *mut CVec<CVec<Point2i>>,
         bboxes: *mut CVec<Rect>,
+        error: *mut CError,
     );
 }
 
@@ -42,9 +46,10 @@ impl MSER {
         area_threshold: f64,
         min_margin: f64,
         edge_blur_size: i32,
-    ) -> Self {
-        let mser = unsafe {
-            cv_mser_new(
+    ) -> Result<Self, Error> {
+        let mut value = ptr::null_mut();
+        let result = cv_try(|e| unsafe {
+            value = cv_mser_new(
                 delta,
                 min_area,
                 max_area,
@@ -54,21 +59,21 @@ impl MSER {
                 area_threshold,
                 min_margin,
                 edge_blur_size,
+                e,
             )
-        };
-        MSER { value: mser }
+        });
+        let mser = MSER { value: value };
+        result.map(|_| mser)
     }
 
     /// Detect MSER regions.
-    pub fn detect_regions(&self, image: &Mat) -> (Vec<Vec<Point2i>>, Vec<Rect>) {
+    pub fn detect_regions(&self, image: &Mat) -> Result<(Vec<Vec<Point2i>>, Vec<Rect>), Error> {
         let mut msers = CVec::<CVec<Point2i>>::default();
         let mut bboxes = CVec::<Rect>::default();
-        unsafe {
-            cv_mser_detect_regions(self.value, image.inner, &mut msers, &mut bboxes);
-        }
+        cv_try(|e| unsafe { cv_mser_detect_regions(self.value, image.inner, &mut msers, &mut bboxes, e) })?;
         let msers = msers.unpack();
         let boxes = bboxes.unpack();
-        (msers, boxes)
+        Ok((msers, boxes))
     }
 }
 
@@ -148,10 +153,10 @@ impl MSERBuilder {
         self.edge_blur_size = Some(value);
         self
     }
-}
 
-impl Into<MSER> for MSERBuilder {
-    fn into(self) -> MSER {
+    /// Creates the MSER extractor, using the defaults of OpenCV for the
+    /// unset values.
+    pub fn build(self) -> Result<MSER, Error> {
         MSER::new(
             self.delta.unwrap_or(5),
             self.min_area.unwrap_or(60),
@@ -1,16 +1,19 @@
 //! highgui: high-level GUI
+use core::{cv_try, CError};
+use failure::Error as Error;
 use std::os::raw::{c_char, c_int, c_void};
 use std::ffi::CString;
 use std::mem;
 use std::ptr;
 
 extern "C" {
-    fn cv_named_window(name: *const c_char, flags: c_int);
-    fn cv_destroy_window(name: *const c_char);
+    fn cv_named_window(name: *const c_char, flags: c_int, error: *mut CError);
+    fn cv_destroy_window(name: *const c_char, error: *mut CError);
     fn cv_set_mouse_callback(
         name: *const c_char,
         on_mouse: extern "C" fn(e: i32, x: i32, y: i32, f: i32, data: *mut c_void),
         userdata: *mut c_void,
+        error: *mut CError,
     );
 }
 
@@ -18,19 +21,15 @@ extern "C" {
 /// Create a window that can be used as a placeholder for images and
 /// trackbars. All created windows are referred to by their names. If a window
 /// with the same name already exists, the function does nothing.
-pub fn highgui_named_window(name: &str, flags: WindowFlags) {
-    let s = CString::new(name).unwrap();
-    unsafe {
-        cv_named_window((&s).as_ptr(), flags as i32);
-    }
+pub fn highgui_named_window(name: &str, flags: WindowFlags) -> Result<(), Error> {
+    let s = CString::new(name)?;
+    cv_try(|e| unsafe { cv_named_window((&s).as_ptr(), flags as i32, e) })
 }
 
 /// Destroy the specified window with the given name.
-pub fn highgui_destroy_window(name: &str) {
-    let s = CString::new(name).unwrap();
-    unsafe {
-        cv_destroy_window((&s).as_ptr());
-    }
+pub fn highgui_destroy_window(name: &str) -> Result<(), Error> {
+    let s = CString::new(name)?;
+    cv_try(|e| unsafe { cv_destroy_window((&s).as_ptr(), e) })
 }
 
 /// Pointer referring to the data used in MouseCallback
@@ -42,7 +41,7 @@ pub type MouseCallback = fn(i32, i32, i32, i32, MouseCallbackData);
 
 /// Set mouse handler for the specified window (identified by name). A callback
 /// handler should be provided and optional user_data can be passed around.
-pub fn highgui_set_mouse_callback(name: &str, on_mouse: MouseCallback, user_data: *mut c_void) {
+pub fn highgui_set_mouse_callback(name: &str, on_mouse: MouseCallback, user_data: *mut c_void) -> Result<(), Error> {
     struct CallbackWrapper {
         cb: Box<MouseCallback>,
         data: *mut c_void,
@@ -59,12 +58,9 @@ pub fn highgui_set_mouse_callback(name: &str, on_mouse: MouseCallback, user_data
         cb: Box::new(on_mouse),
         data: user_data,
     });
+    let s = CString::new(name)?;
     let box_wrapper_raw = Box::into_raw(box_wrapper) as *mut c_void;
-
-    let s = CString::new(name).unwrap();
-    unsafe {
-        cv_set_mouse_callback((&s).as_ptr(), _mouse_callback, box_wrapper_raw);
-    }
+    cv_try(|e| unsafe { cv_set_mouse_callback((&s).as_ptr(), _mouse_callback, box_wrapper_raw, e) })
 }
 
 
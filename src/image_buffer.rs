@@ -0,0 +1,158 @@
+//! Conversions between `Mat` and the `image` crate's `ImageBuffer`, enabled by
+//! the `image` feature.
+//!
+//! OpenCV stores color images in BGR(A) order while `image` usually uses
+//! `Rgb`/`Rgba`, so every conversion states how it treats the channel order:
+//!
+//! - `TryFrom<&Mat>` copies a `Mat` assumed to be BGR(A), like everything
+//!   OpenCV reads or produces, into an `ImageBuffer` of any
+//!   [CvPixel](trait.CvPixel.html), swapping red and blue for `Rgb`/`Rgba`.
+//! - [Mat::from_image](struct.Mat.html#method.from_image) borrows the pixels
+//!   without copying and keeps the channel order of the image as is.
+//! - [Mat::from_image_bgr](struct.Mat.html#method.from_image_bgr) copies the
+//!   pixels into BGR(A) order.
+
+use core::*;
+use errors::*;
+use failure::Error as Error;
+use image::{Bgr, Bgra, ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgba};
+use std::convert::TryFrom;
+use std::ops::Deref;
+
+/// An `image` pixel type with a matching `Mat` layout.
+pub trait CvPixel: Pixel + 'static {
+    /// The `CvType` of a `Mat` holding these pixels.
+    const CV_TYPE: CvType;
+
+    /// True if red is stored before blue, i.e. the reverse of OpenCV's BGR
+    /// order.
+    const RGB: bool;
+}
+
+macro_rules! impl_cv_pixel {
+    ($($pixel: ident, $t: ty, $cv_type: ident, $rgb: expr;)*) => {
+        $(
+            impl CvPixel for $pixel<$t> {
+                const CV_TYPE: CvType = CvType::$cv_type;
+                const RGB: bool = $rgb;
+            }
+        )*
+    }
+}
+
+impl_cv_pixel! {
+    Luma, u8, Cv8UC1, false;
+    LumaA, u8, Cv8UC2, false;
+    Rgb, u8, Cv8UC3, true;
+    Bgr, u8, Cv8UC3, false;
+    Rgba, u8, Cv8UC4, true;
+    Bgra, u8, Cv8UC4, false;
+    Luma, u16, Cv16UC1, false;
+    LumaA, u16, Cv16UC2, false;
+    Rgb, u16, Cv16UC3, true;
+    Bgr, u16, Cv16UC3, false;
+    Rgba, u16, Cv16UC4, true;
+    Bgra, u16, Cv16UC4, false;
+}
+
+/// Swaps the first and third channel of every pixel, turning RGB(A) into
+/// BGR(A) and back.
+fn swap_red_blue<T>(data: &mut [T], channels: usize) {
+    for pixel in data.chunks_mut(channels) {
+        pixel.swap(0, 2);
+    }
+}
+
+fn check_image_size(width: u32, height: u32) -> Result<(i32, i32), Error> {
+    if width > i32::MAX as u32 || height > i32::MAX as u32 {
+        return Err(CvError::InvalidSize {
+            rows: height as i32,
+            cols: width as i32,
+        }.into());
+    }
+    Ok((height as i32, width as i32))
+}
+
+/// The samples of the pixels of `image`, without the rest of its container,
+/// which may be longer.
+fn samples<P, C>(image: &ImageBuffer<P, C>, rows: i32, cols: i32) -> &[P::Subpixel]
+where
+    P: CvPixel,
+    C: Deref<Target = [P::Subpixel]>,
+{
+    &(**image)[..rows as usize * cols as usize * P::channel_count() as usize]
+}
+
+/// Copies a BGR(A) `Mat` into an `ImageBuffer`. The `Mat` type must match
+/// the pixel type, e.g. `Cv8UC3` for `RgbImage` or `Cv16UC1` for
+/// `ImageBuffer<Luma<u16>, Vec<u16>>`; the `Mat` doesn't have to be
+/// continuous.
+impl<'a, P> TryFrom<&'a Mat> for ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: CvPixel,
+    P::Subpixel: DataType,
+{
+    type Error = Error;
+
+    fn try_from(mat: &'a Mat) -> Result<Self, Error> {
+        let cv_type = mat.cv_type()?;
+        if cv_type != P::CV_TYPE {
+            return Err(CvError::ElementTypeMismatch {
+                mat_depth: cv_type.depth(),
+                mat_channels: cv_type.channels(),
+                depth: P::CV_TYPE.depth(),
+                channels: P::CV_TYPE.channels(),
+            }.into());
+        }
+
+        let channels = P::channel_count() as usize;
+        let mut data = Vec::with_capacity(mat.rows as usize * mat.cols as usize * channels);
+        for i in 0..mat.rows as usize {
+            data.extend_from_slice(mat.row::<P::Subpixel>(i)?);
+        }
+        if P::RGB {
+            swap_red_blue(&mut data, channels);
+        }
+
+        let len = data.len();
+        ImageBuffer::from_raw(mat.cols as u32, mat.rows as u32, data).ok_or_else(|| {
+            CvError::BufferSizeMismatch {
+                expected: mat.rows as usize * mat.cols as usize * channels,
+                actual: len,
+            }.into()
+        })
+    }
+}
+
+impl Mat {
+    /// Wraps the pixels of `image` in a `Mat` without copying them. The
+    /// channels keep the order of the image: an `RgbImage` gives an RGB
+    /// `Mat`, which OpenCV functions expecting BGR will misinterpret. Use
+    /// [from_image_bgr](struct.Mat.html#method.from_image_bgr) or convert
+    /// with [cvt_color](struct.Mat.html#method.cvt_color) in that case.
+    pub fn from_image<'a, P, C>(image: &'a ImageBuffer<P, C>) -> Result<MatRef<'a>, Error>
+    where
+        P: CvPixel,
+        P::Subpixel: DataType,
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        let (rows, cols) = check_image_size(image.width(), image.height())?;
+        Mat::from_slice(rows, cols, P::CV_TYPE, samples(image, rows, cols))
+    }
+
+    /// Copies the pixels of `image` into a new `Mat` in OpenCV's BGR(A)
+    /// order, swapping red and blue for `Rgb` and `Rgba` images.
+    pub fn from_image_bgr<P, C>(image: &ImageBuffer<P, C>) -> Result<Mat, Error>
+    where
+        P: CvPixel,
+        P::Subpixel: DataType,
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        let (rows, cols) = check_image_size(image.width(), image.height())?;
+        let mut mat = Mat::from_slice_copy(rows, cols, P::CV_TYPE, samples(image, rows, cols))?;
+        if P::RGB {
+            swap_red_blue(mat.as_mut_slice::<P::Subpixel>()?, P::channel_count() as usize);
+        }
+        Ok(mat)
+    }
+}
@@ -1,10 +1,12 @@
 //! Image file reading and writing, see [OpenCV
 //! imgcodecs](http://docs.opencv.org/3.1.0/d4/da8/group__imgcodecs.html).
 
+use failure::Error as Error;
 use std::ffi::CString;
 use std::path::Path;
 use std::os::raw::{c_char, c_int};
-use super::core::{CMat, Mat};
+use super::core::{cv_try, cv_try_new_mat, CError, CMat, Mat};
+use super::errors::*;
 
 // =============================================================================
 //  Imgproc
@@ -103,10 +105,15 @@ pub enum ImwritePngFlags {
 }
 
 extern "C" {
-    fn cv_imread(input: *const c_char, flags: c_int) -> *mut CMat;
-    fn cv_imdecode(buf: *const u8, l: usize, m: c_int) -> *mut CMat;
-    fn cv_imencode(ext: *const c_char, inner: *const CMat, flag_ptr: *const c_int, flag_size: usize) -> ImencodeResult;
-
+    fn cv_imread(input: *const c_char, flags: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_imdecode(buf: *const u8, l: usize, m: c_int, error: *mut CError) -> *mut CMat;
+    fn cv_imencode(
+        ext: *const c_char,
+        inner: *const CMat,
+        flag_ptr: *const c_int,
+        flag_size: usize,
+        error: *mut CError,
+    ) -> ImencodeResult;
 }
 
 #[repr(C)]
@@ -116,35 +123,51 @@ struct ImencodeResult {
     size: usize,
 }
 
+impl Drop for ImencodeResult {
+    fn drop(&mut self) {
+        extern "C" {
+            fn cv_imencode_result_drop(result: *mut ImencodeResult);
+        }
+        unsafe { cv_imencode_result_drop(self) }
+    }
+}
+
 impl Mat {
-    /// Creates a `Mat` from reading the image specified by the path.
-    pub fn from_path<P: AsRef<Path>>(path: P, flags: ImreadModes) -> Option<Mat> {
-        if let Some(unicode_path) = path.as_ref().as_os_str().to_str() {
-            let s = CString::new(unicode_path).unwrap();
-            let m = unsafe { cv_imread((&s).as_ptr(), flags as c_int) };
-            Some(Mat::from_raw(m))
-        } else {
-            None
+    /// Creates a `Mat` from reading the image specified by the path. Returns
+    /// an error if the file is missing, unreadable or in an unsupported
+    /// format.
+    pub fn from_path<P: AsRef<Path>>(path: P, flags: ImreadModes) -> Result<Mat, Error> {
+        let path = path.as_ref();
+        let unicode_path = path.to_str()
+            .ok_or_else(|| CvError::InvalidPath { path: path.to_path_buf() })?;
+        let s = CString::new(unicode_path)?;
+        let m = cv_try_new_mat(|e| unsafe { cv_imread((&s).as_ptr(), flags as c_int, e) })?;
+        if !m.is_valid() {
+            return Err(CvError::ImageReadFailed { path: path.to_path_buf() }.into());
         }
+        Ok(m)
     }
 
-    /// Decodes an image from `buf` according to the specified mode.
-    pub fn imdecode(buf: &[u8], mode: ImreadModes) -> Mat {
-        let inner = unsafe { cv_imdecode(buf.as_ptr(), buf.len(), mode as i32) };
-        Mat::from_raw(inner)
+    /// Decodes an image from `buf` according to the specified mode. Returns
+    /// an error if the buffer can't be decoded.
+    pub fn imdecode(buf: &[u8], mode: ImreadModes) -> Result<Mat, Error> {
+        let m = cv_try_new_mat(|e| unsafe { cv_imdecode(buf.as_ptr(), buf.len(), mode as i32, e) })?;
+        if !m.is_valid() {
+            return Err(CvError::ImageDecodeFailed.into());
+        }
+        Ok(m)
     }
 
     /// Encodes an image; the encoding scheme depends on the extension provided;
     /// additional write flags can be passed in using a vector. If successful,
     /// returns an owned vector of the encoded image.
-    pub fn imencode(&self, ext: &str, f: Vec<ImwriteFlags>) -> Option<Vec<u8>> {
-        let ext = CString::new(ext).expect("invalid extension string");
+    pub fn imencode(&self, ext: &str, f: Vec<ImwriteFlags>) -> Result<Vec<u8>, Error> {
+        let c_ext = CString::new(ext)?;
         let flags = f.into_iter().map(|f| f as i32).collect::<Vec<_>>();
-        let r = unsafe { cv_imencode(ext.into_raw(), self.inner, flags.as_ptr(), flags.len()) };
-        if r.status {
-            unsafe { Some(::std::slice::from_raw_parts(r.buf, r.size).to_vec()) }
-        } else {
-            None
+        let r = cv_try(|e| unsafe { cv_imencode(c_ext.as_ptr(), self.inner, flags.as_ptr(), flags.len(), e) })?;
+        if !r.status {
+            return Err(CvError::ImageEncodeFailed { ext: ext.to_owned() }.into());
         }
+        Ok(unsafe { ::std::slice::from_raw_parts(r.buf, r.size).to_vec() })
     }
 }
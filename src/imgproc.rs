@@ -1,8 +1,14 @@
 //! Image processing, see [OpenCV
 //! imgproc](http://docs.opencv.org/3.1.0/d7/dbd/group__imgproc.html).
 
+use errors::CvError;
+use failure::Error as Error;
 use super::core::*;
+use std::ops::Range;
 use std::os::raw::{c_double, c_float, c_int};
+use std::ptr;
+
+enum CCLAHE {}
 
 // =============================================================================
 //  Imgproc
@@ -16,9 +22,17 @@ extern "C" {
         thickness: c_int,
         linetype: c_int,
         shift: c_int,
+        error: *mut CError,
     );
 
-    fn cv_rectangle(cmat: *mut CMat, rect: Rect, color: Scalar, thickness: c_int, linetype: c_int);
+    fn cv_rectangle(
+        cmat: *mut CMat,
+        rect: Rect,
+        color: Scalar,
+        thickness: c_int,
+        linetype: c_int,
+        error: *mut CError,
+    );
 
     fn cv_ellipse(
         cmat: *mut CMat,
@@ -31,29 +45,69 @@ extern "C" {
         thickness: c_int,
         linetype: c_int,
         shift: c_int,
+        error: *mut CError,
     );
 
-    fn cv_cvt_color(cmat: *const CMat, output: *mut CMat, code: i32);
-    fn cv_pyr_down(cmat: *const CMat, output: *mut CMat);
-    fn cv_resize(from: *const CMat, to: *mut CMat, dsize: Size2i, fx: c_double, fy: c_double, interpolation: c_int);
+    fn cv_cvt_color(cmat: *const CMat, output: *mut CMat, code: i32, error: *mut CError);
+    fn cv_pyr_down(cmat: *const CMat, output: *mut CMat, error: *mut CError);
+    fn cv_resize(
+        from: *const CMat,
+        to: *mut CMat,
+        dsize: Size2i,
+        fx: c_double,
+        fy: c_double,
+        interpolation: c_int,
+        error: *mut CError,
+    );
     fn cv_calc_hist(
-        cimages: *const CMat,
-        nimages: i32,
+        cimages: *const *const CMat,
+        nimages: usize,
         channels: *const c_int,
         cmask: *const CMat,
         chist: *mut CMat,
         dims: c_int,
         hist_size: *const c_int,
-        ranges: *const *const c_float,
+        ranges: *const c_float,
+        error: *mut CError,
+    );
+    fn cv_calc_hist_sparse(
+        cimages: *const *const CMat,
+        nimages: usize,
+        channels: *const c_int,
+        cmask: *const CMat,
+        chist: *mut CSparseMat,
+        dims: c_int,
+        hist_size: *const c_int,
+        ranges: *const c_float,
+        error: *mut CError,
     );
     fn cv_calc_back_project(
-        cimages: *const CMat,
-        nimages: c_int,
+        cimages: *const *const CMat,
+        nimages: usize,
         channels: *const c_int,
         chist: *const CMat,
         cback_project: *mut CMat,
-        ranges: *const *const c_float,
+        dims: c_int,
+        ranges: *const c_float,
+        scale: c_double,
+        error: *mut CError,
     );
+    fn cv_compare_hist(chist1: *const CMat, chist2: *const CMat, method: c_int, error: *mut CError) -> c_double;
+    fn cv_equalize_hist(csrc: *const CMat, cdst: *mut CMat, error: *mut CError);
+
+    fn cv_clahe_new(clip_limit: c_double, tile_grid_size: Size2i, error: *mut CError) -> *mut CCLAHE;
+    fn cv_clahe_drop(cclahe: *mut CCLAHE);
+    fn cv_clahe_apply(cclahe: *const CCLAHE, csrc: *const CMat, cdst: *mut CMat, error: *mut CError);
+
+    fn cv_min_area_rect(points: *const Point2f, npoints: usize, error: *mut CError) -> RotatedRect;
+    fn cv_fit_ellipse(points: *const Point2f, npoints: usize, error: *mut CError) -> RotatedRect;
+    fn cv_box_points(rect: RotatedRect, points: *mut Point2f, error: *mut CError);
+    fn cv_rotated_rectangle_intersection(
+        rect1: RotatedRect,
+        rect2: RotatedRect,
+        region: *mut CVec<Point2f>,
+        error: *mut CError,
+    ) -> c_int;
 }
 
 /// Color conversion code used in
@@ -228,24 +282,273 @@ pub enum InterpolationFlag {
     WarpInverseMap = 16,
 }
 
+/// Histogram comparison methods used in
+/// [compare_hist](../struct.Mat.html#method.compare_hist).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HistCompMethods {
+    /// Correlation
+    Correl = 0,
+
+    /// Chi-Square
+    ChiSqr = 1,
+
+    /// Intersection
+    Intersect = 2,
+
+    /// Bhattacharyya distance (In fact, OpenCV computes Hellinger distance,
+    /// which is related to Bhattacharyya coefficient.)
+    Bhattacharyya = 3,
+
+    /// Alternative Chi-Square
+    ChiSqrAlt = 4,
+
+    /// Kullback-Leibler divergence
+    KlDiv = 5,
+}
+
+impl HistCompMethods {
+    /// Synonym for `Bhattacharyya`.
+    pub const HELLINGER: HistCompMethods = HistCompMethods::Bhattacharyya;
+}
+
+/// Describes the bins of a histogram: which channels of the input images are
+/// counted, how many bins each dimension has and the range of values they
+/// cover. There is one channel, size and range per histogram dimension.
+///
+/// ```rust,ignore
+/// // Hue-saturation histogram of an HSV image
+/// let hist = Histogram::new()
+///     .channels(&[0, 1])
+///     .sizes(&[30, 32])
+///     .ranges(&[0.0..180.0, 0.0..256.0])
+///     .calc(&[&hsv], None)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    channels: Vec<i32>,
+    sizes: Vec<i32>,
+    ranges: Vec<Range<f32>>,
+}
+
+impl Histogram {
+    /// Creates an empty histogram description.
+    pub fn new() -> Self {
+        Histogram::default()
+    }
+
+    /// Replace current channels with specified value. The channels of all
+    /// input images are numbered one after the other.
+    pub fn channels(mut self, channels: &[i32]) -> Self {
+        self.channels = channels.to_vec();
+        self
+    }
+
+    /// Replace current number of bins per dimension with specified value
+    pub fn sizes(mut self, sizes: &[i32]) -> Self {
+        self.sizes = sizes.to_vec();
+        self
+    }
+
+    /// Replace current value ranges per dimension with specified value. The
+    /// upper bound is exclusive.
+    pub fn ranges(mut self, ranges: &[Range<f32>]) -> Self {
+        self.ranges = ranges.to_vec();
+        self
+    }
+
+    /// Calculates the histogram of a set of images, counting only the pixels
+    /// selected by `mask` if given.
+    pub fn calc(&self, images: &[&Mat], mask: Option<&Mat>) -> Result<Mat, Error> {
+        self.check_dims(true)?;
+        let images = images.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        let ranges = self.flat_ranges();
+        let mask = mask.map_or(::std::ptr::null(), |m| m.inner as *const CMat);
+        cv_try_mat(|m, e| unsafe {
+            cv_calc_hist(
+                images.as_ptr(),
+                images.len(),
+                self.channels.as_ptr(),
+                mask,
+                m,
+                self.channels.len() as c_int,
+                self.sizes.as_ptr(),
+                ranges.as_ptr(),
+                e,
+            )
+        })
+    }
+
+    /// Like [calc](struct.Histogram.html#method.calc), but returns a
+    /// `SparseMat` that only stores the non-empty bins.
+    pub fn calc_sparse(&self, images: &[&Mat], mask: Option<&Mat>) -> Result<SparseMat, Error> {
+        self.check_dims(true)?;
+        let images = images.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        let ranges = self.flat_ranges();
+        let mask = mask.map_or(::std::ptr::null(), |m| m.inner as *const CMat);
+        let hist = SparseMat::new(&self.sizes, CvType::Cv32FC1)?;
+        cv_try(|e| unsafe {
+            cv_calc_hist_sparse(
+                images.as_ptr(),
+                images.len(),
+                self.channels.as_ptr(),
+                mask,
+                hist.inner,
+                self.channels.len() as c_int,
+                self.sizes.as_ptr(),
+                ranges.as_ptr(),
+                e,
+            )
+        })?;
+        Ok(hist)
+    }
+
+    /// Calculates the back projection of `hist` on a set of images: each
+    /// pixel gets the value of the bin it falls into, multiplied by `scale`.
+    /// The sizes are taken from `hist`.
+    pub fn back_project(&self, images: &[&Mat], hist: &Mat, scale: f64) -> Result<Mat, Error> {
+        self.check_dims(false)?;
+        let images = images.iter().map(|m| m.inner as *const CMat).collect::<Vec<_>>();
+        let ranges = self.flat_ranges();
+        cv_try_mat(|m, e| unsafe {
+            cv_calc_back_project(
+                images.as_ptr(),
+                images.len(),
+                self.channels.as_ptr(),
+                hist.inner,
+                m,
+                self.channels.len() as c_int,
+                ranges.as_ptr(),
+                scale,
+                e,
+            )
+        })
+    }
+
+    fn check_dims(&self, with_sizes: bool) -> Result<(), CvError> {
+        let dims = self.channels.len();
+        if dims == 0 || self.ranges.len() != dims || (with_sizes && self.sizes.len() != dims) {
+            return Err(CvError::HistogramDimensionMismatch {
+                channels: dims,
+                sizes: self.sizes.len(),
+                ranges: self.ranges.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn flat_ranges(&self) -> Vec<c_float> {
+        self.ranges.iter().flat_map(|r| vec![r.start, r.end]).collect()
+    }
+}
+
+/// Contrast Limited Adaptive Histogram Equalization.
+#[derive(Debug)]
+pub struct CLAHE {
+    value: *mut CCLAHE,
+}
+
+impl CLAHE {
+    /// Creates a new equalizer. `clip_limit` is the threshold for contrast
+    /// limiting and `tile_grid_size` the number of tiles the image is divided
+    /// into in each direction.
+    pub fn new(clip_limit: f64, tile_grid_size: Size2i) -> Result<Self, Error> {
+        let mut clahe = ptr::null_mut();
+        let result = cv_try(|e| clahe = unsafe { cv_clahe_new(clip_limit, tile_grid_size, e) });
+        let clahe = CLAHE { value: clahe };
+        result.map(|_| clahe)
+    }
+
+    /// Equalizes the histogram of a grayscale image.
+    pub fn apply(&self, src: &Mat) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_clahe_apply(self.value, src.inner, m, e) })
+    }
+}
+
+impl Default for CLAHE {
+    /// Creates an equalizer with OpenCV's defaults: a clip limit of 40 and
+    /// 8x8 tiles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if OpenCV fails to create it.
+    fn default() -> Self {
+        CLAHE::new(40.0, Size2i::new(8, 8)).expect("failed to create CLAHE")
+    }
+}
+
+impl Drop for CLAHE {
+    fn drop(&mut self) {
+        unsafe {
+            cv_clahe_drop(self.value);
+        }
+    }
+}
+
+/// Types of intersection between rotated rectangles, see
+/// [rotated_rectangle_intersection](fn.rotated_rectangle_intersection.html).
+#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
+pub enum RectanglesIntersectTypes {
+    /// No intersection
+    IntersectNone = 0,
+
+    /// There is a partial intersection
+    IntersectPartial = 1,
+
+    /// One of the rectangles is fully enclosed in the other
+    IntersectFull = 2,
+}
+
+/// Finds the rotated rectangle of the minimum area enclosing the points.
+pub fn min_area_rect(points: &[Point2f]) -> Result<RotatedRect, Error> {
+    cv_try(|e| unsafe { cv_min_area_rect(points.as_ptr(), points.len(), e) })
+}
+
+/// Fits an ellipse around the points, in the least-squares sense. At least
+/// 5 points are needed.
+pub fn fit_ellipse(points: &[Point2f]) -> Result<RotatedRect, Error> {
+    cv_try(|e| unsafe { cv_fit_ellipse(points.as_ptr(), points.len(), e) })
+}
+
+/// Finds the four vertices of a rotated rectangle, in the same order as
+/// [RotatedRect::points](../struct.RotatedRect.html#method.points).
+pub fn box_points(rect: &RotatedRect) -> Result<[Point2f; 4], Error> {
+    let mut points = [Point2f::default(); 4];
+    cv_try(|e| unsafe { cv_box_points(*rect, points.as_mut_ptr(), e) })?;
+    Ok(points)
+}
+
+/// Finds out if there is any intersection between two rotated rectangles,
+/// and returns the vertices of the intersecting region as well.
+pub fn rotated_rectangle_intersection(
+    rect1: &RotatedRect,
+    rect2: &RotatedRect,
+) -> Result<(RectanglesIntersectTypes, Vec<Point2f>), Error> {
+    let mut region = CVec::<Point2f>::default();
+    let t = cv_try(|e| unsafe { cv_rotated_rectangle_intersection(*rect1, *rect2, &mut region, e) })?;
+    let t = ::num::FromPrimitive::from_i32(t).ok_or(CvError::EnumFromPrimitiveConversionError { value: t })?;
+    Ok((t, region.unpack()))
+}
+
 impl Mat {
-    /// Draws a simple line.
-    pub fn line(&self, pt1: Point2i, pt2: Point2i) {
-        let color = Scalar::new(255, 255, 0, 255);
-        self.line_custom(pt1, pt2, color, 1, LineTypes::Line8, 0);
+    /// Draws a simple line in [cyan](../struct.Color.html#method.cyan).
+    pub fn line(&self, pt1: Point2i, pt2: Point2i) -> Result<(), Error> {
+        self.line_custom(pt1, pt2, Color::cyan(), 1, LineTypes::Line8, 0)
     }
 
-    /// Draws a line with custom color, thickness and linetype.
-    pub fn line_custom(
+    /// Draws a line with custom color, thickness and linetype. The color can
+    /// be a [Color](../struct.Color.html) or anything convertible to a
+    /// `Scalar`.
+    pub fn line_custom<C: Into<Scalar>>(
         &self,
         pt1: Point2i,
         pt2: Point2i,
-        color: Scalar,
+        color: C,
         thickness: i32,
         linetype: LineTypes,
         shift: i32,
-    ) {
-        unsafe {
+    ) -> Result<(), Error> {
+        let color = color.into();
+        cv_try(|e| unsafe {
             cv_line(
                 self.inner,
                 pt1,
@@ -254,35 +557,51 @@ impl Mat {
                 thickness,
                 linetype as i32,
                 shift,
-            );
-        }
+                e,
+            )
+        })
     }
 
-    /// Draws a simple, thick, or filled up-right rectangle.
-    pub fn rectangle(&self, rect: Rect) {
-        self.rectangle_custom(rect, Scalar::new(255, 255, 0, 255), 1, LineTypes::Line8);
+    /// Draws a simple, thick, or filled up-right rectangle in
+    /// [cyan](../struct.Color.html#method.cyan).
+    pub fn rectangle(&self, rect: Rect) -> Result<(), Error> {
+        self.rectangle_custom(rect, Color::cyan(), 1, LineTypes::Line8)
     }
 
     /// Draws a rectangle with custom color, thickness and linetype.
-    pub fn rectangle_custom(&self, rect: Rect, color: Scalar, thickness: i32, linetype: LineTypes) {
-        unsafe { cv_rectangle(self.inner, rect, color, thickness, linetype as i32) }
+    pub fn rectangle_custom<C: Into<Scalar>>(
+        &self,
+        rect: Rect,
+        color: C,
+        thickness: i32,
+        linetype: LineTypes,
+    ) -> Result<(), Error> {
+        let color = color.into();
+        cv_try(|e| unsafe { cv_rectangle(self.inner, rect, color, thickness, linetype as i32, e) })
     }
 
     /// Draw a simple, thick, or filled up-right rectangle.
-    pub fn rectangle2f(&self, rect: Rect2f) {
+    pub fn rectangle2f(&self, rect: Rect2f) -> Result<(), Error> {
         let abs_rect = rect.normalize_to_mat(self);
-        self.rectangle(abs_rect);
+        self.rectangle(abs_rect)
     }
 
-    /// Draws a simple, thick ellipse
-    pub fn ellipse(&self, center: Point2i, axes: Size2i, angle: f64, start_angle: f64, end_angle: f64) {
+    /// Draws a simple, thick ellipse in [cyan](../struct.Color.html#method.cyan).
+    pub fn ellipse(
+        &self,
+        center: Point2i,
+        axes: Size2i,
+        angle: f64,
+        start_angle: f64,
+        end_angle: f64,
+    ) -> Result<(), Error> {
         self.ellipse_custom(
             center,
             axes,
             angle,
             start_angle,
             end_angle,
-            Scalar::new(255, 255, 0, 255),
+            Color::cyan(),
             1,
             LineTypes::Line8,
             0,
@@ -290,19 +609,20 @@ impl Mat {
     }
 
     /// Draws a custom ellipse
-    pub fn ellipse_custom(
+    pub fn ellipse_custom<C: Into<Scalar>>(
         &self,
         center: Point2i,
         axes: Size2i,
         angle: f64,
         start_angle: f64,
         end_angle: f64,
-        color: Scalar,
+        color: C,
         thickness: i32,
         linetype: LineTypes,
         shift: i32,
-    ) {
-        unsafe {
+    ) -> Result<(), Error> {
+        let color = color.into();
+        cv_try(|e| unsafe {
             cv_ellipse(
                 self.inner,
                 center,
@@ -314,42 +634,36 @@ impl Mat {
                 thickness,
                 linetype as i32,
                 shift,
+                e,
             )
-        }
+        })
     }
 
     /// Convert an image from one color space to another.
-    pub fn cvt_color(&self, code: ColorConversionCodes) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_cvt_color(self.inner, m, code as i32) }
-        Mat::from_raw(m)
+    pub fn cvt_color(&self, code: ColorConversionCodes) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_cvt_color(self.inner, m, code as i32, e) })
     }
 
     /// Blurs an image and downsamples it. This function performs the
     /// downsampling step of the Gaussian pyramid construction.
-    pub fn pyr_down(&self) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_pyr_down(self.inner, m) }
-        Mat::from_raw(m)
+    pub fn pyr_down(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_pyr_down(self.inner, m, e) })
     }
 
     /// Resizes an image.
     ///
     /// The function resize resizes the image down to or up to the specified
     /// size.
-    pub fn resize_to(&self, dsize: Size2i, interpolation: InterpolationFlag) -> Mat {
-        let m = CMat::new();
-        unsafe { cv_resize(self.inner, m, dsize, 0.0, 0.0, interpolation as c_int) }
-        Mat::from_raw(m)
+    pub fn resize_to(&self, dsize: Size2i, interpolation: InterpolationFlag) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_resize(self.inner, m, dsize, 0.0, 0.0, interpolation as c_int, e) })
     }
 
     /// Resizes an image.
     ///
     /// The function resize resizes the image down to or up to the specified
     /// size.
-    pub fn resize_by(&self, fx: f64, fy: f64, interpolation: InterpolationFlag) -> Mat {
-        let m = CMat::new();
-        unsafe {
+    pub fn resize_by(&self, fx: f64, fy: f64, interpolation: InterpolationFlag) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe {
             cv_resize(
                 self.inner,
                 m,
@@ -357,43 +671,29 @@ impl Mat {
                 fx,
                 fy,
                 interpolation as c_int,
+                e,
             )
-        }
-        Mat::from_raw(m)
+        })
     }
 
-    /// Calculate a histogram of an image.
-    pub fn calc_hist(
-        &self,
-        channels: *const c_int,
-        mask: Mat,
-        dims: c_int,
-        hist_size: *const c_int,
-        ranges: *const *const f32,
-    ) -> Mat {
-        let m = CMat::new();
-        unsafe {
-            cv_calc_hist(
-                self.inner,
-                1,
-                channels,
-                mask.inner,
-                m,
-                dims,
-                hist_size,
-                ranges,
-            );
-        }
-        Mat::from_raw(m)
+    /// Calculate a histogram of the image, see
+    /// [Histogram](imgproc/struct.Histogram.html) for more than one image.
+    pub fn calc_hist(&self, hist: &Histogram, mask: Option<&Mat>) -> Result<Mat, Error> {
+        hist.calc(&[self], mask)
     }
 
-    /// Calculate the back projection of a histogram. The function calculates
-    /// the back project of the histogram.
-    pub fn calc_back_project(&self, channels: *const i32, hist: &Mat, ranges: *const *const f32) -> Mat {
-        let m = CMat::new();
-        unsafe {
-            cv_calc_back_project(self.inner, 1, channels, (*hist).inner, m, ranges);
-        }
-        Mat::from_raw(m)
+    /// Calculate the back projection of a histogram on the image.
+    pub fn calc_back_project(&self, bins: &Histogram, hist: &Mat) -> Result<Mat, Error> {
+        bins.back_project(&[self], hist, 1.0)
+    }
+
+    /// Compares two histograms, the returned value depends on the `method`.
+    pub fn compare_hist(&self, other: &Mat, method: HistCompMethods) -> Result<f64, Error> {
+        cv_try(|e| unsafe { cv_compare_hist(self.inner, other.inner, method as c_int, e) })
+    }
+
+    /// Equalizes the histogram of a grayscale image.
+    pub fn equalize_hist(&self) -> Result<Mat, Error> {
+        cv_try_mat(|m, e| unsafe { cv_equalize_hist(self.inner, m, e) })
     }
 }
@@ -18,22 +18,58 @@
 extern crate bytes;
 #[macro_use]
 extern crate failure;
+#[cfg(feature = "image")]
+extern crate image;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate num;
 #[macro_use]
 extern crate num_derive;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[macro_use]
+mod macros;
 
-mod core;
+pub mod core;
+pub use core::BorderTypes;
+pub use core::CmpTypes;
+pub use core::Color;
 pub use core::CvType;
+pub use core::DataType;
+pub use core::DftFlags;
+pub use core::{EnumeratePixels, Pixels, PixelsMut, Rows, RowsMut};
 pub use core::FlipCode;
+pub use core::Formatter;
 pub use core::LineTypes;
 pub use core::Mat;
+pub use core::{MatMut, MatRef};
 pub use core::NormTypes;
-pub use core::Point2f;
-pub use core::Point2i;
-pub use core::Rect;
+pub use core::{Point2, Point2d, Point2f, Point2i};
+pub use core::{Point3, Point3d, Point3f, Point3i};
+pub use core::{Rect, Rect2, Rect2d, Rect2f, Rect2i};
+pub use core::ReduceTypes;
+pub use core::RotateFlags;
+pub use core::RotatedRect;
+pub use core::{Rng, RngDistribution};
 pub use core::Scalar;
-pub use core::Size2f;
-pub use core::Size2i;
+pub use core::SparseMat;
+pub use core::{Size2, Size2d, Size2f, Size2i};
+pub use core::TypedMat;
+pub use core::{Vec2b, Vec2d, Vec2f, Vec2i, Vec2s, Vec2w, Vec3b, Vec3d, Vec3f, Vec3i, Vec3s, Vec3w, Vec4b, Vec4d, Vec4f,
+               Vec4i, Vec4s, Vec4w};
+pub use core::get_optimal_dft_size;
+pub use core::set_rng_seed;
+
+#[cfg(feature = "image")]
+mod image_buffer;
+#[cfg(feature = "image")]
+pub use image_buffer::CvPixel;
+#[cfg(feature = "ndarray")]
+mod nd_array;
 
 pub mod errors;
 pub mod imgproc;
@@ -43,6 +79,9 @@ pub mod highgui;
 pub mod video;
 pub mod objdetect;
 pub mod features2d;
+pub mod persistence;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[cfg(feature = "gpu")]
 pub mod cuda;
@@ -0,0 +1,44 @@
+//! Macros shared by the modules of this crate.
+
+/// Defines a newtype over the `int` flags of an OpenCV function, with the
+/// given constants, `bits`, `contains` and `|` to combine them.
+macro_rules! bit_flags {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$flag_attr:meta])*
+                const $flag:ident = $value:expr;
+            )+
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+        pub struct $name(::std::os::raw::c_int);
+
+        impl $name {
+            $(
+                $(#[$flag_attr])*
+                pub const $flag: $name = $name($value);
+            )+
+
+            /// Returns the raw value passed to OpenCV.
+            pub fn bits(self) -> i32 {
+                self.0
+            }
+
+            /// Returns true if every flag of `other` is set.
+            pub fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+        }
+
+        impl ::std::ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+    };
+}
@@ -0,0 +1,113 @@
+//! Conversions between `Mat` and `ndarray` arrays, enabled by the `ndarray`
+//! feature.
+//!
+//! A 2-dimensional `Mat` maps to a 3-dimensional array indexed by row, column
+//! and channel, so a `Cv8UC3` image of 640x480 is an `ArrayView3<u8>` of shape
+//! `(480, 640, 3)`. The element type is a primitive matching the depth of the
+//! `Mat`.
+
+use core::*;
+use errors::*;
+use failure::Error as Error;
+use ndarray::{ArrayBase, ArrayView3, ArrayViewMut3, Data, Ix3, ShapeBuilder};
+
+fn check_element_type<T: DataType>(mat: &Mat) -> Result<Layout, Error> {
+    let layout = mat.layout();
+    if T::CHANNELS != 1 || T::DEPTH != layout.depth {
+        return Err(CvError::ElementTypeMismatch {
+            mat_depth: layout.depth,
+            mat_channels: layout.channels,
+            depth: T::DEPTH,
+            channels: T::CHANNELS,
+        }.into());
+    }
+    if mat.dims() > 2 {
+        return Err(CvError::DimensionMismatch {
+            dims: mat.dims() as usize,
+            indices: 2,
+        }.into());
+    }
+    Ok(layout)
+}
+
+/// Returns the size and type of a `Mat` holding an array of shape `dim`.
+fn mat_type<T: DataType>(dim: (usize, usize, usize)) -> Result<(i32, i32, CvType), Error> {
+    let (rows, cols, channels) = dim;
+    if rows > i32::MAX as usize || cols > i32::MAX as usize {
+        return Err(CvError::InvalidSize {
+            rows: rows as i32,
+            cols: cols as i32,
+        }.into());
+    }
+    if T::CHANNELS != 1 {
+        return Err(CvError::UnsupportedType {
+            depth: T::DEPTH,
+            channels: T::CHANNELS,
+        }.into());
+    }
+    let channels = channels as i32;
+    let cv_type = CvType::from_depth_and_channels(T::DEPTH, channels).ok_or(CvError::UnsupportedType {
+        depth: T::DEPTH,
+        channels: channels,
+    })?;
+    Ok((rows as i32, cols as i32, cv_type))
+}
+
+impl Mat {
+    /// Returns a view of the matrix as an array of shape `(rows, cols,
+    /// channels)`. Rows are `step1(0)` elements apart, so the matrix doesn't
+    /// have to be continuous.
+    pub fn as_array_view<T: DataType>(&self) -> Result<ArrayView3<'_, T>, Error> {
+        let layout = check_element_type::<T>(self)?;
+        let shape = (layout.rows as usize, layout.cols as usize, layout.channels as usize);
+        let data = self.data();
+        if data.is_null() || self.total() == 0 {
+            return Ok(ArrayView3::from_shape(shape, &[])?);
+        }
+        let strides = (self.step1(0), layout.channels as usize, 1);
+        Ok(unsafe { ArrayView3::from_shape_ptr(shape.strides(strides), data as *const T) })
+    }
+
+    /// Returns a mutable view of the matrix, see
+    /// [as_array_view](struct.Mat.html#method.as_array_view).
+    pub fn as_array_view_mut<T: DataType>(&mut self) -> Result<ArrayViewMut3<'_, T>, Error> {
+        let layout = check_element_type::<T>(self)?;
+        let shape = (layout.rows as usize, layout.cols as usize, layout.channels as usize);
+        let data = self.data() as *mut u8;
+        if data.is_null() || self.total() == 0 {
+            return Ok(ArrayViewMut3::from_shape(shape, &mut [])?);
+        }
+        let strides = (self.step1(0), layout.channels as usize, 1);
+        Ok(unsafe { ArrayViewMut3::from_shape_ptr(shape.strides(strides), data as *mut T) })
+    }
+
+    /// Creates a new `Mat` holding a copy of `array`, indexed by row, column
+    /// and channel. The type of the `Mat` follows from `T` and the number of
+    /// channels, e.g. `Cv32FC1` for an `Array3<f32>` of shape `(h, w, 1)`. Any
+    /// memory layout is accepted.
+    pub fn from_array<T, S>(array: &ArrayBase<S, Ix3>) -> Result<Mat, Error>
+    where
+        T: DataType,
+        S: Data<Elem = T>,
+    {
+        let (rows, cols, cv_type) = mat_type::<T>(array.dim())?;
+        let mut mat = Mat::with_size(rows, cols, cv_type as i32)?;
+        for (dst, src) in mat.as_mut_slice::<T>()?.iter_mut().zip(array.iter()) {
+            *dst = *src;
+        }
+        Ok(mat)
+    }
+
+    /// Wraps the elements of `array` in a `Mat` without copying them. The
+    /// array must be in standard (row-major, contiguous) layout; see
+    /// [from_array](struct.Mat.html#method.from_array) for the type.
+    pub fn from_array_view<'a, T, S>(array: &'a ArrayBase<S, Ix3>) -> Result<MatRef<'a>, Error>
+    where
+        T: DataType + 'a,
+        S: Data<Elem = T>,
+    {
+        let (rows, cols, cv_type) = mat_type::<T>(array.dim())?;
+        let data = array.as_slice().ok_or(CvError::NotContinuous)?;
+        Mat::from_slice(rows, cols, cv_type, data)
+    }
+}
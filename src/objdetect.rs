@@ -3,6 +3,7 @@
 
 use super::core::*;
 use super::errors::*;
+use super::persistence::*;
 use failure::Error as Error;
 use std::os::raw::{c_char, c_double, c_int};
 use std::ffi::CString;
@@ -13,7 +14,7 @@ use std::vec::Vec;
 pub trait ObjectDetect {
     /// Detects the object inside this image and returns a list of detections
     /// with their confidence.
-    fn detect(&self, image: &Mat) -> Vec<(Rect, f64)>;
+    fn detect(&self, image: &Mat) -> Result<Vec<(Rect, f64)>, Error>;
 }
 
 /// The opaque type for C
@@ -30,7 +31,7 @@ unsafe impl Send for CascadeClassifier {}
 
 extern "C" {
     fn cv_cascade_classifier_new() -> *mut CCascadeClassifier;
-    fn cv_cascade_classifier_load(cc: *mut CCascadeClassifier, p: *const c_char) -> bool;
+    fn cv_cascade_classifier_load(cc: *mut CCascadeClassifier, p: *const c_char, error: *mut CError) -> bool;
     fn cv_cascade_classifier_drop(p: *mut CCascadeClassifier);
     fn cv_cascade_classifier_detect(
         cc: *mut CCascadeClassifier,
@@ -41,15 +42,16 @@ extern "C" {
         flags: c_int,
         min_size: Size2i,
         max_size: Size2i,
+        error: *mut CError,
     );
 }
 
 impl ObjectDetect for CascadeClassifier {
-    fn detect(&self, image: &Mat) -> Vec<(Rect, f64)> {
-        self.detect_multiscale(image)
+    fn detect(&self, image: &Mat) -> Result<Vec<(Rect, f64)>, Error> {
+        Ok(self.detect_multiscale(image)?
             .into_iter()
             .map(|r| (r, 0f64))
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>())
     }
 }
 
@@ -72,7 +74,7 @@ impl CascadeClassifier {
     pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
         if let Some(p) = path.as_ref().to_str() {
             let s = CString::new(p)?;
-            if unsafe { cv_cascade_classifier_load(self.inner, (&s).as_ptr()) } {
+            if cv_try(|e| unsafe { cv_cascade_classifier_load(self.inner, (&s).as_ptr(), e) })? {
                 return Ok(());
             }
         }
@@ -84,7 +86,7 @@ impl CascadeClassifier {
 
     /// The default detection uses scale factor 1.1, minNeighbors 3, no min size
     /// or max size.
-    pub fn detect_multiscale(&self, mat: &Mat) -> Vec<Rect> {
+    pub fn detect_multiscale(&self, mat: &Mat) -> Result<Vec<Rect>, Error> {
         self.detect_with_params(mat, 1.1, 3, Size2i::default(), Size2i::default())
     }
 
@@ -109,9 +111,9 @@ impl CascadeClassifier {
         min_neighbors: i32,
         min_size: Size2i,
         max_size: Size2i,
-    ) -> Vec<Rect> {
+    ) -> Result<Vec<Rect>, Error> {
         let mut c_result = CVec::<Rect>::default();
-        unsafe {
+        cv_try(|e| unsafe {
             cv_cascade_classifier_detect(
                 self.inner,
                 mat.inner,
@@ -121,9 +123,10 @@ impl CascadeClassifier {
                 0,
                 min_size,
                 max_size,
+                e,
             )
-        }
-        c_result.unpack()
+        })?;
+        Ok(c_result.unpack())
     }
 }
 
@@ -180,7 +183,8 @@ impl Drop for SvmDetector {
 }
 
 /// Parameters that controls the behavior of HOG.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HogParams {
     /// Detection window size. Align to block size and block stride. The default
     /// is 64x128, trained the same as original paper.
@@ -288,6 +292,53 @@ impl Default for HogParams {
     }
 }
 
+/// Stored as a map keyed by the field names.
+impl ToFileStorage for HogParams {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.start_map(name, false)?;
+        fs.write("win_size", &self.win_size)?;
+        fs.write("block_size", &self.block_size)?;
+        fs.write("block_stride", &self.block_stride)?;
+        fs.write("cell_size", &self.cell_size)?;
+        fs.write("nbins", &self.nbins)?;
+        fs.write("win_sigma", &self.win_sigma)?;
+        fs.write("l2hys_threshold", &self.l2hys_threshold)?;
+        fs.write("gamma_correction", &self.gamma_correction)?;
+        fs.write("nlevels", &(self.nlevels as i32))?;
+        fs.write("hit_threshold", &self.hit_threshold)?;
+        fs.write("win_stride", &self.win_stride)?;
+        fs.write("padding", &self.padding)?;
+        fs.write("scale", &self.scale)?;
+        fs.write("group_threshold", &self.group_threshold)?;
+        fs.write("use_meanshift_grouping", &self.use_meanshift_grouping)?;
+        fs.write("final_threshold", &self.final_threshold)?;
+        fs.end()
+    }
+}
+
+impl FromFileNode for HogParams {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        Ok(HogParams {
+            win_size: node.read_field("win_size")?,
+            block_size: node.read_field("block_size")?,
+            block_stride: node.read_field("block_stride")?,
+            cell_size: node.read_field("cell_size")?,
+            nbins: node.read_field("nbins")?,
+            win_sigma: node.read_field("win_sigma")?,
+            l2hys_threshold: node.read_field("l2hys_threshold")?,
+            gamma_correction: node.read_field("gamma_correction")?,
+            nlevels: node.read_field::<i32>("nlevels")?.max(0) as usize,
+            hit_threshold: node.read_field("hit_threshold")?,
+            win_stride: node.read_field("win_stride")?,
+            padding: node.read_field("padding")?,
+            scale: node.read_field("scale")?,
+            group_threshold: node.read_field("group_threshold")?,
+            use_meanshift_grouping: node.read_field("use_meanshift_grouping")?,
+            final_threshold: node.read_field("final_threshold")?,
+        })
+    }
+}
+
 enum CHogDescriptor {}
 
 /// `HogDescriptor` implements Histogram of Oriented Gradients.
@@ -304,7 +355,7 @@ unsafe impl Send for HogDescriptor {}
 extern "C" {
     fn cv_hog_new() -> *mut CHogDescriptor;
     fn cv_hog_drop(hog: *mut CHogDescriptor);
-    fn cv_hog_set_svm_detector(hog: *mut CHogDescriptor, svm: *mut CSvmDetector);
+    fn cv_hog_set_svm_detector(hog: *mut CHogDescriptor, svm: *mut CSvmDetector, error: *mut CError);
     fn cv_hog_detect(
         hog: *mut CHogDescriptor,
         image: *mut CMat,
@@ -315,6 +366,7 @@ extern "C" {
         scale: c_double,
         final_threshold: c_double,
         use_means_shift: bool,
+        error: *mut CError,
     );
 }
 
@@ -328,10 +380,10 @@ impl Default for HogDescriptor {
 }
 
 impl ObjectDetect for HogDescriptor {
-    fn detect(&self, image: &Mat) -> Vec<(Rect, f64)> {
+    fn detect(&self, image: &Mat) -> Result<Vec<(Rect, f64)>, Error> {
         let mut detected = CVec::<Rect>::default();
         let mut weights = CVec::<c_double>::default();
-        unsafe {
+        cv_try(|e| unsafe {
             cv_hog_detect(
                 self.inner,
                 image.inner,
@@ -342,27 +394,30 @@ impl ObjectDetect for HogDescriptor {
                 self.params.scale,
                 self.params.final_threshold,
                 self.params.use_meanshift_grouping,
+                e,
             )
-        }
+        })?;
 
         let results = detected.unpack();
         let weights = weights.unpack();
-        results.into_iter().zip(weights).collect::<Vec<_>>()
+        Ok(results.into_iter().zip(weights).collect::<Vec<_>>())
     }
 }
 
 impl HogDescriptor {
     /// Creates a HogDescriptor with provided parameters.
-    pub fn with_params(params: HogParams) -> HogDescriptor {
-        HogDescriptor {
+    /// Returns a `Result` like `GpuHog::with_params`, so that both detectors
+    /// can be used interchangeably.
+    pub fn with_params(params: HogParams) -> Result<HogDescriptor, Error> {
+        Ok(HogDescriptor {
             inner: unsafe { cv_hog_new() },
             params: params,
-        }
+        })
     }
 
     /// Sets the SVM detector.
-    pub fn set_svm_detector(&mut self, detector: SvmDetector) {
-        unsafe { cv_hog_set_svm_detector(self.inner, detector.inner) }
+    pub fn set_svm_detector(&mut self, detector: SvmDetector) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_hog_set_svm_detector(self.inner, detector.inner, e) })
     }
 }
 
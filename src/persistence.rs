@@ -0,0 +1,661 @@
+//! XML/YAML/JSON persistence, see
+//! [cv::FileStorage](https://docs.opencv.org/3.4/da/d56/classcv_1_1FileStorage.html).
+//!
+//! A [FileStorage](struct.FileStorage.html) is written as a tree of maps and
+//! sequences and read back through [FileNode](struct.FileNode.html)s. Values
+//! are converted with the [ToFileStorage](trait.ToFileStorage.html) and
+//! [FromFileNode](trait.FromFileNode.html) traits:
+//!
+//! ```rust,no_run
+//! # extern crate cv;
+//! # fn main() {
+//! use cv::persistence::*;
+//! use cv::Rect;
+//!
+//! let mut fs = FileStorage::write_memory(FileStorageFormat::Yaml).unwrap();
+//! fs.write("roi", &Rect::new(10, 20, 30, 40)).unwrap();
+//! let yaml = fs.release_and_get_string().unwrap();
+//!
+//! let fs = FileStorage::read_memory(&yaml).unwrap();
+//! let roi: Rect = fs.read("roi").unwrap();
+//! # }
+//! ```
+
+use core::*;
+use errors::*;
+use failure::Error as Error;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_double, c_int};
+use std::path::Path;
+use std::ptr;
+
+enum CFileStorage {}
+enum CFileNode {}
+enum CFileNodeIter {}
+
+extern "C" {
+    fn cv_file_storage_new(source: *const c_char, flags: c_int, error: *mut CError) -> *mut CFileStorage;
+    fn cv_file_storage_drop(cfs: *mut CFileStorage);
+    fn cv_file_storage_is_opened(cfs: *const CFileStorage) -> bool;
+    fn cv_file_storage_release(cfs: *mut CFileStorage, error: *mut CError) -> *mut c_char;
+    fn cv_file_storage_start_struct(
+        cfs: *mut CFileStorage,
+        name: *const c_char,
+        seq: bool,
+        flow: bool,
+        error: *mut CError,
+    );
+    fn cv_file_storage_end_struct(cfs: *mut CFileStorage, error: *mut CError);
+    fn cv_file_storage_write_int(cfs: *mut CFileStorage, name: *const c_char, value: c_int, error: *mut CError);
+    fn cv_file_storage_write_real(cfs: *mut CFileStorage, name: *const c_char, value: c_double, error: *mut CError);
+    fn cv_file_storage_write_string(
+        cfs: *mut CFileStorage,
+        name: *const c_char,
+        value: *const c_char,
+        error: *mut CError,
+    );
+    fn cv_file_storage_write_mat(cfs: *mut CFileStorage, name: *const c_char, cmat: *const CMat, error: *mut CError);
+    fn cv_file_storage_root(cfs: *const CFileStorage) -> *mut CFileNode;
+
+    fn cv_file_node_drop(cnode: *mut CFileNode);
+    fn cv_file_node_type(cnode: *const CFileNode) -> c_int;
+    fn cv_file_node_size(cnode: *const CFileNode) -> usize;
+    fn cv_file_node_name(cnode: *const CFileNode, error: *mut CError) -> *mut c_char;
+    fn cv_file_node_get(cnode: *const CFileNode, key: *const c_char, error: *mut CError) -> *mut CFileNode;
+    fn cv_file_node_child(cnode: *const CFileNode, index: usize, error: *mut CError) -> *mut CFileNode;
+    fn cv_file_node_int(cnode: *const CFileNode, error: *mut CError) -> c_int;
+    fn cv_file_node_real(cnode: *const CFileNode, error: *mut CError) -> c_double;
+    fn cv_file_node_string(cnode: *const CFileNode, error: *mut CError) -> *mut c_char;
+    fn cv_file_node_mat(cnode: *const CFileNode, dst: *mut CMat, error: *mut CError);
+
+    fn cv_file_node_iter_new(cnode: *const CFileNode) -> *mut CFileNodeIter;
+    fn cv_file_node_iter_drop(citer: *mut CFileNodeIter);
+    fn cv_file_node_iter_next(citer: *mut CFileNodeIter, error: *mut CError) -> *mut CFileNode;
+}
+
+/// How a [FileStorage](struct.FileStorage.html) is opened.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FileStorageMode {
+    /// Open the file for reading.
+    Read = 0,
+
+    /// Create or truncate the file for writing.
+    Write = 1,
+
+    /// Append to the end of an existing file.
+    Append = 2,
+}
+
+/// Output format of an in-memory [FileStorage](struct.FileStorage.html).
+/// Files on disk take the format from their extension (`.xml`, `.yml`,
+/// `.yaml` or `.json`, optionally followed by `.gz`).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FileStorageFormat {
+    /// XML
+    Xml = 8,
+
+    /// YAML
+    Yaml = 16,
+
+    /// JSON
+    Json = 24,
+}
+
+impl FileStorageFormat {
+    fn extension(&self) -> &'static str {
+        match *self {
+            FileStorageFormat::Xml => ".xml",
+            FileStorageFormat::Yaml => ".yml",
+            FileStorageFormat::Json => ".json",
+        }
+    }
+}
+
+/// Read from or write to the source instead of a file.
+const MEMORY: c_int = 4;
+
+/// XML/YAML/JSON file storage.
+///
+/// While writing, the storage is a map: every value is written under a name.
+/// [start_seq](#method.start_seq) and [start_map](#method.start_map) open
+/// nested structures; inside a sequence, values are written with an empty
+/// name.
+#[derive(Debug)]
+pub struct FileStorage {
+    inner: *mut CFileStorage,
+}
+
+unsafe impl Send for FileStorage {}
+
+impl FileStorage {
+    fn new(source: &str, flags: c_int) -> Result<FileStorage, Error> {
+        let s = CString::new(source)?;
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_file_storage_new(s.as_ptr(), flags, e) });
+        let fs = FileStorage { inner: inner };
+        result?;
+        if !unsafe { cv_file_storage_is_opened(fs.inner) } {
+            Err(CvError::FileStorageOpenFailed {
+                source: source.to_owned(),
+            })?;
+        }
+        Ok(fs)
+    }
+
+    /// Opens the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P, mode: FileStorageMode) -> Result<FileStorage, Error> {
+        let path = path.as_ref();
+        let source = path.to_str().ok_or(CvError::InvalidPath {
+            path: path.to_path_buf(),
+        })?;
+        FileStorage::new(source, mode as c_int)
+    }
+
+    /// Reads the storage from its serialized `content`, as produced by
+    /// [release_and_get_string](#method.release_and_get_string).
+    pub fn read_memory(content: &str) -> Result<FileStorage, Error> {
+        FileStorage::new(content, FileStorageMode::Read as c_int | MEMORY)
+    }
+
+    /// Creates a storage written to memory in the given `format`. Retrieve the
+    /// content with [release_and_get_string](#method.release_and_get_string).
+    pub fn write_memory(format: FileStorageFormat) -> Result<FileStorage, Error> {
+        FileStorage::new(
+            format.extension(),
+            FileStorageMode::Write as c_int | MEMORY | format as c_int,
+        )
+    }
+
+    /// Closes the storage, flushing everything written to it. Dropping the
+    /// storage does the same but ignores errors.
+    pub fn release(self) -> Result<(), Error> {
+        self.release_and_get_string().map(|_| ())
+    }
+
+    /// Closes the storage and returns the content written to memory. The
+    /// string is empty for file storages.
+    pub fn release_and_get_string(self) -> Result<String, Error> {
+        let mut s = ptr::null_mut();
+        let result = cv_try(|e| s = unsafe { cv_file_storage_release(self.inner, e) });
+        let content = take_string(s);
+        result.map(|_| content)
+    }
+
+    /// Returns the top-level map of the storage.
+    pub fn root(&self) -> FileNode<'_> {
+        FileNode::from_raw(unsafe { cv_file_storage_root(self.inner) })
+    }
+
+    /// Reads the top-level element `name`.
+    pub fn read<T: FromFileNode>(&self, name: &str) -> Result<T, Error> {
+        self.root().read_field(name)
+    }
+
+    /// Writes `value` as the element `name`.
+    pub fn write<T: ToFileStorage + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), Error> {
+        value.write_to(self, name)
+    }
+
+    fn start_struct(&mut self, name: &str, seq: bool, flow: bool) -> Result<(), Error> {
+        let s = CString::new(name)?;
+        cv_try(|e| unsafe { cv_file_storage_start_struct(self.inner, s.as_ptr(), seq, flow, e) })
+    }
+
+    /// Starts a sequence named `name`. A `flow` sequence is written on a
+    /// single line in YAML (`[1, 2, 3]`).
+    pub fn start_seq(&mut self, name: &str, flow: bool) -> Result<(), Error> {
+        self.start_struct(name, true, flow)
+    }
+
+    /// Starts a map named `name`. A `flow` map is written on a single line in
+    /// YAML (`{ a: 1, b: 2 }`).
+    pub fn start_map(&mut self, name: &str, flow: bool) -> Result<(), Error> {
+        self.start_struct(name, false, flow)
+    }
+
+    /// Ends the innermost sequence or map.
+    pub fn end(&mut self) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_file_storage_end_struct(self.inner, e) })
+    }
+
+    /// Writes an integer.
+    pub fn write_i32(&mut self, name: &str, value: i32) -> Result<(), Error> {
+        let s = CString::new(name)?;
+        cv_try(|e| unsafe { cv_file_storage_write_int(self.inner, s.as_ptr(), value, e) })
+    }
+
+    /// Writes a floating-point number.
+    pub fn write_f64(&mut self, name: &str, value: f64) -> Result<(), Error> {
+        let s = CString::new(name)?;
+        cv_try(|e| unsafe { cv_file_storage_write_real(self.inner, s.as_ptr(), value, e) })
+    }
+
+    /// Writes a string.
+    pub fn write_str(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let s = CString::new(name)?;
+        let v = CString::new(value)?;
+        cv_try(|e| unsafe { cv_file_storage_write_string(self.inner, s.as_ptr(), v.as_ptr(), e) })
+    }
+
+    /// Writes a matrix.
+    pub fn write_mat(&mut self, name: &str, mat: &Mat) -> Result<(), Error> {
+        let s = CString::new(name)?;
+        cv_try(|e| unsafe { cv_file_storage_write_mat(self.inner, s.as_ptr(), mat.inner, e) })
+    }
+}
+
+impl Drop for FileStorage {
+    fn drop(&mut self) {
+        unsafe {
+            cv_file_storage_drop(self.inner);
+        }
+    }
+}
+
+/// Type of a [FileNode](struct.FileNode.html).
+#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive)]
+pub enum FileNodeType {
+    /// Missing node
+    None = 0,
+
+    /// Integer
+    Int = 1,
+
+    /// Floating-point number
+    Real = 2,
+
+    /// String
+    Str = 3,
+
+    /// Reference
+    Ref = 4,
+
+    /// Sequence
+    Seq = 5,
+
+    /// Map
+    Map = 6,
+}
+
+/// Mask of the node type in the flags returned by `cv::FileNode::type`.
+const TYPE_MASK: c_int = 7;
+
+/// A node of a [FileStorage](struct.FileStorage.html) opened for reading:
+/// a scalar, a string, a sequence or a map. Looking up a missing element
+/// gives a node of type [None](enum.FileNodeType.html#variant.None).
+#[derive(Debug)]
+pub struct FileNode<'a> {
+    inner: *mut CFileNode,
+    storage: PhantomData<&'a FileStorage>,
+}
+
+impl<'a> FileNode<'a> {
+    fn from_raw(inner: *mut CFileNode) -> FileNode<'a> {
+        FileNode {
+            inner: inner,
+            storage: PhantomData,
+        }
+    }
+
+    /// Returns the type of this node.
+    pub fn node_type(&self) -> FileNodeType {
+        let t = unsafe { cv_file_node_type(self.inner) } & TYPE_MASK;
+        ::num::FromPrimitive::from_i32(t).unwrap_or(FileNodeType::None)
+    }
+
+    /// Returns true if the node is missing.
+    pub fn is_none(&self) -> bool {
+        self.node_type() == FileNodeType::None
+    }
+
+    /// Returns the name of this node inside its parent map, or an empty string.
+    pub fn name(&self) -> Result<String, Error> {
+        cv_try(|e| take_string(unsafe { cv_file_node_name(self.inner, e) }))
+    }
+
+    /// Returns the number of elements of a sequence or map, 1 for other
+    /// nodes and 0 for a missing node.
+    pub fn len(&self) -> usize {
+        unsafe { cv_file_node_size(self.inner) }
+    }
+
+    /// Returns true if the node has no element.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element `key` of a map.
+    pub fn get(&self, key: &str) -> Result<FileNode<'a>, Error> {
+        let s = CString::new(key)?;
+        cv_try(|e| FileNode::from_raw(unsafe { cv_file_node_get(self.inner, s.as_ptr(), e) }))
+    }
+
+    /// Returns the `index`-th element of a sequence or map.
+    pub fn at(&self, index: usize) -> Result<FileNode<'a>, Error> {
+        let len = self.len();
+        if index >= len {
+            Err(CvError::IndexOutOfRange {
+                index: index,
+                bound: len,
+            })?;
+        }
+        cv_try(|e| FileNode::from_raw(unsafe { cv_file_node_child(self.inner, index, e) }))
+    }
+
+    /// Iterates over the elements of a sequence or map. The elements of a map
+    /// carry their key as [name](#method.name). The iterator only borrows the
+    /// storage, so it may outlive this node.
+    pub fn iter(&self) -> FileNodeIter<'a> {
+        FileNodeIter {
+            inner: unsafe { cv_file_node_iter_new(self.inner) },
+            remaining: self.len(),
+            storage: PhantomData,
+        }
+    }
+
+    /// Converts this node.
+    pub fn read<T: FromFileNode>(&self) -> Result<T, Error> {
+        T::from_node(self)
+    }
+
+    /// Converts the element `key` of a map, failing if it is missing.
+    pub fn read_field<T: FromFileNode>(&self, key: &str) -> Result<T, Error> {
+        let node = self.get(key)?;
+        if node.is_none() {
+            Err(CvError::MissingFileNode { name: key.to_owned() })?;
+        }
+        node.read()
+    }
+
+    fn expect(&self, types: &[FileNodeType]) -> Result<FileNodeType, Error> {
+        let t = self.node_type();
+        if !types.contains(&t) {
+            Err(CvError::UnexpectedFileNode {
+                expected: types
+                    .iter()
+                    .map(|t| format!("{:?}", t))
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                found: format!("{:?}", t),
+            })?;
+        }
+        Ok(t)
+    }
+
+    fn read_seq<T: FromFileNode>(&self, len: usize) -> Result<Vec<T>, Error> {
+        let values = Vec::<T>::from_node(self)?;
+        if values.len() != len {
+            Err(CvError::BufferSizeMismatch {
+                expected: len,
+                actual: values.len(),
+            })?;
+        }
+        Ok(values)
+    }
+}
+
+impl<'a> Drop for FileNode<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cv_file_node_drop(self.inner);
+        }
+    }
+}
+
+/// Iterator over the elements of a [FileNode](struct.FileNode.html).
+#[derive(Debug)]
+pub struct FileNodeIter<'a> {
+    inner: *mut CFileNodeIter,
+    remaining: usize,
+    storage: PhantomData<&'a FileStorage>,
+}
+
+impl<'a> Iterator for FileNodeIter<'a> {
+    type Item = Result<FileNode<'a>, Error>;
+
+    fn next(&mut self) -> Option<Result<FileNode<'a>, Error>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = cv_try(|e| FileNode::from_raw(unsafe { cv_file_node_iter_next(self.inner, e) }));
+        // Stop after an error, the native iterator is in an unknown state.
+        self.remaining = if node.is_ok() { self.remaining - 1 } else { 0 };
+        Some(node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> Drop for FileNodeIter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            cv_file_node_iter_drop(self.inner);
+        }
+    }
+}
+
+/// A value that can be written to a [FileStorage](struct.FileStorage.html).
+pub trait ToFileStorage {
+    /// Writes `self` as the element `name` of the current map, or as the
+    /// next element of the current sequence when `name` is empty.
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error>;
+}
+
+/// A value that can be read from a [FileNode](struct.FileNode.html).
+pub trait FromFileNode: Sized {
+    /// Converts `node`, failing if it has an unexpected type.
+    fn from_node(node: &FileNode) -> Result<Self, Error>;
+}
+
+impl ToFileStorage for i32 {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_i32(name, *self)
+    }
+}
+
+impl FromFileNode for i32 {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Int])?;
+        cv_try(|e| unsafe { cv_file_node_int(node.inner, e) })
+    }
+}
+
+impl ToFileStorage for f64 {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_f64(name, *self)
+    }
+}
+
+impl FromFileNode for f64 {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Int, FileNodeType::Real])?;
+        cv_try(|e| unsafe { cv_file_node_real(node.inner, e) })
+    }
+}
+
+impl ToFileStorage for f32 {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_f64(name, f64::from(*self))
+    }
+}
+
+impl FromFileNode for f32 {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        f64::from_node(node).map(|v| v as f32)
+    }
+}
+
+/// Booleans are stored as the integers 0 and 1, like OpenCV does.
+impl ToFileStorage for bool {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_i32(name, i32::from(*self))
+    }
+}
+
+impl FromFileNode for bool {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        i32::from_node(node).map(|v| v != 0)
+    }
+}
+
+impl ToFileStorage for str {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_str(name, self)
+    }
+}
+
+impl ToFileStorage for String {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_str(name, self)
+    }
+}
+
+impl FromFileNode for String {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Str])?;
+        cv_try(|e| take_string(unsafe { cv_file_node_string(node.inner, e) }))
+    }
+}
+
+impl ToFileStorage for Mat {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.write_mat(name, self)
+    }
+}
+
+impl FromFileNode for Mat {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Map])?;
+        cv_try_mat(|m, e| unsafe { cv_file_node_mat(node.inner, m, e) })
+    }
+}
+
+impl<T: ToFileStorage> ToFileStorage for [T] {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.start_seq(name, false)?;
+        for value in self {
+            value.write_to(fs, "")?;
+        }
+        fs.end()
+    }
+}
+
+impl<T: ToFileStorage> ToFileStorage for Vec<T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        self.as_slice().write_to(fs, name)
+    }
+}
+
+impl<T: FromFileNode> FromFileNode for Vec<T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Seq])?;
+        node.iter().map(|n| T::from_node(&n?)).collect()
+    }
+}
+
+impl<T: ToFileStorage> ToFileStorage for BTreeMap<String, T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        fs.start_map(name, false)?;
+        for (key, value) in self {
+            value.write_to(fs, key)?;
+        }
+        fs.end()
+    }
+}
+
+impl<T: FromFileNode> FromFileNode for BTreeMap<String, T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        node.expect(&[FileNodeType::Map])?;
+        node.iter()
+            .map(|n| {
+                let n = n?;
+                Ok((n.name()?, T::from_node(&n)?))
+            })
+            .collect()
+    }
+}
+
+/// Writes `values` as a flow sequence, the way OpenCV stores its geometry
+/// types.
+fn write_flow_seq<T: ToFileStorage>(fs: &mut FileStorage, name: &str, values: &[T]) -> Result<(), Error> {
+    fs.start_seq(name, true)?;
+    for value in values {
+        value.write_to(fs, "")?;
+    }
+    fs.end()
+}
+
+/// Stored as `[x, y]`.
+impl<T: ToFileStorage + Copy> ToFileStorage for Point2<T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        write_flow_seq(fs, name, &[self.x, self.y])
+    }
+}
+
+impl<T: FromFileNode + Copy> FromFileNode for Point2<T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        let v = node.read_seq::<T>(2)?;
+        Ok(Point2::new(v[0], v[1]))
+    }
+}
+
+/// Stored as `[x, y, z]`.
+impl<T: ToFileStorage + Copy> ToFileStorage for Point3<T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        write_flow_seq(fs, name, &[self.x, self.y, self.z])
+    }
+}
+
+impl<T: FromFileNode + Copy> FromFileNode for Point3<T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        let v = node.read_seq::<T>(3)?;
+        Ok(Point3::new(v[0], v[1], v[2]))
+    }
+}
+
+/// Stored as `[width, height]`.
+impl<T: ToFileStorage + Copy> ToFileStorage for Size2<T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        write_flow_seq(fs, name, &[self.width, self.height])
+    }
+}
+
+impl<T: FromFileNode + Copy> FromFileNode for Size2<T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        let v = node.read_seq::<T>(2)?;
+        Ok(Size2::new(v[0], v[1]))
+    }
+}
+
+/// Stored as `[x, y, width, height]`.
+impl<T: ToFileStorage + Copy> ToFileStorage for Rect2<T> {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        write_flow_seq(fs, name, &[self.x, self.y, self.width, self.height])
+    }
+}
+
+impl<T: FromFileNode + Copy> FromFileNode for Rect2<T> {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        let v = node.read_seq::<T>(4)?;
+        Ok(Rect2::new(v[0], v[1], v[2], v[3]))
+    }
+}
+
+/// Stored as `[v0, v1, v2, v3]`.
+impl ToFileStorage for Scalar {
+    fn write_to(&self, fs: &mut FileStorage, name: &str) -> Result<(), Error> {
+        let v: [f64; 4] = (*self).into();
+        write_flow_seq(fs, name, &v)
+    }
+}
+
+impl FromFileNode for Scalar {
+    fn from_node(node: &FileNode) -> Result<Self, Error> {
+        let v = node.read_seq::<f64>(4)?;
+        Ok(Scalar::new(v[0], v[1], v[2], v[3]))
+    }
+}
@@ -0,0 +1,443 @@
+//! Helpers for tests comparing processed images with each other or with
+//! reference ("golden") images, enabled by the `testing` feature.
+//!
+//! [assert_mat_near!](../macro.assert_mat_near.html) compares two `Mat` in
+//! memory. [Golden](struct.Golden.html) compares a `Mat` with a PNG file by
+//! norm, [psnr](fn.psnr.html) or [ssim](fn.ssim.html); on failure it dumps the
+//! actual and expected images and a heatmap of their difference so CI can
+//! keep them as artifacts. Goldens are (re)generated by running the tests
+//! with the environment variable `CV_UPDATE_GOLDENS=1`.
+//!
+//! ```rust,no_run
+//! extern crate cv;
+//!
+//! use cv::*;
+//! use cv::testing::Golden;
+//!
+//! # fn main() {
+//! let a = Mat::eye(3, 3, CvType::Cv64FC1).unwrap();
+//! let b = Mat::eye(3, 3, CvType::Cv64FC1).unwrap();
+//! assert_mat_near!(a, b, 1e-9);
+//!
+//! let frame = Mat::ones(480, 640, CvType::Cv8UC3).unwrap();
+//! Golden::new("tests/goldens").assert_psnr("ones", &frame, 40.0);
+//! # }
+//! ```
+
+use core::*;
+use errors::CvError;
+use failure::Error as Error;
+use imgcodecs::ImreadModes;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes [Golden](struct.Golden.html) write the
+/// actual images as the new goldens instead of comparing them.
+pub const UPDATE_GOLDENS_ENV: &str = "CV_UPDATE_GOLDENS";
+
+/// Environment variable overriding the directory where
+/// [Golden](struct.Golden.html) dumps the images of failed comparisons.
+pub const GOLDEN_FAILURES_ENV: &str = "CV_GOLDEN_FAILURES";
+
+/// Asserts that two `Mat` have the same size and type and that the norm of
+/// their difference is at most the tolerance. The norm is
+/// `NormTypes::NormInf`, i.e. the largest per-element difference, unless
+/// given as the fourth argument.
+///
+/// ```rust,no_run
+/// # extern crate cv;
+/// # use cv::*;
+/// # fn main() {
+/// # let a = Mat::new();
+/// # let b = Mat::new();
+/// assert_mat_near!(a, b, 1e-6);
+/// assert_mat_near!(a, b, 0.5, NormTypes::NormL2);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_mat_near {
+    ($actual: expr, $expected: expr, $tolerance: expr) => {
+        assert_mat_near!($actual, $expected, $tolerance, $crate::NormTypes::NormInf)
+    };
+    ($actual: expr, $expected: expr, $tolerance: expr, $norm_type: expr) => {
+        if let Err(message) = $crate::testing::mat_near(&$actual, &$expected, $tolerance, $norm_type) {
+            panic!(
+                "assertion failed: `{} ≈ {}`: {}",
+                stringify!($actual),
+                stringify!($expected),
+                message
+            );
+        }
+    };
+}
+
+/// Checks the condition of [assert_mat_near!](../macro.assert_mat_near.html)
+/// and describes the difference if it doesn't hold.
+pub fn mat_near(actual: &Mat, expected: &Mat, tolerance: f64, norm_type: NormTypes) -> Result<(), String> {
+    if actual.sizes() != expected.sizes() || actual.cv_type().ok() != expected.cv_type().ok() {
+        return Err(format!("got {}, expected {}", actual.header(), expected.header()));
+    }
+    if actual.total() == 0 {
+        return Ok(());
+    }
+    let distance = actual
+        .norm_between(expected, norm_type)
+        .map_err(|e| e.to_string())?;
+    if distance > tolerance {
+        return Err(format!(
+            "{:?} of the difference is {}, more than {}",
+            norm_type, distance, tolerance
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the largest value of a pixel for the depth of `mat`: 255 for
+/// 8-bit, 65535 for 16-bit and 1 for floating point images.
+fn max_pixel_value(mat: &Mat) -> Result<f64, Error> {
+    match mat.depth {
+        0 => Ok(255.0),
+        2 => Ok(65_535.0),
+        5 | 6 => Ok(1.0),
+        depth => Err(CvError::UnsupportedType {
+            depth: depth,
+            channels: mat.channels,
+        }.into()),
+    }
+}
+
+fn check_same_shape(actual: &Mat, expected: &Mat) -> Result<(), Error> {
+    if actual.rows != expected.rows || actual.cols != expected.cols {
+        return Err(CvError::InvalidSize {
+            rows: actual.rows,
+            cols: actual.cols,
+        }.into());
+    }
+    let (actual_type, expected_type) = (actual.cv_type()?, expected.cv_type()?);
+    if actual_type != expected_type {
+        return Err(CvError::ElementTypeMismatch {
+            mat_depth: actual_type.depth(),
+            mat_channels: actual_type.channels(),
+            depth: expected_type.depth(),
+            channels: expected_type.channels(),
+        }.into());
+    }
+    Ok(())
+}
+
+/// Computes the peak signal-to-noise ratio between two images of the same
+/// size and type, in dB. Identical images give infinity; 8-bit images that
+/// look the same usually score above 40.
+pub fn psnr(actual: &Mat, expected: &Mat) -> Result<f64, Error> {
+    check_same_shape(actual, expected)?;
+    let max = max_pixel_value(actual)?;
+    let count = actual.total() * actual.channels as usize;
+    if count == 0 {
+        return Ok(f64::INFINITY);
+    }
+    let mse = actual.norm_between(expected, NormTypes::NormL2Sqr)? / count as f64;
+    if mse == 0.0 {
+        return Ok(f64::INFINITY);
+    }
+    Ok(10.0 * (max * max / mse).log10())
+}
+
+/// Copies the elements of `mat` as `f64`, one `Vec` per channel.
+fn planes(mat: &Mat) -> Result<Vec<Vec<f64>>, Error> {
+    let cv_type = CvType::from_depth_and_channels(6, mat.channels).ok_or(CvError::UnsupportedType {
+        depth: 6,
+        channels: mat.channels,
+    })?;
+    let converted = mat.convert_to(cv_type, 1.0, 0.0)?;
+    let channels = mat.channels as usize;
+    let mut planes = vec![Vec::with_capacity(mat.total()); channels];
+    for pixel in converted.as_slice::<f64>()?.chunks(channels) {
+        for (plane, &v) in planes.iter_mut().zip(pixel) {
+            plane.push(v);
+        }
+    }
+    Ok(planes)
+}
+
+/// Blurs a plane with the 11x11 Gaussian window (σ = 1.5) of SSIM,
+/// replicating the border.
+fn gaussian_blur(plane: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+    const RADIUS: isize = 5;
+    let mut kernel = (-RADIUS..RADIUS + 1)
+        .map(|i| (-(i * i) as f64 / (2.0 * 1.5 * 1.5)).exp())
+        .collect::<Vec<_>>();
+    let sum: f64 = kernel.iter().sum();
+    for k in &mut kernel {
+        *k /= sum;
+    }
+
+    let clamp = |i: isize, len: usize| i.max(0).min(len as isize - 1) as usize;
+    let mut horizontal = vec![0.0; plane.len()];
+    for r in 0..rows {
+        for c in 0..cols {
+            horizontal[r * cols + c] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * plane[r * cols + clamp(c as isize + k as isize - RADIUS, cols)])
+                .sum();
+        }
+    }
+    let mut blurred = vec![0.0; plane.len()];
+    for r in 0..rows {
+        for c in 0..cols {
+            blurred[r * cols + c] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * horizontal[clamp(r as isize + k as isize - RADIUS, rows) * cols + c])
+                .sum();
+        }
+    }
+    blurred
+}
+
+/// Computes the mean structural similarity index between two images of the
+/// same size and type, averaged over the channels. It is 1 for identical
+/// images and lower the more their local structure differs; unlike
+/// [psnr](fn.psnr.html) it tolerates small uniform changes such as
+/// re-encoding noise.
+pub fn ssim(actual: &Mat, expected: &Mat) -> Result<f64, Error> {
+    check_same_shape(actual, expected)?;
+    let max = max_pixel_value(actual)?;
+    let (c1, c2) = ((0.01 * max) * (0.01 * max), (0.03 * max) * (0.03 * max));
+    let (rows, cols) = (actual.rows as usize, actual.cols as usize);
+    if rows == 0 || cols == 0 {
+        return Ok(1.0);
+    }
+
+    let (xs, ys) = (planes(actual)?, planes(expected)?);
+    let mut total = 0.0;
+    for (x, y) in xs.iter().zip(&ys) {
+        let product = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).collect::<Vec<_>>();
+        let mu_x = gaussian_blur(x, rows, cols);
+        let mu_y = gaussian_blur(y, rows, cols);
+        let xx = gaussian_blur(&product(x, x), rows, cols);
+        let yy = gaussian_blur(&product(y, y), rows, cols);
+        let xy = gaussian_blur(&product(x, y), rows, cols);
+
+        let sum: f64 = (0..x.len())
+            .map(|i| {
+                let (mx, my) = (mu_x[i], mu_y[i]);
+                let sigma_x = xx[i] - mx * mx;
+                let sigma_y = yy[i] - my * my;
+                let sigma_xy = xy[i] - mx * my;
+                ((2.0 * mx * my + c1) * (2.0 * sigma_xy + c2))
+                    / ((mx * mx + my * my + c1) * (sigma_x + sigma_y + c2))
+            })
+            .sum();
+        total += sum / x.len() as f64;
+    }
+    Ok(total / xs.len() as f64)
+}
+
+/// Renders the per-pixel difference of two images of the same size and type
+/// as a `Cv8UC3` heatmap, from blue (no difference) to red (the largest
+/// difference). The largest difference over the channels is used for each
+/// pixel.
+pub fn diff_heatmap(actual: &Mat, expected: &Mat) -> Result<Mat, Error> {
+    check_same_shape(actual, expected)?;
+    let planes = planes(&actual.abs_diff(expected)?)?;
+    let len = actual.total();
+    let diff = (0..len)
+        .map(|i| planes.iter().map(|p| p[i]).fold(0.0, f64::max))
+        .collect::<Vec<_>>();
+    let max = diff.iter().cloned().fold(0.0, f64::max);
+
+    let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut pixels = Vec::with_capacity(len * 3);
+    for d in diff {
+        let t = if max > 0.0 { d / max } else { 0.0 };
+        pixels.push(channel(1.5 - (4.0 * t - 1.0).abs()));
+        pixels.push(channel(1.5 - (4.0 * t - 2.0).abs()));
+        pixels.push(channel(1.5 - (4.0 * t - 3.0).abs()));
+    }
+    Mat::from_vec(actual.rows, actual.cols, CvType::Cv8UC3, pixels)
+}
+
+/// How [Golden](struct.Golden.html) compares an image with its golden.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Comparison {
+    /// The norm of the difference must be at most `tolerance`, see
+    /// [assert_mat_near!](../macro.assert_mat_near.html).
+    Norm {
+        /// Largest accepted norm
+        tolerance: f64,
+        /// Norm of the difference
+        norm_type: NormTypes,
+    },
+    /// The [psnr](fn.psnr.html) must be at least this many dB.
+    Psnr(f64),
+    /// The [ssim](fn.ssim.html) must be at least this value.
+    Ssim(f64),
+}
+
+impl Comparison {
+    /// Compares the images, returning a description of the failure.
+    fn check(&self, actual: &Mat, expected: &Mat) -> Result<(), String> {
+        let score = |s: Result<f64, Error>| s.map_err(|e| e.to_string());
+        match *self {
+            Comparison::Norm { tolerance, norm_type } => mat_near(actual, expected, tolerance, norm_type),
+            Comparison::Psnr(min) => {
+                let psnr = score(psnr(actual, expected))?;
+                if psnr < min {
+                    return Err(format!("PSNR is {:.2} dB, less than {} dB", psnr, min));
+                }
+                Ok(())
+            }
+            Comparison::Ssim(min) => {
+                let ssim = score(ssim(actual, expected))?;
+                if ssim < min {
+                    return Err(format!("SSIM is {:.4}, less than {}", ssim, min));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A directory of golden PNG images that test results are compared with.
+///
+/// Each golden is stored as `<dir>/<name>.png`, so only 8-bit and 16-bit
+/// images with 1, 3 or 4 channels can be compared. When a comparison fails,
+/// `<name>.actual.png`, `<name>.expected.png` and `<name>.diff.png` (see
+/// [diff_heatmap](fn.diff_heatmap.html)) are written to the failure
+/// directory before panicking.
+#[derive(Debug, Clone)]
+pub struct Golden {
+    dir: PathBuf,
+    failure_dir: PathBuf,
+    update: bool,
+}
+
+/// Returns true if `CV_UPDATE_GOLDENS` is set to anything but an empty
+/// string or `0`.
+pub fn update_goldens() -> bool {
+    env::var(UPDATE_GOLDENS_ENV)
+        .map(|v| !v.is_empty() && v != "0")
+        .unwrap_or(false)
+}
+
+impl Golden {
+    /// Uses the goldens in `dir`. Failures are dumped into the directory
+    /// named by `CV_GOLDEN_FAILURES`, or `cv-golden-failures` in the
+    /// temporary directory, and the goldens are regenerated if
+    /// [update_goldens](fn.update_goldens.html) is true.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Golden {
+        let failure_dir = env::var_os(GOLDEN_FAILURES_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| env::temp_dir().join("cv-golden-failures"));
+        Golden {
+            dir: dir.as_ref().to_path_buf(),
+            failure_dir: failure_dir,
+            update: update_goldens(),
+        }
+    }
+
+    /// Sets the directory where failed comparisons are dumped.
+    pub fn failure_dir<P: AsRef<Path>>(mut self, dir: P) -> Golden {
+        self.failure_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Overrides whether the goldens are regenerated rather than compared.
+    pub fn update(mut self, update: bool) -> Golden {
+        self.update = update;
+        self
+    }
+
+    /// Returns the path of the golden `name`.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", name))
+    }
+
+    /// Compares `actual` with the golden `name`, or writes it as the new
+    /// golden in update mode. Returns a description of the failure, after
+    /// dumping the images.
+    pub fn check(&self, name: &str, actual: &Mat, comparison: Comparison) -> Result<(), String> {
+        let path = self.path(name);
+        if self.update {
+            return write_png(actual, &path).map_err(|e| format!("failed to write {}: {}", path.display(), e));
+        }
+
+        let expected = Mat::from_path(&path, ImreadModes::ImreadUnchanged).map_err(|e| {
+            format!(
+                "failed to read golden {}: {} (run with {}=1 to create it)",
+                path.display(),
+                e,
+                UPDATE_GOLDENS_ENV
+            )
+        })?;
+        comparison.check(actual, &expected).map_err(|message| {
+            format!(
+                "{} does not match {}: {}\n{}\n(run with {}=1 to accept the new result)",
+                name,
+                path.display(),
+                message,
+                self.dump(name, actual, &expected),
+                UPDATE_GOLDENS_ENV
+            )
+        })
+    }
+
+    /// Writes the images of a failed comparison and describes where they
+    /// are.
+    fn dump(&self, name: &str, actual: &Mat, expected: &Mat) -> String {
+        let mut written = Vec::new();
+        let mut dump = |suffix: &str, mat: Result<Mat, Error>| {
+            let path = self.failure_dir.join(format!("{}.{}.png", name, suffix));
+            match mat.and_then(|m| write_png(&m, &path)) {
+                Ok(()) => written.push(format!("  wrote {}", path.display())),
+                Err(e) => written.push(format!("  failed to write {}: {}", path.display(), e)),
+            }
+        };
+        dump("actual", actual.try_clone());
+        dump("expected", expected.try_clone());
+        dump("diff", diff_heatmap(actual, expected));
+        written.join("\n")
+    }
+
+    /// Asserts that the norm of the difference between `actual` and the
+    /// golden `name` is at most `tolerance`; see [check](#method.check).
+    pub fn assert_near(&self, name: &str, actual: &Mat, tolerance: f64, norm_type: NormTypes) {
+        self.assert(
+            name,
+            actual,
+            Comparison::Norm {
+                tolerance: tolerance,
+                norm_type: norm_type,
+            },
+        );
+    }
+
+    /// Asserts that the PSNR of `actual` against the golden `name` is at
+    /// least `min_psnr` dB; see [check](#method.check).
+    pub fn assert_psnr(&self, name: &str, actual: &Mat, min_psnr: f64) {
+        self.assert(name, actual, Comparison::Psnr(min_psnr));
+    }
+
+    /// Asserts that the SSIM of `actual` against the golden `name` is at
+    /// least `min_ssim`; see [check](#method.check).
+    pub fn assert_ssim(&self, name: &str, actual: &Mat, min_ssim: f64) {
+        self.assert(name, actual, Comparison::Ssim(min_ssim));
+    }
+
+    fn assert(&self, name: &str, actual: &Mat, comparison: Comparison) {
+        if let Err(message) = self.check(name, actual, comparison) {
+            panic!("{}", message);
+        }
+    }
+}
+
+fn write_png(mat: &Mat, path: &Path) -> Result<(), Error> {
+    let buf = mat.imencode(".png", Vec::new())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, buf)?;
+    Ok(())
+}
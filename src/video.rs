@@ -4,6 +4,7 @@ pub mod tracking {
     //! Object Tracking, see [OpenCV video
     //! track](http://docs.opencv.org/3.1.0/dc/d6b/group__video__track.html)
 
+    use failure::Error as Error;
     use super::super::core::*;
     // =========================================================================
     //   VideoTrack
@@ -24,7 +25,12 @@ pub mod tracking {
     extern "C" {
         fn cv_term_criteria_new(t: i32, count: i32, epsilon: f64) -> *mut CTermCriteria;
         fn cv_term_criteria_drop(criteria: *mut CTermCriteria);
-        fn cv_camshift(image: *mut CMat, w: Rect, c_criteria: *const CTermCriteria) -> RotatedRect;
+        fn cv_camshift(
+            image: *mut CMat,
+            w: Rect,
+            c_criteria: *const CTermCriteria,
+            error: *mut CError,
+        ) -> RotatedRect;
     }
 
     /// Termination criteria for iterative algorithms.
@@ -56,8 +62,8 @@ pub mod tracking {
         ///
         /// * `wndw` - initial search window.
         /// * `criteria` - stop criteria for the underlying meanShift.
-        pub fn camshift(&self, wndw: Rect, criteria: &TermCriteria) -> RotatedRect {
-            unsafe { cv_camshift(self.inner, wndw, criteria.c_criteria) }
+        pub fn camshift(&self, wndw: Rect, criteria: &TermCriteria) -> Result<RotatedRect, Error> {
+            cv_try(|e| unsafe { cv_camshift(self.inner, wndw, criteria.c_criteria, e) })
         }
     }
 }
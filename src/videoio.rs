@@ -1,8 +1,11 @@
 //! Media I/O, see [OpenCV
 //! videoio](http://docs.opencv.org/3.1.0/dd/de7/group__videoio.html)
 
-use core::{CMat, Mat, Size2i};
+use core::{cv_try, CError, CMat, Mat, Size2i};
+use failure::Error as Error;
+use std::ffi::CString;
 use std::os::raw::{c_char, c_double, c_int};
+use std::ptr;
 
 // =============================================================================
 //   VideoCapture
@@ -20,18 +23,19 @@ pub struct VideoCapture {
 unsafe impl Send for VideoCapture {}
 
 extern "C" {
-    fn cv_videocapture_new(index: c_int) -> *mut CvVideoCapture;
-    fn cv_videocapture_from_file(path: *const c_char) -> *mut CvVideoCapture;
+    fn cv_videocapture_new(index: c_int, error: *mut CError) -> *mut CvVideoCapture;
+    fn cv_videocapture_from_file(path: *const c_char, error: *mut CError) -> *mut CvVideoCapture;
     fn cv_videocapture_is_opened(ccap: *const CvVideoCapture) -> bool;
-    fn cv_videocapture_read(v: *mut CvVideoCapture, m: *mut CMat) -> bool;
+    fn cv_videocapture_read(v: *mut CvVideoCapture, m: *mut CMat, error: *mut CError) -> bool;
     fn cv_videocapture_drop(cap: *mut CvVideoCapture);
-    fn cv_videocapture_set(cap: *mut CvVideoCapture, property: c_int, value: c_double) -> bool;
-    fn cv_videocapture_get(cap: *mut CvVideoCapture, property: c_int) -> c_double;
+    fn cv_videocapture_set(cap: *mut CvVideoCapture, property: c_int, value: c_double, error: *mut CError) -> bool;
+    fn cv_videocapture_get(cap: *mut CvVideoCapture, property: c_int, error: *mut CError) -> c_double;
 }
 
 #[allow(missing_docs)]
 /// Video capture's property identifier.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CapProp {
     /// Current position of the video file in milliseconds or video capture
     /// timestamp.
@@ -117,18 +121,22 @@ pub enum CapProp {
 impl VideoCapture {
     /// Creates a capture device with specified camera id. If there is a single
     /// camera connected, just pass 0.
-    pub fn new(index: i32) -> Self {
-        let cap = unsafe { cv_videocapture_new(index) };
-        VideoCapture { inner: cap }
+    pub fn new(index: i32) -> Result<Self, Error> {
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_videocapture_new(index, e) });
+        let cap = VideoCapture { inner: inner };
+        result.map(|_| cap)
     }
 
     /// Creates a capture device with the path of a video file (eg. video.avi).
     /// This also supports image sequence, eg. img_%02d.jpg, which will read
     /// samples like img_00.jpg, img_01.jpg, img_02.jpg, ...).
-    pub fn from_path(path: &str) -> Self {
-        let s = ::std::ffi::CString::new(path).unwrap();
-        let cap = unsafe { cv_videocapture_from_file((&s).as_ptr()) };
-        VideoCapture { inner: cap }
+    pub fn from_path(path: &str) -> Result<Self, Error> {
+        let s = CString::new(path)?;
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_videocapture_from_file((&s).as_ptr(), e) });
+        let cap = VideoCapture { inner: inner };
+        result.map(|_| cap)
     }
 
     /// Returns true if video capturing has been initialized already.
@@ -142,29 +150,26 @@ impl VideoCapture {
     /// from decode and return the just grabbed frame.
     ///
     /// If no frames has been grabbed (camera has been disconnected, or there
-    /// are no more frames in video file), the methods return `None`.
-    pub fn read(&self) -> Option<Mat> {
+    /// are no more frames in video file), the methods return `Ok(None)`.
+    pub fn read(&self) -> Result<Option<Mat>, Error> {
         let inner = CMat::new();
-        let status = unsafe { cv_videocapture_read(self.inner, inner) };
-        if status {
-            Some(Mat::from_raw(inner))
-        } else {
-            None
-        }
+        let result = cv_try(|e| unsafe { cv_videocapture_read(self.inner, inner, e) });
+        let mat = Mat::from_raw(inner);
+        result.map(|grabbed| if grabbed { Some(mat) } else { None })
     }
 
     /// Sets a property in the `VideoCapture`.
-    pub fn set(&self, property: CapProp, value: f64) -> bool {
-        unsafe { cv_videocapture_set(self.inner, property as c_int, value) }
+    pub fn set(&self, property: CapProp, value: f64) -> Result<bool, Error> {
+        cv_try(|e| unsafe { cv_videocapture_set(self.inner, property as c_int, value, e) })
     }
 
     /// Gets a property in the `VideoCapture`.
-    pub fn get(&self, property: CapProp) -> Option<f64> {
-        let ret = unsafe { cv_videocapture_get(self.inner, property as c_int) };
+    pub fn get(&self, property: CapProp) -> Result<Option<f64>, Error> {
+        let ret = cv_try(|e| unsafe { cv_videocapture_get(self.inner, property as c_int, e) })?;
         if ret != 0.0 {
-            Some(ret)
+            Ok(Some(ret))
         } else {
-            None
+            Ok(None)
         }
     }
 }
@@ -201,6 +206,7 @@ extern "C" {
         fps: c_double,
         frame_size: Size2i,
         is_color: bool,
+        error: *mut CError,
     ) -> *mut CvVideoWriter;
     fn cv_videowriter_drop(w: *mut CvVideoWriter);
 
@@ -211,11 +217,12 @@ extern "C" {
         fps: c_double,
         frame_size: Size2i,
         is_color: bool,
+        error: *mut CError,
     ) -> bool;
     fn cv_videowriter_is_opened(w: *mut CvVideoWriter) -> bool;
-    fn cv_videowriter_write(w: *mut CvVideoWriter, m: *mut CMat);
-    fn cv_videowriter_set(w: *mut CvVideoWriter, property: c_int, value: c_double) -> bool;
-    fn cv_videowriter_get(w: *mut CvVideoWriter, property: c_int) -> c_double;
+    fn cv_videowriter_write(w: *mut CvVideoWriter, m: *mut CMat, error: *mut CError);
+    fn cv_videowriter_set(w: *mut CvVideoWriter, property: c_int, value: c_double, error: *mut CError) -> bool;
+    fn cv_videowriter_get(w: *mut CvVideoWriter, property: c_int, error: *mut CError) -> c_double;
 }
 
 impl VideoWriter {
@@ -230,10 +237,12 @@ impl VideoWriter {
     /// * is_color – If it is not zero, the encoder will expect and encode color
     ///   frames, otherwise it will work with grayscale frames (the flag is
     ///   currently supported on Windows only).
-    pub fn new(path: &str, fourcc: i32, fps: f64, frame_size: Size2i, is_color: bool) -> VideoWriter {
-        let s = ::std::ffi::CString::new(path).unwrap();
-        let writer = unsafe { cv_videowriter_new((&s).as_ptr(), fourcc, fps, frame_size, is_color) };
-        VideoWriter { inner: writer }
+    pub fn new(path: &str, fourcc: i32, fps: f64, frame_size: Size2i, is_color: bool) -> Result<VideoWriter, Error> {
+        let s = CString::new(path)?;
+        let mut inner = ptr::null_mut();
+        let result = cv_try(|e| inner = unsafe { cv_videowriter_new((&s).as_ptr(), fourcc, fps, frame_size, is_color, e) });
+        let writer = VideoWriter { inner: inner };
+        result.map(|_| writer)
     }
 
     /// `VideoWriter` constructor.
@@ -247,15 +256,17 @@ impl VideoWriter {
     /// * is_color – If it is not zero, the encoder will expect and encode color
     ///   frames, otherwise it will work with grayscale frames (the flag is
     ///   currently supported on Windows only).
-    pub fn open(&self, path: &str, fourcc: i32, fps: f64, frame_size: Size2i, is_color: bool) -> bool {
-        let s = ::std::ffi::CString::new(path).unwrap();
-        unsafe { cv_videowriter_open(self.inner, (&s).as_ptr(), fourcc, fps, frame_size, is_color) }
+    ///
+    /// Returns whether the file could be opened.
+    pub fn open(&self, path: &str, fourcc: i32, fps: f64, frame_size: Size2i, is_color: bool) -> Result<bool, Error> {
+        let s = CString::new(path)?;
+        cv_try(|e| unsafe { cv_videowriter_open(self.inner, (&s).as_ptr(), fourcc, fps, frame_size, is_color, e) })
     }
 
     /// Writes the specified image to video file. It must have the same size as
     /// has been specified when opening the video writer.
-    pub fn write(&self, mat: &Mat) {
-        unsafe { cv_videowriter_write(self.inner, mat.inner) }
+    pub fn write(&self, mat: &Mat) -> Result<(), Error> {
+        cv_try(|e| unsafe { cv_videowriter_write(self.inner, mat.inner, e) })
     }
 
     /// Returns true if video writer has been initialized already.
@@ -265,17 +276,17 @@ impl VideoWriter {
 
     /// Sets a property in the `VideoWriter`.
     /// Note: `VideoWriterProperty::FrameBytes` is read-only.
-    pub fn set(&self, property: VideoWriterProperty, value: f64) -> bool {
-        unsafe { cv_videowriter_set(self.inner, property as c_int, value) }
+    pub fn set(&self, property: VideoWriterProperty, value: f64) -> Result<bool, Error> {
+        cv_try(|e| unsafe { cv_videowriter_set(self.inner, property as c_int, value, e) })
     }
 
     /// Gets a property in the `VideoWriter`.
-    pub fn get(&self, property: VideoWriterProperty) -> Option<f64> {
-        let ret = unsafe { cv_videowriter_get(self.inner, property as c_int) };
+    pub fn get(&self, property: VideoWriterProperty) -> Result<Option<f64>, Error> {
+        let ret = cv_try(|e| unsafe { cv_videowriter_get(self.inner, property as c_int, e) })?;
         if ret != 0.0 {
-            Some(ret)
+            Ok(Some(ret))
         } else {
-            None
+            Ok(None)
         }
     }
 }
@@ -18,7 +18,7 @@ fn bench_mat_new() {
 fn bench_decode_lenna() {
     let buf = load_lenna_as_buf();
     timed("decode lenna.png", || {
-        Mat::imdecode(&buf, ImreadModes::ImreadGrayscale);
+        Mat::imdecode(&buf, ImreadModes::ImreadGrayscale).unwrap();
     });
 }
 
@@ -29,10 +29,10 @@ fn bench_face_detect_physicists() {
 
     for i in 0..3 {
         let rate = 1.0 - (i as f64) * 0.1;
-        let m = mat.resize_by(rate, rate, InterpolationFlag::InterLinear);
+        let m = mat.resize_by(rate, rate, InterpolationFlag::InterLinear).unwrap();
         let name = format!("detect physicists: {}x{}", m.rows, m.cols);
         timed_multiple(&name, 1, || {
-            cascade.detect(&m);
+            cascade.detect(&m).unwrap();
         });
     }
 }
@@ -0,0 +1,832 @@
+extern crate cv;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+mod utils;
+
+use cv::*;
+use cv::errors::CvError;
+use cv::imgcodecs::ImreadModes;
+use cv::imgproc::ColorConversionCodes;
+
+#[test]
+fn test_as_slice_matches_at2() {
+    let img = utils::load_messi_color();
+    let cols = img.cols as usize;
+
+    let pixels = img.as_slice::<[u8; 3]>().unwrap();
+    assert_eq!(pixels.len(), img.total());
+    let pixel = img.at2::<(u8, u8, u8)>(100, 100);
+    assert_eq!(pixels[100 * cols + 100], [pixel.0, pixel.1, pixel.2]);
+
+    let bytes = img.as_slice::<u8>().unwrap();
+    assert_eq!(bytes.len(), img.total() * 3);
+    assert_eq!(bytes[(100 * cols + 100) * 3], pixel.0);
+}
+
+#[test]
+fn test_as_slice_type_mismatch() {
+    let img = utils::load_messi_color();
+    assert!(img.as_slice::<f32>().is_err());
+    assert!(img.as_slice::<[u8; 4]>().is_err());
+    assert!(img.row::<u16>(0).is_err());
+}
+
+#[test]
+fn test_checks_ignore_public_fields() {
+    let mut img = Mat::zeros(4, 6, CvType::Cv8UC1 as i32).unwrap();
+    img.rows = 1 << 20;
+    img.cols = 1 << 20;
+    img.channels = 4;
+    assert_eq!(img.as_mut_slice::<u8>().unwrap().len(), 24);
+    assert_eq!(img.row_mut::<u8>(3).unwrap().len(), 6);
+    assert!(img.row::<u8>(4).is_err());
+    assert!(img.as_slice::<[u8; 4]>().is_err());
+    assert_eq!(img.rows_iter::<u8>().unwrap().count(), 4);
+}
+
+#[test]
+fn test_row_of_roi() {
+    let mut img = Mat::zeros(4, 6, CvType::Cv8UC1 as i32).unwrap();
+    for (i, v) in img.as_mut_slice::<u8>().unwrap().iter_mut().enumerate() {
+        *v = i as u8;
+    }
+
+    let roi = img.roi(Rect::new(1, 1, 3, 2)).unwrap();
+    assert!(!roi.is_continuous());
+    assert!(roi.as_slice::<u8>().is_err());
+    assert_eq!(roi.row::<u8>(0).unwrap(), &[7, 8, 9]);
+    assert_eq!(roi.row::<u8>(1).unwrap(), &[13, 14, 15]);
+    assert!(roi.row::<u8>(2).is_err());
+
+    img.row_mut::<u8>(3).unwrap()[0] = 42;
+    assert_eq!(img.at2::<u8>(3, 0), 42);
+}
+
+#[test]
+fn test_pixel_iterators() {
+    let mut img = Mat::zeros(3, 4, CvType::Cv8UC3 as i32).unwrap();
+    for (i, v) in img.as_mut_slice::<u8>().unwrap().iter_mut().enumerate() {
+        *v = i as u8;
+    }
+
+    let roi = img.roi(Rect::new(1, 1, 2, 2)).unwrap();
+    let rows = roi.rows_iter::<Vec3b>().unwrap().collect::<Vec<_>>();
+    assert_eq!(rows, vec![&[[15, 16, 17], [18, 19, 20]], &[[27, 28, 29], [30, 31, 32]]]);
+
+    let pixels = roi.pixels::<Vec3b>().unwrap().map(|p| p[0]).collect::<Vec<_>>();
+    assert_eq!(pixels, vec![15, 18, 27, 30]);
+    assert_eq!(roi.pixels::<u8>().unwrap().count(), 12);
+    assert!(roi.pixels::<f32>().is_err());
+
+    let (row, col, pixel) = roi.enumerate_pixels::<Vec3b>().unwrap().last().unwrap();
+    assert_eq!((row, col, pixel[2]), (1, 1, 32));
+
+    for pixel in img.pixels_mut::<Vec3b>().unwrap() {
+        pixel[1] = 0;
+    }
+    assert!(img.rows_iter::<u8>().unwrap().all(|row| row.iter().skip(1).step_by(3).all(|&v| v == 0)));
+
+    assert_eq!(Mat::new().pixels::<u8>().unwrap().count(), 0);
+}
+
+#[test]
+fn test_format_and_display() {
+    let mat = Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &[1u8, 2, 3, 4]).unwrap();
+    assert_eq!(mat.header(), "Mat 2x2 Cv8UC1 (continuous)");
+    assert!(mat.format(Formatter::Numpy).unwrap().contains("dtype='uint8'"));
+    assert!(mat.format(Formatter::C).unwrap().starts_with('{'));
+
+    let display = mat.to_string();
+    assert!(display.starts_with("Mat 2x2 Cv8UC1 (continuous)\n["));
+    assert!(!display.contains("..."));
+
+    let big = Mat::zeros(20, 40, CvType::Cv32FC1 as i32).unwrap();
+    let display = big.to_string();
+    assert_eq!(display.lines().count(), 1 + 16 + 1);
+    assert!(display.ends_with("... showing 16 of 20 rows and 16 of 40 columns"));
+
+    let roi = big.roi(Rect::new(1, 1, 2, 2)).unwrap();
+    assert_eq!(roi.header(), "Mat 2x2 Cv32FC1 (not continuous)");
+    assert_eq!(Mat::new().to_string(), "Mat 0x0 Cv8UC1 (empty)");
+    assert_eq!(Mat::new_nd(&[2, 3, 4], CvType::Cv8UC1).unwrap().to_string(), "Mat 2x3x4 Cv8UC1 (continuous)");
+
+    // The region of the first rows is out of bounds, so OpenCV fails
+    let mut broken = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    broken.rows = 100;
+    let display = broken.to_string();
+    assert!(display.starts_with("Mat 100x2 Cv8UC1"));
+    assert!(display.contains("\n<failed to format the elements: "));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_rows_mut() {
+    use rayon::prelude::*;
+
+    let mut img = Mat::zeros(64, 32, CvType::Cv32FC1 as i32).unwrap();
+    img.par_rows_mut::<f32>().unwrap().enumerate().for_each(|(i, row)| {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (i * 100 + j) as f32;
+        }
+    });
+    assert_eq!(img.at2::<f32>(63, 31), 6331.0);
+    assert_eq!(img.at2::<f32>(5, 0), 500.0);
+
+    // The rows of a region are not contiguous
+    let mut big = Mat::zeros(8, 8, CvType::Cv8UC1 as i32).unwrap();
+    {
+        let mut roi = big.roi_mut(Rect::new(2, 2, 4, 3)).unwrap();
+        let rows = roi.par_rows_mut::<u8>().unwrap();
+        assert_eq!(rows.len(), 3);
+        rows.for_each(|row| {
+            for v in row {
+                *v = 1;
+            }
+        });
+    }
+    assert_eq!(big.as_slice::<u8>().unwrap().iter().filter(|&&v| v == 1).count(), 12);
+    assert_eq!((big.at2::<u8>(2, 2), big.at2::<u8>(4, 5), big.at2::<u8>(5, 2)), (1, 1, 0));
+}
+
+#[test]
+fn test_typed_mat_get_set() {
+    let mut m = TypedMat::<Vec3b>::new(2, 3).unwrap();
+    assert_eq!(m.cv_type(), CvType::Cv8UC3);
+    m.set(1, 2, [1, 2, 3]).unwrap();
+    assert_eq!(m.get(1, 2).unwrap(), [1, 2, 3]);
+    assert_eq!(m.get(0, 0).unwrap(), [0, 0, 0]);
+    assert!(m.get(2, 0).is_err());
+    assert!(m.set(0, 3, [0, 0, 0]).is_err());
+
+    let mat: Mat = m.into();
+    assert_eq!(mat.at3::<u8>(1, 2, 2), 3);
+}
+
+#[test]
+fn test_typed_mat_checked_cast() {
+    let mat = Mat::zeros(2, 2, CvType::Cv32FC1 as i32).unwrap();
+    assert!(TypedMat::<u8>::from_mat(mat).is_err());
+
+    let mat = Mat::zeros(2, 2, CvType::Cv32FC1 as i32).unwrap();
+    let typed = TypedMat::<f32>::from_mat(mat).unwrap();
+    assert_eq!(typed.as_slice().unwrap(), &[0.0; 4]);
+    assert_eq!(typed.rows, 2);
+}
+
+#[test]
+fn test_from_slice_copy() {
+    let data: Vec<u8> = (0..12).collect();
+    let mat = Mat::from_slice_copy(2, 2, CvType::Cv8UC3, &data).unwrap();
+    assert_eq!(mat.as_slice::<u8>().unwrap(), &data[..]);
+    assert_ne!(mat.data(), data.as_ptr());
+    assert_eq!(mat.at2::<(u8, u8, u8)>(1, 1), (9, 10, 11));
+}
+
+#[test]
+fn test_from_slice_borrows() {
+    let data = [[1u8, 2, 3], [4, 5, 6]];
+    let mat = Mat::from_slice(1, 2, CvType::Cv8UC3, &data).unwrap();
+    assert_eq!(mat.data(), data.as_ptr() as *const u8);
+    assert_eq!(mat.as_slice::<Vec3b>().unwrap(), &data);
+}
+
+#[test]
+fn test_from_vec_adopts() {
+    let data = vec![0.5f32; 6];
+    let ptr = data.as_ptr();
+    let mat = Mat::from_vec(2, 3, CvType::Cv32FC1, data).unwrap();
+    assert_eq!(mat.data(), ptr as *const u8);
+    assert_eq!(mat.at2::<f32>(1, 2), 0.5);
+}
+
+#[test]
+fn test_from_slice_validation() {
+    let data = vec![0u8; 11];
+    assert!(Mat::from_slice_copy(2, 2, CvType::Cv8UC3, &data).is_err());
+    assert!(Mat::from_slice(2, 2, CvType::Cv8UC3, &data).is_err());
+    assert!(Mat::from_vec(2, 2, CvType::Cv8UC3, data).is_err());
+
+    let data = vec![0u16; 4];
+    assert!(Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &data).is_err());
+    assert!(Mat::from_slice_copy(-2, -2, CvType::Cv16UC1, &data).is_err());
+}
+
+#[test]
+fn test_opencv_exception_is_captured() {
+    let gray = Mat::zeros(4, 4, CvType::Cv8UC1 as i32).unwrap();
+    let err = gray.cvt_color(ColorConversionCodes::BGR2HSV).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::OpenCvException { code, func, message, .. } => {
+            assert!(code < 0);
+            assert!(!func.is_empty());
+            assert!(!message.is_empty());
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+
+    let other = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    assert!(gray.and(&other).is_err());
+    assert!(gray.roi(Rect::new(2, 2, 4, 4)).is_err());
+}
+
+#[test]
+fn test_imdecode_invalid_buffer() {
+    let err = Mat::imdecode(&[0u8; 16], ImreadModes::ImreadColor).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::ImageDecodeFailed => {}
+        e => panic!("unexpected error: {}", e),
+    }
+    assert!(Mat::from_path("assets/does-not-exist.png", ImreadModes::ImreadColor).is_err());
+}
+
+#[test]
+fn test_clone_is_deep() {
+    let mut a = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    let b = a.clone();
+    a.set_to(Scalar::all(7.0), None).unwrap();
+    assert_eq!(a.as_slice::<u8>().unwrap(), &[7; 4]);
+    assert_eq!(b.as_slice::<u8>().unwrap(), &[0; 4]);
+    assert_ne!(a.data(), b.data());
+
+    let c = a.try_clone().unwrap();
+    assert_eq!(c.as_slice::<u8>().unwrap(), &[7; 4]);
+    assert_ne!(a.data(), c.data());
+}
+
+#[test]
+fn test_share_keeps_data_alive() {
+    let shared = {
+        let mut a = Mat::from_vec(1, 3, CvType::Cv8UC1, vec![1u8, 2, 3]).unwrap();
+        let shared = unsafe { a.share() };
+        a.as_mut_slice::<u8>().unwrap()[0] = 9;
+        assert_eq!(shared.data(), a.data());
+        shared
+    };
+    assert_eq!(shared.as_slice::<u8>().unwrap(), &[9, 2, 3]);
+
+    let data = [4u8, 5, 6];
+    let borrowed = Mat::from_slice(1, 3, CvType::Cv8UC1, &data).unwrap();
+    let copy = unsafe { borrowed.share() };
+    assert_ne!(copy.data(), borrowed.data());
+    assert_eq!(copy.as_slice::<u8>().unwrap(), &data);
+}
+
+#[test]
+fn test_roi_borrows_data() {
+    let mut owner = Mat::from_vec(2, 3, CvType::Cv8UC1, vec![1u8, 2, 3, 4, 5, 6]).unwrap();
+    {
+        let roi = owner.roi(Rect::new(1, 0, 2, 2)).unwrap();
+        assert_eq!(roi.row::<u8>(0).unwrap(), &[2, 3]);
+        assert_eq!(roi.row::<u8>(1).unwrap(), &[5, 6]);
+    }
+    {
+        let mut roi = owner.roi_mut(Rect::new(0, 1, 2, 1)).unwrap();
+        roi.as_mut_slice::<u8>().unwrap()[0] = 9;
+        roi.row_mut::<u8>(0).unwrap()[1] = 8;
+    }
+    assert_eq!(owner.as_slice::<u8>().unwrap(), &[1, 2, 3, 9, 8, 6]);
+
+    let data = [1u8, 2, 3, 4];
+    let borrowed = Mat::from_slice(2, 2, CvType::Cv8UC1, &data).unwrap();
+    let roi = borrowed.roi(Rect::new(0, 1, 2, 1)).unwrap();
+    assert_eq!(roi.data(), data[2..].as_ptr());
+    assert_eq!(roi.as_slice::<u8>().unwrap(), &[3, 4]);
+}
+
+#[test]
+fn test_copy_to_masked() {
+    let src = Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &[1u8, 2, 3, 4]).unwrap();
+    let mask = Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &[255u8, 0, 0, 255]).unwrap();
+
+    let mut dst = Mat::new();
+    src.copy_to(&mut dst).unwrap();
+    assert_eq!(dst.rows, 2);
+    assert_eq!(dst.as_slice::<u8>().unwrap(), &[1, 2, 3, 4]);
+
+    let mut dst = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    src.copy_to_masked(&mut dst, &mask).unwrap();
+    assert_eq!(dst.as_slice::<u8>().unwrap(), &[1, 0, 0, 4]);
+
+    dst.set_to(Scalar::all(8.0), Some(&mask)).unwrap();
+    assert_eq!(dst.as_slice::<u8>().unwrap(), &[8, 0, 0, 8]);
+}
+
+#[test]
+fn test_arithmetic_ops() {
+    let a = Mat::from_slice_copy(1, 4, CvType::Cv8UC1, &[10u8, 200, 30, 40]).unwrap();
+    let b = Mat::from_slice_copy(1, 4, CvType::Cv8UC1, &[5u8, 100, 40, 8]).unwrap();
+
+    assert_eq!((&a + &b).unwrap().as_slice::<u8>().unwrap(), &[15, 255, 70, 48]);
+    assert_eq!((&a - &b).unwrap().as_slice::<u8>().unwrap(), &[5, 100, 0, 32]);
+    assert_eq!((&a * &b).unwrap().as_slice::<u8>().unwrap(), &[50, 255, 255, 255]);
+    assert_eq!((&a / &b).unwrap().as_slice::<u8>().unwrap(), &[2, 2, 1, 5]);
+    assert_eq!((&a + Scalar::all(100.0)).unwrap().as_slice::<u8>().unwrap(), &[110, 255, 130, 140]);
+    assert_eq!((&a / Scalar::all(10.0)).unwrap().as_slice::<u8>().unwrap(), &[1, 20, 3, 4]);
+
+    let c = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    assert!((&a + &c).is_err());
+}
+
+#[test]
+fn test_weighted_and_conversion() {
+    let a = Mat::from_slice_copy(1, 3, CvType::Cv8UC1, &[10u8, 20, 250]).unwrap();
+    let b = Mat::from_slice_copy(1, 3, CvType::Cv8UC1, &[30u8, 10, 250]).unwrap();
+
+    let w = a.add_weighted(0.5, &b, 0.5, 1.0).unwrap();
+    assert_eq!(w.as_slice::<u8>().unwrap(), &[21, 16, 251]);
+    assert_eq!(a.abs_diff(&b).unwrap().as_slice::<u8>().unwrap(), &[20, 10, 0]);
+    assert_eq!(a.scale_add(2.0, &b).unwrap().as_slice::<u8>().unwrap(), &[50, 50, 255]);
+
+    let f = a.convert_to(CvType::Cv32FC1, 0.5, 1.0).unwrap();
+    assert_eq!(f.cv_type().unwrap(), CvType::Cv32FC1);
+    assert_eq!(f.as_slice::<f32>().unwrap(), &[6.0, 11.0, 126.0]);
+}
+
+#[test]
+fn test_split_merge_roundtrip() {
+    let bgr = Mat::from_slice_copy(1, 2, CvType::Cv8UC3, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+
+    let channels = bgr.split().unwrap();
+    assert_eq!(channels.len(), 3);
+    assert_eq!(channels[0].as_slice::<u8>().unwrap(), &[1, 4]);
+    assert_eq!(channels[2].as_slice::<u8>().unwrap(), &[3, 6]);
+
+    let merged = Mat::merge(&[&channels[0], &channels[1], &channels[2]]).unwrap();
+    assert_eq!(merged.cv_type().unwrap(), CvType::Cv8UC3);
+    assert_eq!(merged.as_slice::<u8>().unwrap(), bgr.as_slice::<u8>().unwrap());
+
+    match Mat::merge(&[]).unwrap_err().downcast::<CvError>() {
+        Ok(CvError::EmptyInput) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_extract_insert_channel() {
+    let mut bgr = Mat::from_slice_copy(1, 2, CvType::Cv8UC3, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+
+    let green = bgr.extract_channel(1).unwrap();
+    assert_eq!(green.as_slice::<u8>().unwrap(), &[2, 5]);
+
+    let red = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[9u8, 9]).unwrap();
+    bgr.insert_channel(&red, 2).unwrap();
+    assert_eq!(bgr.as_slice::<u8>().unwrap(), &[1, 2, 9, 4, 5, 9]);
+
+    assert!(bgr.extract_channel(3).is_err());
+}
+
+#[test]
+fn test_mix_channels() {
+    let bgr = Mat::from_slice_copy(1, 2, CvType::Cv8UC3, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+
+    let mut rgb = Mat::with_size(1, 2, CvType::Cv8UC3 as i32).unwrap();
+    Mat::mix_channels(&[&bgr], &mut [&mut rgb], &[(0, 2), (1, 1), (2, 0)]).unwrap();
+    assert_eq!(rgb.as_slice::<u8>().unwrap(), &[3, 2, 1, 6, 5, 4]);
+
+    let mut blue = Mat::with_size(1, 2, CvType::Cv8UC1 as i32).unwrap();
+    let err = Mat::mix_channels(&[&bgr], &mut [&mut blue], &[(3, 0)]).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::IndexOutOfRange { index: 3, bound: 3 } => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+
+    let five = (0..5).map(|i| (i % 3, i)).collect::<Vec<_>>();
+    let err = Mat::mix_channels(&[&bgr], &mut [&mut rgb], &five).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::IndexOutOfRange { index: 3, bound: 3 } => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+
+    let mut empty = Mat::new();
+    let err = Mat::mix_channels(&[&bgr], &mut [&mut rgb, &mut empty], &[(0, 0)]).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::EmptyMat => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+
+    let err = Mat::mix_channels(&[], &mut [&mut rgb], &[(0, 0)]).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::EmptyInput => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn test_mix_channels_into_several_destinations() {
+    let bgra = Mat::from_slice_copy(1, 2, CvType::Cv8UC4, &[1u8, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    let mut rgb = Mat::with_size(1, 2, CvType::Cv8UC3 as i32).unwrap();
+    let mut alpha = Mat::with_size(1, 2, CvType::Cv8UC1 as i32).unwrap();
+    Mat::mix_channels(&[&bgra], &mut [&mut rgb, &mut alpha], &[(0, 2), (1, 1), (2, 0), (3, 3)]).unwrap();
+    assert_eq!(rgb.as_slice::<u8>().unwrap(), &[3, 2, 1, 7, 6, 5]);
+    assert_eq!(alpha.as_slice::<u8>().unwrap(), &[4, 8]);
+}
+
+#[test]
+fn test_reshape_shares_data() {
+    let mut bgr = Mat::from_vec(1, 2, CvType::Cv8UC3, vec![1u8, 2, 3, 4, 5, 6]).unwrap();
+    {
+        let flat = bgr.reshape(1, 0).unwrap();
+        assert_eq!(flat.cv_type().unwrap(), CvType::Cv8UC1);
+        assert_eq!((flat.rows, flat.cols), (1, 6));
+        assert_eq!(flat.min_max_loc(Mat::new()).unwrap().1, 6.0);
+        assert_eq!(flat.data(), bgr.data());
+        assert!(flat.reshape(4, 0).is_err());
+
+        let column = bgr.reshape(1, 6).unwrap();
+        assert_eq!((column.rows, column.cols), (6, 1));
+    }
+
+    bgr.set_to(Scalar::all(0.0), None).unwrap();
+    assert_eq!(bgr.reshape(1, 6).unwrap().as_slice::<u8>().unwrap(), &[0; 6]);
+}
+
+#[test]
+fn test_transpose_repeat_rotate() {
+    let mat = Mat::from_slice_copy(2, 3, CvType::Cv8UC1, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+
+    let t = mat.t().unwrap();
+    assert_eq!((t.rows, t.cols), (3, 2));
+    assert_eq!(t.as_slice::<u8>().unwrap(), &[1, 4, 2, 5, 3, 6]);
+
+    let r = mat.repeat(2, 1).unwrap();
+    assert_eq!((r.rows, r.cols), (4, 3));
+    assert_eq!(r.as_slice::<u8>().unwrap(), &[1, 2, 3, 4, 5, 6, 1, 2, 3, 4, 5, 6]);
+
+    let cw = mat.rotate(RotateFlags::Rotate90Clockwise).unwrap();
+    assert_eq!(cw.as_slice::<u8>().unwrap(), &[4, 1, 5, 2, 6, 3]);
+    let half = mat.rotate(RotateFlags::Rotate180).unwrap();
+    assert_eq!(half.as_slice::<u8>().unwrap(), &[6, 5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn test_concat() {
+    let a = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[1u8, 2]).unwrap();
+    let b = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[3u8, 4]).unwrap();
+
+    let h = Mat::hconcat(&[&a, &b]).unwrap();
+    assert_eq!((h.rows, h.cols), (1, 4));
+    assert_eq!(h.as_slice::<u8>().unwrap(), &[1, 2, 3, 4]);
+
+    let v = Mat::vconcat(&[&a, &b]).unwrap();
+    assert_eq!((v.rows, v.cols), (2, 2));
+    assert_eq!(v.as_slice::<u8>().unwrap(), &[1, 2, 3, 4]);
+
+    assert!(Mat::hconcat(&[&h, &v]).is_err());
+}
+
+#[test]
+fn test_statistics() {
+    let bgr = Mat::from_slice_copy(1, 2, CvType::Cv8UC3, &[10u8, 20, 30, 30, 40, 50]).unwrap();
+    assert_eq!(bgr.sum().unwrap(), Scalar::new(40.0, 60.0, 80.0, 0.0));
+    assert_eq!(bgr.mean(None).unwrap(), Scalar::new(20.0, 30.0, 40.0, 0.0));
+
+    let mask = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[0u8, 1]).unwrap();
+    assert_eq!(bgr.mean(Some(&mask)).unwrap(), Scalar::new(30.0, 40.0, 50.0, 0.0));
+
+    let (mean, stddev) = bgr.mean_std_dev(None).unwrap();
+    assert_eq!(mean, Scalar::new(20.0, 30.0, 40.0, 0.0));
+    assert_eq!(stddev, Scalar::new(10.0, 10.0, 10.0, 0.0));
+}
+
+#[test]
+fn test_norm() {
+    let a = Mat::from_slice_copy(1, 2, CvType::Cv32FC1, &[3.0f32, -4.0]).unwrap();
+    let b = Mat::zeros(1, 2, CvType::Cv32FC1 as i32).unwrap();
+
+    assert_eq!(a.norm(NormTypes::NormL2, None).unwrap(), 5.0);
+    assert_eq!(a.norm(NormTypes::NormL1, None).unwrap(), 7.0);
+    assert_eq!(a.norm(NormTypes::NormInf, None).unwrap(), 4.0);
+
+    let mask = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[1u8, 0]).unwrap();
+    assert_eq!(a.norm(NormTypes::NormL2, Some(&mask)).unwrap(), 3.0);
+
+    assert_eq!(a.norm_between(&b, NormTypes::NormL2).unwrap(), 5.0);
+    assert!(a.norm_between(&Mat::zeros(2, 2, CvType::Cv32FC1 as i32).unwrap(), NormTypes::NormL2).is_err());
+}
+
+#[test]
+fn test_reduce() {
+    let mat = Mat::from_slice_copy(2, 3, CvType::Cv8UC1, &[1u8, 2, 3, 4, 5, 6]).unwrap();
+
+    let rows = mat.reduce(0, ReduceTypes::Sum, Some(CvType::Cv32SC1)).unwrap();
+    assert_eq!((rows.rows, rows.cols), (1, 3));
+    assert_eq!(rows.as_slice::<i32>().unwrap(), &[5, 7, 9]);
+
+    let cols = mat.reduce(1, ReduceTypes::Max, None).unwrap();
+    assert_eq!((cols.rows, cols.cols), (2, 1));
+    assert_eq!(cols.as_slice::<u8>().unwrap(), &[3, 6]);
+
+    let avg = mat.reduce(1, ReduceTypes::Avg, Some(CvType::Cv32FC1)).unwrap();
+    assert_eq!(avg.as_slice::<f32>().unwrap(), &[2.0, 5.0]);
+}
+
+#[test]
+fn test_scalar_and_color() {
+    let s: Scalar = (1.5, 2.0, 3.0).into();
+    assert_eq!(s, Scalar::new(1.5, 2.0, 3.0, 0.0));
+    assert_eq!(Scalar::from([1.0, 2.0, 3.0, 4.0])[3], 4.0);
+    assert_eq!(<[f64; 4]>::from(Scalar::from(2.0)), [2.0, 0.0, 0.0, 0.0]);
+
+    assert_eq!(Scalar::from(Color::rgb(1, 2, 3)), Scalar::new(3.0, 2.0, 1.0, 255.0));
+    assert_eq!(Color::bgr(0, 0, 255), Color::red());
+
+    let mut mat = Mat::zeros(1, 1, CvType::Cv8UC3 as i32).unwrap();
+    mat.set_to(Color::rgb(10, 20, 30), None).unwrap();
+    assert_eq!(mat.as_slice::<u8>().unwrap(), &[30, 20, 10]);
+}
+
+#[test]
+fn test_in_range_fractional() {
+    let mat = Mat::from_slice_copy(1, 4, CvType::Cv32FC1, &[0.1f32, 0.4, 0.6, 0.9]).unwrap();
+    let mask = mat.in_range(Scalar::all(0.25), Scalar::all(0.75)).unwrap();
+    assert_eq!(mask.as_slice::<u8>().unwrap(), &[0, 255, 255, 0]);
+}
+
+#[test]
+fn test_point_size_ops() {
+    let p = Point2i::new(1, 2) + Point2i::new(3, 4);
+    assert_eq!(p, Point2i::new(4, 6));
+    assert_eq!(p - Point2i::new(1, 1), Point2i::new(3, 5));
+    assert_eq!(p * 2, Point2i::new(8, 12));
+    assert_eq!(-p, Point2i::new(-4, -6));
+    assert_eq!(p.dot(Point2i::new(1, 1)), 10);
+
+    let x = Point3f::new(1.0, 0.0, 0.0);
+    let y = Point3f::new(0.0, 1.0, 0.0);
+    assert_eq!(x.cross(y), Point3f::new(0.0, 0.0, 1.0));
+
+    let s = Size2i::new(2, 3) * 2;
+    assert_eq!(s, Size2i::new(4, 6));
+    assert_eq!(s.area(), 24);
+    assert!(Size2i::new(0, 3).empty());
+}
+
+#[test]
+fn test_rect_algebra() {
+    let a = Rect::new(0, 0, 10, 10);
+    let b = Rect::new(5, 5, 10, 10);
+
+    assert_eq!(a.tl(), Point2i::new(0, 0));
+    assert_eq!(b.br(), Point2i::new(15, 15));
+    assert_eq!(b.center(), Point2i::new(10, 10));
+    assert_eq!(a.area(), 100);
+    assert!(a.contains(Point2i::new(9, 9)));
+    assert!(!a.contains(Point2i::new(10, 5)));
+
+    assert_eq!(a.intersection(&b), Rect::new(5, 5, 5, 5));
+    assert_eq!(a.union(&b), Rect::new(0, 0, 15, 15));
+    assert!(a.intersection(&Rect::new(20, 20, 1, 1)).empty());
+    assert_eq!(a.union(&Rect::default()), a);
+    assert!((a.iou(&b) - 25.0 / 175.0).abs() < 1e-9);
+    assert_eq!(a.iou(&a), 1.0);
+
+    assert_eq!(b.clamp_to(Size2i::new(12, 8)), Rect::new(5, 5, 7, 3));
+    assert_eq!(a + Point2i::new(1, 2), Rect::new(1, 2, 10, 10));
+    assert_eq!(a - Size2i::new(5, 5), Rect::new(0, 0, 5, 5));
+    assert_eq!(Rect::from_points(Point2i::new(1, 1), Point2i::new(4, 5)), Rect::new(1, 1, 3, 4));
+}
+
+#[test]
+fn test_geometry_conversions() {
+    let r: Rect2d = Rect::new(1, 2, 3, 4).into();
+    assert_eq!(r, Rect2d::new(1.0, 2.0, 3.0, 4.0));
+    let p: Point2d = Point2f::new(0.5, 1.5).into();
+    assert_eq!(p, Point2d::new(0.5, 1.5));
+
+    assert_eq!(Rect2f::new(1.7, 2.2, 3.0, 4.9).cast::<i32>(), Some(Rect::new(1, 2, 3, 4)));
+    assert_eq!(Point2i::new(1, 2).cast::<f32>(), Some(Point2f::new(1.0, 2.0)));
+    assert_eq!(Point2f::new(std::f32::NAN, 0.0).cast::<i32>(), None);
+    assert_eq!(Size2i::new(300, 2).cast::<u8>(), None);
+}
+
+#[test]
+fn test_nd_mat() {
+    let mut mat = Mat::new_nd(&[2, 3, 4], CvType::Cv32FC1).unwrap();
+    assert_eq!(mat.dims(), 3);
+    assert_eq!(mat.sizes(), vec![2, 3, 4]);
+    assert_eq!((mat.rows, mat.cols), (-1, -1));
+    assert_eq!(mat.total(), 24);
+
+    *mat.at_nd_mut::<f32>(&[1, 2, 3]).unwrap() = 5.0;
+    assert_eq!(*mat.at_nd::<f32>(&[1, 2, 3]).unwrap(), 5.0);
+    assert_eq!(mat.as_slice::<f32>().unwrap()[23], 5.0);
+    assert_eq!(*mat.at_nd::<f32>(&[0, 0, 0]).unwrap(), 0.0);
+
+    assert!(mat.at_nd::<f32>(&[2, 0, 0]).is_err());
+    assert!(mat.at_nd::<f32>(&[0, 0]).is_err());
+    assert!(mat.at_nd::<u8>(&[0, 0, 0]).is_err());
+
+    let image = Mat::from_slice_copy(2, 2, CvType::Cv8UC3, &[0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]).unwrap();
+    assert_eq!(image.sizes(), vec![2, 2]);
+    assert_eq!(*image.at_nd::<Vec3b>(&[1, 0]).unwrap(), [6, 7, 8]);
+
+    let mut empty = Mat::new();
+    assert_eq!(empty.dims(), 0);
+    match empty.at_nd::<u8>(&[]).unwrap_err().downcast::<CvError>() {
+        Ok(CvError::EmptyMat) => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+    assert!(empty.at_nd_mut::<u8>(&[]).is_err());
+    assert!(empty.at_nd::<u8>(&[0, 0]).is_err());
+}
+
+#[test]
+fn test_sparse_mat() {
+    let mut sparse = SparseMat::new(&[100, 100, 100], CvType::Cv32FC1).unwrap();
+    assert_eq!(sparse.dims(), 3);
+    assert_eq!(sparse.sizes(), vec![100, 100, 100]);
+    assert_eq!(sparse.nz_count(), 0);
+
+    sparse.set(&[1, 2, 3], 4.0f32).unwrap();
+    sparse.set(&[99, 0, 50], 1.5f32).unwrap();
+    assert_eq!(sparse.nz_count(), 2);
+    assert_eq!(sparse.get::<f32>(&[1, 2, 3]).unwrap(), 4.0);
+    assert_eq!(sparse.get::<f32>(&[0, 0, 0]).unwrap(), 0.0);
+    assert!(sparse.get::<f32>(&[100, 0, 0]).is_err());
+    assert!(sparse.get::<f64>(&[0, 0, 0]).is_err());
+
+    let mut nodes = sparse.nonzeros::<f32>().unwrap().collect::<Vec<_>>();
+    nodes.sort_by_key(|n| n.0.clone());
+    assert_eq!(nodes, vec![(vec![1, 2, 3], 4.0), (vec![99, 0, 50], 1.5)]);
+
+    sparse.remove(&[99, 0, 50]).unwrap();
+    assert_eq!(sparse.nz_count(), 1);
+
+    let copy = sparse.clone();
+    sparse.set(&[0, 0, 0], 1.0f32).unwrap();
+    assert_eq!(copy.nz_count(), 1);
+
+    let dense = copy.to_dense().unwrap();
+    assert_eq!(dense.sizes(), vec![100, 100, 100]);
+    assert_eq!(*dense.at_nd::<f32>(&[1, 2, 3]).unwrap(), 4.0);
+    assert_eq!(SparseMat::from_mat(&dense).unwrap().nz_count(), 1);
+}
+
+fn assert_close_f32(actual: &Mat, expected: &[f32]) {
+    let actual = actual.as_slice::<f32>().unwrap();
+    assert_eq!(actual.len(), expected.len());
+    for (a, b) in actual.iter().zip(expected) {
+        assert!((a - b).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_dft_round_trip() {
+    assert_eq!(get_optimal_dft_size(480), 480);
+    assert_eq!(get_optimal_dft_size(481), 486);
+
+    let signal = Mat::from_slice_copy(1, 4, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    let spectrum = signal.dft(DftFlags::COMPLEX_OUTPUT, 0).unwrap();
+    assert_eq!(spectrum.cv_type().unwrap(), CvType::Cv32FC2);
+    assert_close_f32(&spectrum, &[10.0, 0.0, -2.0, 2.0, -2.0, 0.0, -2.0, -2.0]);
+
+    let power = spectrum.mul_spectrums(&spectrum, DftFlags::NONE, true).unwrap();
+    assert_close_f32(&power, &[100.0, 0.0, 8.0, 0.0, 4.0, 0.0, 8.0, 0.0]);
+
+    let restored = spectrum.idft(DftFlags::SCALE | DftFlags::REAL_OUTPUT, 0).unwrap();
+    assert_eq!(restored.cv_type().unwrap(), CvType::Cv32FC1);
+    assert_close_f32(&restored, &[1.0, 2.0, 3.0, 4.0]);
+
+    let dct = signal.dct(DftFlags::NONE).unwrap();
+    assert!((dct.as_slice::<f32>().unwrap()[0] - 5.0).abs() < 1e-4);
+    assert_close_f32(&dct.idct(DftFlags::NONE).unwrap(), &[1.0, 2.0, 3.0, 4.0]);
+
+    let odd = Mat::zeros(1, 3, CvType::Cv32FC1 as i32).unwrap();
+    assert!(odd.dct(DftFlags::NONE).is_err());
+}
+
+#[test]
+fn test_copy_make_border() {
+    let mat = Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &[1u8, 2, 3, 4]).unwrap();
+    let padded = mat.copy_make_border(0, 1, 1, 0, BorderTypes::Constant, Scalar::all(9.0)).unwrap();
+    assert_eq!((padded.rows, padded.cols), (3, 3));
+    assert_eq!(padded.as_slice::<u8>().unwrap(), &[9, 1, 2, 9, 3, 4, 9, 9, 9]);
+
+    let padded = mat.copy_make_border(1, 0, 0, 1, BorderTypes::Replicate, Scalar::default()).unwrap();
+    assert_eq!(padded.as_slice::<u8>().unwrap(), &[1, 2, 2, 1, 2, 2, 3, 4, 4]);
+}
+
+#[test]
+fn test_magnitude_and_phase() {
+    let x = Mat::from_slice_copy(1, 2, CvType::Cv32FC1, &[3.0f32, 0.0]).unwrap();
+    let y = Mat::from_slice_copy(1, 2, CvType::Cv32FC1, &[4.0f32, 2.0]).unwrap();
+    assert_close_f32(&Mat::magnitude(&x, &y).unwrap(), &[5.0, 2.0]);
+
+    let angle = Mat::phase(&x, &y, true).unwrap();
+    assert!((angle.as_slice::<f32>().unwrap()[1] - 90.0).abs() < 0.5);
+
+    let (magnitude, angle) = Mat::cart_to_polar(&x, &y, false).unwrap();
+    assert_close_f32(&magnitude, &[5.0, 2.0]);
+    assert!((angle.as_slice::<f32>().unwrap()[0] - (4.0f32).atan2(3.0)).abs() < 1e-2);
+
+    let ints = Mat::zeros(1, 2, CvType::Cv8UC1 as i32).unwrap();
+    assert!(Mat::magnitude(&ints, &ints).is_err());
+}
+
+#[test]
+fn test_rng_is_deterministic() {
+    let mut a = Rng::new(42).unwrap();
+    let mut b = Rng::new(42).unwrap();
+    let first = (0..8).map(|_| a.next_u32().unwrap()).collect::<Vec<_>>();
+    assert_eq!(first, (0..8).map(|_| b.next_u32().unwrap()).collect::<Vec<_>>());
+    assert_eq!(a.state(), b.state());
+
+    let mut resumed = Rng::new(a.state()).unwrap();
+    assert_eq!(resumed.next_u32().unwrap(), a.next_u32().unwrap());
+
+    for _ in 0..100 {
+        let i = a.uniform_i32(-3, 5).unwrap();
+        assert!((-3..5).contains(&i));
+        let f = a.uniform_f64(0.5, 1.0).unwrap();
+        assert!((0.5..1.0).contains(&f));
+    }
+    assert!(a.gaussian(1.0).unwrap().is_finite());
+
+    let mut x = Mat::zeros(16, 16, CvType::Cv8UC3 as i32).unwrap();
+    let mut y = Mat::zeros(16, 16, CvType::Cv8UC3 as i32).unwrap();
+    Rng::new(7).unwrap().fill(&mut x, RngDistribution::Uniform, 10.0, 20.0, false).unwrap();
+    Rng::new(7).unwrap().fill(&mut y, RngDistribution::Uniform, 10.0, 20.0, false).unwrap();
+    assert_eq!(x.as_slice::<u8>().unwrap(), y.as_slice::<u8>().unwrap());
+    assert!(x.as_slice::<u8>().unwrap().iter().all(|v| (10..20).contains(v)));
+}
+
+#[test]
+fn test_randu_randn_and_shuffle() {
+    let mut x = Mat::zeros(32, 32, CvType::Cv32FC1 as i32).unwrap();
+    let mut y = Mat::zeros(32, 32, CvType::Cv32FC1 as i32).unwrap();
+    set_rng_seed(1234);
+    x.randu(-1.0, 1.0).unwrap();
+    set_rng_seed(1234);
+    y.randu(-1.0, 1.0).unwrap();
+    assert_eq!(x.as_slice::<f32>().unwrap(), y.as_slice::<f32>().unwrap());
+    assert!(x.as_slice::<f32>().unwrap().iter().all(|v| (-1.0..1.0).contains(v)));
+
+    let mut noise = Mat::zeros(100, 100, CvType::Cv32FC1 as i32).unwrap();
+    noise.randn(10.0, 2.0).unwrap();
+    let (mean, stddev) = noise.mean_std_dev(None).unwrap();
+    assert!((mean[0] - 10.0).abs() < 0.2);
+    assert!((stddev[0] - 2.0).abs() < 0.2);
+
+    let values = (0..100).collect::<Vec<i32>>();
+    let mut a = Mat::from_slice_copy(10, 10, CvType::Cv32SC1, &values).unwrap();
+    let mut b = Mat::from_slice_copy(10, 10, CvType::Cv32SC1, &values).unwrap();
+    a.rand_shuffle(1.0, Some(&mut Rng::new(5).unwrap())).unwrap();
+    b.rand_shuffle(1.0, Some(&mut Rng::new(5).unwrap())).unwrap();
+    assert_eq!(a.as_slice::<i32>().unwrap(), b.as_slice::<i32>().unwrap());
+    assert_ne!(a.as_slice::<i32>().unwrap(), &values[..]);
+    let mut sorted = a.as_slice::<i32>().unwrap().to_vec();
+    sorted.sort();
+    assert_eq!(sorted, values);
+}
+
+#[test]
+fn test_compare_min_max() {
+    let a = Mat::from_slice_copy(1, 4, CvType::Cv8UC1, &[1u8, 5, 3, 7]).unwrap();
+    let b = Mat::from_slice_copy(1, 4, CvType::Cv8UC1, &[2u8, 5, 1, 9]).unwrap();
+    assert_eq!(a.compare(&b, CmpTypes::Eq).unwrap().as_slice::<u8>().unwrap(), &[0, 255, 0, 0]);
+    assert_eq!(a.compare(&b, CmpTypes::Gt).unwrap().as_slice::<u8>().unwrap(), &[0, 0, 255, 0]);
+    assert_eq!(a.compare(&b, CmpTypes::Le).unwrap().as_slice::<u8>().unwrap(), &[255, 255, 0, 255]);
+    assert_eq!(a.min(&b).unwrap().as_slice::<u8>().unwrap(), &[1, 5, 1, 7]);
+    assert_eq!(a.max(&b).unwrap().as_slice::<u8>().unwrap(), &[2, 5, 3, 9]);
+
+    let c = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    assert!(a.compare(&c, CmpTypes::Eq).is_err());
+    assert!(a.min(&c).is_err());
+}
+
+#[test]
+fn test_check_range() {
+    let mut a = Mat::from_slice_copy(2, 2, CvType::Cv32FC1, &[0.0f32, 0.5, 0.25, 0.75]).unwrap();
+    assert_eq!(a.check_range(0.0, 1.0).unwrap(), None);
+    assert_eq!(a.check_range(0.0, 0.5).unwrap(), Some(Point2i::new(1, 0)));
+
+    a.as_mut_slice::<f32>().unwrap()[2] = f32::NAN;
+    assert_eq!(a.check_range(-1e9, 1e9).unwrap(), Some(Point2i::new(0, 1)));
+}
+
+#[test]
+fn test_mat_equality() {
+    let a = Mat::from_slice_copy(2, 2, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    let mut b = a.clone();
+    assert_eq!(a, b);
+    assert!(a.approx_eq(&b, 0.0, NormTypes::NormInf).unwrap());
+
+    b.as_mut_slice::<f32>().unwrap()[3] = 4.000_001;
+    assert_ne!(a, b);
+    assert!(a.approx_eq(&b, 1e-5, NormTypes::NormInf).unwrap());
+    assert!(!a.approx_eq(&b, 1e-7, NormTypes::NormInf).unwrap());
+
+    // Same bytes, different type or shape
+    let bits = a.as_slice::<f32>().unwrap().iter().map(|v| v.to_bits() as i32).collect::<Vec<_>>();
+    let ints = Mat::from_slice_copy(2, 2, CvType::Cv32SC1, &bits).unwrap();
+    assert_ne!(a, ints);
+    assert_ne!(a, *a.reshape(1, 1).unwrap());
+    assert!(!a.approx_eq(&a.reshape(1, 4).unwrap(), 1.0, NormTypes::NormL2).unwrap());
+
+    // Rois compare by content, not by step
+    let big = Mat::from_slice_copy(2, 3, CvType::Cv8UC1, &[1u8, 2, 9, 3, 4, 9]).unwrap();
+    let small = Mat::from_slice_copy(2, 2, CvType::Cv8UC1, &[1u8, 2, 3, 4]).unwrap();
+    assert_eq!(*big.roi(Rect::new(0, 0, 2, 2)).unwrap(), small);
+
+    assert_eq!(Mat::new(), Mat::new());
+}
@@ -7,8 +7,8 @@ use cv::features2d::*;
 #[test]
 fn mser_lenna() {
     let lenna = load_lenna();
-    let mser: MSER = MSERBuilder::default().into();
-    let (msers, boxes) = mser.detect_regions(&lenna);
+    let mser = MSERBuilder::default().build().unwrap();
+    let (msers, boxes) = mser.detect_regions(&lenna).unwrap();
     assert_ne!(msers.len(), 0);
     assert_ne!(boxes.len(), 0);
 }
@@ -0,0 +1,68 @@
+#![cfg(feature = "image")]
+extern crate cv;
+extern crate image;
+
+use cv::*;
+use image::{Bgr, GrayImage, ImageBuffer, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+use std::convert::TryFrom;
+
+#[test]
+fn test_mat_to_rgb_image() {
+    // One blue and one red pixel in BGR order.
+    let mat = Mat::from_slice_copy(1, 2, CvType::Cv8UC3, &[255u8, 0, 0, 0, 0, 255]).unwrap();
+    let image = RgbImage::try_from(&mat).unwrap();
+    assert_eq!(image.dimensions(), (2, 1));
+    assert_eq!(*image.get_pixel(0, 0), Rgb([0, 0, 255]));
+    assert_eq!(*image.get_pixel(1, 0), Rgb([255, 0, 0]));
+
+    let image = ImageBuffer::<Bgr<u8>, Vec<u8>>::try_from(&mat).unwrap();
+    assert_eq!(image.into_raw(), vec![255, 0, 0, 0, 0, 255]);
+
+    assert!(GrayImage::try_from(&mat).is_err());
+    assert!(RgbaImage::try_from(&mat).is_err());
+}
+
+#[test]
+fn test_mat_roi_to_gray_image() {
+    let mat = Mat::from_slice_copy(3, 3, CvType::Cv8UC1, &[1u8, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+    let roi = mat.roi(Rect::new(1, 1, 2, 2)).unwrap();
+    let image = GrayImage::try_from(&*roi).unwrap();
+    assert_eq!(image.into_raw(), vec![5, 6, 8, 9]);
+}
+
+#[test]
+fn test_16bit_images() {
+    let mat = Mat::from_slice_copy(1, 1, CvType::Cv16UC4, &[1u16, 2, 3, 65535]).unwrap();
+    let image = ImageBuffer::<Rgba<u16>, Vec<u16>>::try_from(&mat).unwrap();
+    assert_eq!(*image.get_pixel(0, 0), Rgba([3, 2, 1, 65535]));
+
+    let gray = ImageBuffer::<Luma<u16>, Vec<u16>>::from_raw(2, 1, vec![1000, 2000]).unwrap();
+    let mat = Mat::from_image(&gray).unwrap();
+    assert_eq!(mat.cv_type().unwrap(), CvType::Cv16UC1);
+    assert_eq!(mat.as_slice::<u16>().unwrap(), &[1000, 2000]);
+}
+
+#[test]
+fn test_image_with_longer_container() {
+    let image = GrayImage::from_raw(2, 1, vec![1u8, 2, 3, 4]).unwrap();
+    let mat = Mat::from_image(&image).unwrap();
+    assert_eq!(mat.as_slice::<u8>().unwrap(), &[1, 2]);
+
+    let image = RgbImage::from_raw(1, 1, vec![1u8, 2, 3, 4]).unwrap();
+    let mat = Mat::from_image_bgr(&image).unwrap();
+    assert_eq!(mat.as_slice::<u8>().unwrap(), &[3, 2, 1]);
+}
+
+#[test]
+fn test_image_to_mat() {
+    let image = RgbImage::from_raw(2, 1, vec![10, 20, 30, 40, 50, 60]).unwrap();
+
+    let borrowed = Mat::from_image(&image).unwrap();
+    assert_eq!(borrowed.size(), Size2i::new(2, 1));
+    assert_eq!(borrowed.as_slice::<u8>().unwrap(), &[10, 20, 30, 40, 50, 60]);
+    assert_eq!(borrowed.data(), image.as_ptr());
+
+    let bgr = Mat::from_image_bgr(&image).unwrap();
+    assert_eq!(bgr.as_slice::<u8>().unwrap(), &[30, 20, 10, 60, 50, 40]);
+    assert_eq!(RgbImage::try_from(&bgr).unwrap().into_raw(), image.into_raw());
+}
@@ -0,0 +1,151 @@
+extern crate cv;
+mod utils;
+
+use cv::*;
+use cv::errors::CvError;
+use cv::imgproc::*;
+
+#[test]
+fn test_calc_hist() {
+    let mat = Mat::from_slice_copy(2, 3, CvType::Cv8UC1, &[0u8, 10, 128, 200, 255, 255]).unwrap();
+    let bins = Histogram::new().channels(&[0]).sizes(&[4]).ranges(&[0.0..256.0]);
+
+    let hist = mat.calc_hist(&bins, None).unwrap();
+    assert_eq!(hist.as_slice::<f32>().unwrap(), &[2.0, 0.0, 1.0, 3.0]);
+
+    let mask = Mat::from_slice_copy(2, 3, CvType::Cv8UC1, &[255u8, 0, 0, 0, 0, 255]).unwrap();
+    let hist = mat.calc_hist(&bins, Some(&mask)).unwrap();
+    assert_eq!(hist.as_slice::<f32>().unwrap(), &[1.0, 0.0, 0.0, 1.0]);
+
+    let back_project = mat.calc_back_project(&bins, &hist).unwrap();
+    assert_eq!(back_project.as_slice::<u8>().unwrap(), &[1, 1, 0, 1, 1, 1]);
+}
+
+#[test]
+fn test_calc_hist_multiple_images() {
+    let a = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[0u8, 255]).unwrap();
+    let b = Mat::from_slice_copy(1, 2, CvType::Cv8UC1, &[0u8, 0]).unwrap();
+    let hist = Histogram::new()
+        .channels(&[0, 1])
+        .sizes(&[2, 2])
+        .ranges(&[0.0..256.0, 0.0..256.0])
+        .calc(&[&a, &b], None)
+        .unwrap();
+    assert_eq!(hist.at2::<f32>(0, 0), 1.0);
+    assert_eq!(hist.at2::<f32>(1, 0), 1.0);
+    assert_eq!(hist.at2::<f32>(1, 1), 0.0);
+}
+
+#[test]
+fn test_calc_hist_dimension_mismatch() {
+    let mat = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    let bins = Histogram::new().channels(&[0]).sizes(&[4, 4]).ranges(&[0.0..256.0]);
+    let err = mat.calc_hist(&bins, None).unwrap_err();
+    match err.downcast::<CvError>().unwrap() {
+        CvError::HistogramDimensionMismatch { channels: 1, sizes: 2, ranges: 1 } => {}
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn test_compare_hist() {
+    let lenna = utils::load_lenna();
+    let bins = Histogram::new().channels(&[0]).sizes(&[32]).ranges(&[0.0..256.0]);
+    let hist = lenna.calc_hist(&bins, None).unwrap();
+    let equalized = lenna.equalize_hist().unwrap().calc_hist(&bins, None).unwrap();
+
+    let same = hist.compare_hist(&hist, HistCompMethods::Correl).unwrap();
+    assert!((same - 1.0).abs() < 1e-6);
+    let other = hist.compare_hist(&equalized, HistCompMethods::Correl).unwrap();
+    assert!(other < same);
+    assert!(hist.compare_hist(&hist, HistCompMethods::HELLINGER).unwrap() < 1e-6);
+}
+
+#[test]
+fn test_clahe() {
+    let lenna = utils::load_lenna();
+    let out = CLAHE::new(2.0, Size2i::new(8, 8)).unwrap().apply(&lenna).unwrap();
+    assert_eq!(out.rows, lenna.rows);
+    assert_eq!(out.cols, lenna.cols);
+    assert_eq!(out.cv_type().unwrap(), CvType::Cv8UC1);
+
+    let color = utils::load_messi_color();
+    assert!(CLAHE::default().apply(&color).is_err());
+}
+
+#[test]
+fn test_min_area_rect() {
+    let points = [
+        Point2f::new(0.0, 0.0),
+        Point2f::new(4.0, 0.0),
+        Point2f::new(4.0, 2.0),
+        Point2f::new(0.0, 2.0),
+        Point2f::new(1.0, 1.0),
+    ];
+    let rect = min_area_rect(&points).unwrap();
+    assert!((rect.center.x - 2.0).abs() < 1e-4);
+    assert!((rect.center.y - 1.0).abs() < 1e-4);
+    assert!((rect.size.width * rect.size.height - 8.0).abs() < 1e-4);
+
+    let expected = rect.points();
+    for (a, b) in box_points(&rect).unwrap().iter().zip(expected.iter()) {
+        assert!((a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_fit_ellipse() {
+    let points = (0..12)
+        .map(|i| {
+            let t = i as f32 * ::std::f32::consts::PI / 6.0;
+            Point2f::new(10.0 + 4.0 * t.cos(), 5.0 + 2.0 * t.sin())
+        })
+        .collect::<Vec<_>>();
+    let ellipse = fit_ellipse(&points).unwrap();
+    assert!((ellipse.center.x - 10.0).abs() < 1e-3);
+    assert!((ellipse.center.y - 5.0).abs() < 1e-3);
+
+    let mut axes = [ellipse.size.width, ellipse.size.height];
+    axes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert!((axes[0] - 4.0).abs() < 1e-2);
+    assert!((axes[1] - 8.0).abs() < 1e-2);
+
+    assert!(fit_ellipse(&points[..3]).is_err());
+}
+
+#[test]
+fn test_rotated_rectangle_intersection() {
+    let a = RotatedRect::new(Point2f::new(0.0, 0.0), Size2f::new(2.0, 2.0), 0.0);
+    let b = RotatedRect::new(Point2f::new(1.0, 1.0), Size2f::new(2.0, 2.0), 0.0);
+    let c = RotatedRect::new(Point2f::new(10.0, 10.0), Size2f::new(2.0, 2.0), 45.0);
+    let inner = RotatedRect::new(Point2f::new(0.0, 0.0), Size2f::new(1.0, 1.0), 45.0);
+
+    let (t, region) = rotated_rectangle_intersection(&a, &b).unwrap();
+    assert_eq!(t, RectanglesIntersectTypes::IntersectPartial);
+    assert_eq!(region.len(), 4);
+
+    let (t, region) = rotated_rectangle_intersection(&a, &c).unwrap();
+    assert_eq!(t, RectanglesIntersectTypes::IntersectNone);
+    assert!(region.is_empty());
+
+    let (t, _) = rotated_rectangle_intersection(&a, &inner).unwrap();
+    assert_eq!(t, RectanglesIntersectTypes::IntersectFull);
+}
+
+#[test]
+fn test_calc_hist_3d() {
+    let bgr = Mat::from_slice_copy(1, 3, CvType::Cv8UC3, &[0u8, 0, 0, 255, 255, 255, 0, 0, 0]).unwrap();
+    let bins = Histogram::new()
+        .channels(&[0, 1, 2])
+        .sizes(&[8, 8, 8])
+        .ranges(&[0.0..256.0, 0.0..256.0, 0.0..256.0]);
+
+    let hist = bgr.calc_hist(&bins, None).unwrap();
+    assert_eq!(hist.sizes(), vec![8, 8, 8]);
+    assert_eq!(*hist.at_nd::<f32>(&[0, 0, 0]).unwrap(), 2.0);
+    assert_eq!(*hist.at_nd::<f32>(&[7, 7, 7]).unwrap(), 1.0);
+
+    let sparse = bins.calc_sparse(&[&bgr], None).unwrap();
+    assert_eq!(sparse.nz_count(), 2);
+    assert_eq!(sparse.get::<f32>(&[0, 0, 0]).unwrap(), 2.0);
+}
@@ -0,0 +1,117 @@
+extern crate cv;
+
+use cv::*;
+use cv::errors::CvError;
+use cv::core::linalg::*;
+
+fn mat64(rows: i32, cols: i32, data: &[f64]) -> Mat {
+    Mat::from_slice_copy(rows, cols, CvType::Cv64FC1, data).unwrap()
+}
+
+fn assert_close(mat: &Mat, expected: &[f64]) {
+    let data = mat.as_slice::<f64>().unwrap();
+    assert_eq!(data.len(), expected.len());
+    for (a, b) in data.iter().zip(expected) {
+        assert!((a - b).abs() < 1e-9, "{:?} != {:?}", data, expected);
+    }
+}
+
+#[test]
+fn test_eye_and_ones() {
+    let eye = Mat::eye(2, 3, CvType::Cv64FC1).unwrap();
+    assert_close(&eye, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+
+    let ones = Mat::ones(2, 2, CvType::Cv8UC1).unwrap();
+    assert_eq!(ones.as_slice::<u8>().unwrap(), &[1, 1, 1, 1]);
+}
+
+#[test]
+fn test_gemm_and_matmul() {
+    let a = mat64(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let b = mat64(3, 2, &[7.0, 8.0, 9.0, 10.0, 11.0, 12.0]);
+    assert_close(&matmul(&a, &b).unwrap(), &[58.0, 64.0, 139.0, 154.0]);
+
+    // a * a^T * 2 + I
+    let eye = Mat::eye(2, 2, CvType::Cv64FC1).unwrap();
+    let c = gemm(&a, &a, 2.0, Some(&eye), 1.0, GemmFlags::TRANSPOSE_SRC2).unwrap();
+    assert_close(&c, &[29.0, 64.0, 64.0, 155.0]);
+
+    let flags = GemmFlags::TRANSPOSE_SRC1 | GemmFlags::TRANSPOSE_SRC2;
+    assert!(flags.contains(GemmFlags::TRANSPOSE_SRC1));
+    assert!(!flags.contains(GemmFlags::TRANSPOSE_SRC3));
+    assert_close(&gemm(&a, &b, 1.0, None, 0.0, flags).unwrap(), &[39.0, 49.0, 59.0, 54.0, 68.0, 82.0, 69.0, 87.0, 105.0]);
+
+    assert!(matmul(&a, &a).is_err());
+}
+
+#[test]
+fn test_invert_and_solve() {
+    let a = mat64(2, 2, &[4.0, 7.0, 2.0, 6.0]);
+    for &method in &[DecompTypes::Lu, DecompTypes::Svd] {
+        let inv = invert(&a, method).unwrap();
+        assert_close(&inv, &[0.6, -0.7, -0.2, 0.4]);
+        assert_close(&matmul(&a, &inv).unwrap(), &[1.0, 0.0, 0.0, 1.0]);
+    }
+
+    let b = mat64(2, 1, &[1.0, 2.0]);
+    assert_close(&solve(&a, &b, DecompTypes::Lu).unwrap(), &[-0.8, 0.6]);
+
+    let singular = mat64(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+    match invert(&singular, DecompTypes::Lu).unwrap_err().downcast::<CvError>() {
+        Ok(CvError::SingularMatrix) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert!(solve(&singular, &b, DecompTypes::Lu).is_err());
+
+    // The pseudo-inverse of [[1, 2], [2, 4]] is the matrix divided by 25
+    let pinv = invert(&singular, DecompTypes::Svd).unwrap();
+    assert_close(&pinv, &[0.04, 0.08, 0.08, 0.16]);
+
+    // Least squares fit of y = x through (0, 0.1), (1, 0.9), (2, 2.0)
+    let x = mat64(3, 1, &[0.0, 1.0, 2.0]);
+    let y = mat64(3, 1, &[0.1, 0.9, 2.0]);
+    let slope = solve(&x, &y, DecompTypes::Svd).unwrap();
+    assert_close(&slope, &[0.98]);
+}
+
+#[test]
+fn test_svd() {
+    let a = mat64(2, 2, &[3.0, 0.0, 0.0, -2.0]);
+    let (w, u, vt) = SVD::compute(&a).unwrap();
+    assert_close(&w, &[3.0, 2.0]);
+    let w = mat64(2, 2, &[3.0, 0.0, 0.0, 2.0]);
+    assert_close(&matmul(&matmul(&u, &w).unwrap(), &vt).unwrap(), &[3.0, 0.0, 0.0, -2.0]);
+    assert_close(&SVD::values(&a).unwrap(), &[3.0, 2.0]);
+
+    let tall = mat64(3, 1, &[1.0, 2.0, 2.0]);
+    let (w, u, vt) = SVD::compute(&tall).unwrap();
+    assert_close(&w, &[3.0]);
+    assert_eq!((u.rows, u.cols, vt.rows, vt.cols), (3, 1, 1, 1));
+    let (_, u, _) = SVD::compute_full(&tall).unwrap();
+    assert_eq!((u.rows, u.cols), (3, 3));
+}
+
+#[test]
+fn test_eigen() {
+    let a = mat64(2, 2, &[2.0, 1.0, 1.0, 2.0]);
+    let (values, vectors) = eigen(&a).unwrap();
+    assert_close(&values, &[3.0, 1.0]);
+    let v = vectors.as_slice::<f64>().unwrap();
+    assert!((v[0].abs() - v[1].abs()).abs() < 1e-9);
+    assert!((v[0] * v[0] + v[1] * v[1] - 1.0).abs() < 1e-9);
+
+    let b = mat64(2, 2, &[2.0, 0.0, 1.0, 3.0]);
+    let (values, vectors) = eigen_non_symmetric(&b).unwrap();
+    let mut values = values.as_slice::<f64>().unwrap().to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert!((values[0] - 2.0).abs() < 1e-9 && (values[1] - 3.0).abs() < 1e-9);
+    assert_eq!((vectors.rows, vectors.cols), (2, 2));
+}
+
+#[test]
+fn test_determinant_and_trace() {
+    let a = mat64(3, 3, &[2.0, 0.0, 1.0, 1.0, 3.0, 2.0, 1.0, 1.0, 2.0]);
+    assert!((determinant(&a).unwrap() - 6.0).abs() < 1e-9);
+    assert_eq!(trace(&a).unwrap(), Scalar::new(7.0, 0.0, 0.0, 0.0));
+    assert!(determinant(&mat64(2, 3, &[0.0; 6])).is_err());
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "ndarray")]
+extern crate cv;
+extern crate ndarray;
+
+use cv::*;
+use ndarray::{arr3, Array3, Axis};
+
+#[test]
+fn test_as_array_view() {
+    let mat = Mat::from_slice_copy(2, 2, CvType::Cv8UC3, &[1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]).unwrap();
+    let view = mat.as_array_view::<u8>().unwrap();
+    assert_eq!(view.dim(), (2, 2, 3));
+    assert_eq!(view[[1, 0, 2]], 9);
+    assert_eq!(view.index_axis(Axis(2), 0), ndarray::arr2(&[[1, 4], [7, 10]]));
+
+    assert!(mat.as_array_view::<f32>().is_err());
+    assert!(mat.as_array_view::<[u8; 3]>().is_err());
+}
+
+#[test]
+fn test_as_array_view_of_roi() {
+    let mat = Mat::from_slice_copy(3, 3, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]).unwrap();
+    let roi = mat.roi(Rect::new(1, 1, 2, 2)).unwrap();
+    let view = roi.as_array_view::<f32>().unwrap();
+    assert_eq!(view, arr3(&[[[5.0], [6.0]], [[8.0], [9.0]]]));
+}
+
+#[test]
+fn test_as_array_view_mut() {
+    let mut mat = Mat::zeros(2, 3, CvType::Cv16SC1 as i32).unwrap();
+    mat.as_array_view_mut::<i16>().unwrap()[[1, 2, 0]] = -7;
+    assert_eq!(mat.as_slice::<i16>().unwrap(), &[0, 0, 0, 0, 0, -7]);
+}
+
+#[test]
+fn test_from_array() {
+    let array = Array3::from_shape_fn((2, 3, 2), |(r, c, ch)| (r * 100 + c * 10 + ch) as f64);
+    let mat = Mat::from_array(&array).unwrap();
+    assert_eq!(mat.size(), Size2i::new(3, 2));
+    assert_eq!(mat.cv_type().unwrap(), CvType::Cv64FC2);
+    assert_eq!(mat.as_array_view::<f64>().unwrap(), array);
+
+    // Transposed arrays are copied in logical order but can't be borrowed.
+    let transposed = array.view().permuted_axes([1, 0, 2]);
+    let mat = Mat::from_array(&transposed).unwrap();
+    assert_eq!(mat.as_array_view::<f64>().unwrap(), transposed);
+    assert!(Mat::from_array_view(&transposed).is_err());
+
+    let borrowed = Mat::from_array_view(&array).unwrap();
+    assert_eq!(borrowed.data() as *const f64, array.as_ptr());
+
+    let too_many_channels = Array3::<u8>::zeros((1, 1, 5));
+    assert!(Mat::from_array(&too_many_channels).is_err());
+}
@@ -23,10 +23,10 @@ fn test_pedestrian_detection() {
 
     let mut params = HogParams::default();
     params.hit_threshold = 0.3;
-    let mut hog = Hog::with_params(params);
+    let mut hog = Hog::with_params(params).unwrap();
     let detector = SvmDetector::default_people_detector();
-    hog.set_svm_detector(detector);
-    let result = hog.detect(&mat);
+    hog.set_svm_detector(detector).unwrap();
+    let result = hog.detect(&mat).unwrap();
     assert!(result.len() > 1);
 }
 
@@ -35,7 +35,7 @@ fn test_cascade_lenna() {
     let mat = utils::load_lenna();
     let model_path = cascade_model_path();
     let cascade = CascadeClassifier::from_path(model_path).unwrap();
-    let result = cascade.detect(&mat);
+    let result = cascade.detect(&mat).unwrap();
     assert!(result.len() > 0);
     assert!(utils::close_rect(
         result[0].0,
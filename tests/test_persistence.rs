@@ -0,0 +1,145 @@
+extern crate cv;
+
+use cv::*;
+use cv::objdetect::HogParams;
+use cv::persistence::*;
+use std::collections::BTreeMap;
+
+fn round_trip<F>(format: FileStorageFormat, write: F) -> String
+where
+    F: FnOnce(&mut FileStorage),
+{
+    let mut fs = FileStorage::write_memory(format).unwrap();
+    write(&mut fs);
+    fs.release_and_get_string().unwrap()
+}
+
+#[test]
+fn test_scalars_and_strings() {
+    for &format in &[FileStorageFormat::Xml, FileStorageFormat::Yaml, FileStorageFormat::Json] {
+        let content = round_trip(format, |fs| {
+            fs.write("count", &42).unwrap();
+            fs.write("ratio", &0.25).unwrap();
+            fs.write("enabled", &true).unwrap();
+            fs.write("label", "[not a sequence]").unwrap();
+        });
+
+        let fs = FileStorage::read_memory(&content).unwrap();
+        assert_eq!(fs.read::<i32>("count").unwrap(), 42);
+        assert_eq!(fs.read::<f64>("ratio").unwrap(), 0.25);
+        assert_eq!(fs.read::<f64>("count").unwrap(), 42.0);
+        assert!(fs.read::<bool>("enabled").unwrap());
+        assert_eq!(fs.read::<String>("label").unwrap(), "[not a sequence]");
+        assert!(fs.read::<i32>("missing").is_err());
+        assert!(fs.read::<i32>("label").is_err());
+    }
+}
+
+#[test]
+fn test_mat() {
+    let mat = Mat::from_slice_copy(2, 3, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    let content = round_trip(FileStorageFormat::Yaml, |fs| fs.write("mat", &mat).unwrap());
+
+    let fs = FileStorage::read_memory(&content).unwrap();
+    let read: Mat = fs.read("mat").unwrap();
+    assert_eq!(read.size(), mat.size());
+    assert_eq!(read.cv_type().unwrap(), CvType::Cv32FC1);
+    assert_eq!(read.as_slice::<f32>().unwrap(), mat.as_slice::<f32>().unwrap());
+}
+
+#[test]
+fn test_sequences_and_maps() {
+    let mut weights = BTreeMap::new();
+    weights.insert("a".to_owned(), 1.5);
+    weights.insert("b".to_owned(), -2.0);
+
+    let content = round_trip(FileStorageFormat::Json, |fs| {
+        fs.write("values", &vec![3, 1, 2]).unwrap();
+        fs.write("weights", &weights).unwrap();
+        fs.start_map("nested", false).unwrap();
+        fs.start_seq("names", true).unwrap();
+        fs.write("", "x").unwrap();
+        fs.write("", "y").unwrap();
+        fs.end().unwrap();
+        fs.end().unwrap();
+    });
+
+    let fs = FileStorage::read_memory(&content).unwrap();
+    assert_eq!(fs.read::<Vec<i32>>("values").unwrap(), vec![3, 1, 2]);
+    assert_eq!(fs.read::<BTreeMap<String, f64>>("weights").unwrap(), weights);
+
+    let root = fs.root();
+    assert_eq!(root.node_type(), FileNodeType::Map);
+    let keys = root.iter().map(|n| n.unwrap().name().unwrap()).collect::<Vec<_>>();
+    assert_eq!(keys, vec!["values", "weights", "nested"]);
+
+    // The iterator outlives the temporary node it was created from.
+    let values = fs.root().get("values").unwrap().iter();
+    assert_eq!(values.size_hint(), (3, Some(3)));
+    let values = values.map(|n| n.unwrap().read::<i32>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(values, vec![3, 1, 2]);
+
+    let names = root.get("nested").unwrap().get("names").unwrap();
+    assert_eq!(names.node_type(), FileNodeType::Seq);
+    assert_eq!(names.len(), 2);
+    assert_eq!(names.at(1).unwrap().read::<String>().unwrap(), "y");
+    assert!(names.at(2).is_err());
+    assert!(root.get("missing").unwrap().is_none());
+}
+
+#[test]
+fn test_geometry_round_trip() {
+    let rect = Rect::new(10, 20, 30, 40);
+    let point = Point2f::new(1.5, -2.5);
+    let size = Size2i::new(640, 480);
+    let scalar = Scalar::new(1.0, 2.0, 3.0, 4.0);
+
+    let content = round_trip(FileStorageFormat::Xml, |fs| {
+        fs.write("rect", &rect).unwrap();
+        fs.write("point", &point).unwrap();
+        fs.write("size", &size).unwrap();
+        fs.write("scalar", &scalar).unwrap();
+    });
+
+    let fs = FileStorage::read_memory(&content).unwrap();
+    assert_eq!(fs.read::<Rect>("rect").unwrap(), rect);
+    assert_eq!(fs.read::<Point2f>("point").unwrap(), point);
+    assert_eq!(fs.read::<Size2i>("size").unwrap(), size);
+    assert_eq!(fs.read::<Scalar>("scalar").unwrap(), scalar);
+    assert!(fs.read::<Point2i>("rect").is_err());
+}
+
+#[test]
+fn test_hog_params_round_trip() {
+    let params = HogParams {
+        hit_threshold: 0.3,
+        win_stride: Size2i::new(4, 4),
+        use_meanshift_grouping: true,
+        ..HogParams::default()
+    };
+
+    let content = round_trip(FileStorageFormat::Yaml, |fs| fs.write("hog", &params).unwrap());
+    let fs = FileStorage::read_memory(&content).unwrap();
+    assert_eq!(fs.read::<HogParams>("hog").unwrap(), params);
+}
+
+#[test]
+fn test_open_file() {
+    let path = std::env::temp_dir().join("cv-rs-test-persistence.yml");
+
+    let mut fs = FileStorage::open(&path, FileStorageMode::Write).unwrap();
+    fs.write("first", &1).unwrap();
+    fs.release().unwrap();
+
+    let mut fs = FileStorage::open(&path, FileStorageMode::Append).unwrap();
+    fs.write("second", &2).unwrap();
+    fs.release().unwrap();
+
+    let fs = FileStorage::open(&path, FileStorageMode::Read).unwrap();
+    assert_eq!(fs.read::<i32>("first").unwrap(), 1);
+    assert_eq!(fs.read::<i32>("second").unwrap(), 2);
+    drop(fs);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(FileStorage::open(&path, FileStorageMode::Read).is_err());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+extern crate bincode;
+extern crate cv;
+extern crate serde_json;
+
+use cv::*;
+use cv::objdetect::HogParams;
+use cv::videoio::CapProp;
+
+#[test]
+fn test_geometry_json() {
+    let rect = Rect::new(1, 2, 3, 4);
+    let json = serde_json::to_string(&rect).unwrap();
+    assert_eq!(json, r#"{"x":1,"y":2,"width":3,"height":4}"#);
+    assert_eq!(serde_json::from_str::<Rect>(&json).unwrap(), rect);
+
+    let rr = RotatedRect::new(Point2f::new(1.5, 2.5), Size2f::new(3.0, 4.0), 30.0);
+    let json = serde_json::to_string(&rr).unwrap();
+    assert_eq!(serde_json::from_str::<RotatedRect>(&json).unwrap(), rr);
+
+    let point = Point3d::new(1.0, -2.0, 0.5);
+    let bytes = bincode::serialize(&point).unwrap();
+    assert_eq!(bincode::deserialize::<Point3d>(&bytes).unwrap(), point);
+}
+
+#[test]
+fn test_params_round_trip() {
+    let scalar = Scalar::new(1.0, 2.0, 3.0, 4.0);
+    let bytes = bincode::serialize(&scalar).unwrap();
+    assert_eq!(bincode::deserialize::<Scalar>(&bytes).unwrap(), scalar);
+
+    let params = HogParams::default();
+    let json = serde_json::to_string(&params).unwrap();
+    assert_eq!(serde_json::from_str::<HogParams>(&json).unwrap(), params);
+
+    let json = serde_json::to_string(&(CvType::Cv8UC3, CapProp::FrameWidth)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<(CvType, CapProp)>(&json).unwrap(),
+        (CvType::Cv8UC3, CapProp::FrameWidth)
+    );
+}
+
+#[test]
+fn test_mat_round_trip() {
+    let mat = Mat::from_slice_copy(2, 2, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+
+    let json = serde_json::to_string(&mat).unwrap();
+    let read: Mat = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.size(), mat.size());
+    assert_eq!(read.cv_type().unwrap(), CvType::Cv32FC1);
+    assert_eq!(read.as_slice::<f32>().unwrap(), mat.as_slice::<f32>().unwrap());
+
+    let bytes = bincode::serialize(&mat).unwrap();
+    let read: Mat = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(read.as_slice::<f32>().unwrap(), mat.as_slice::<f32>().unwrap());
+}
+
+#[test]
+fn test_mat_roi_is_serialized_continuously() {
+    let mat = Mat::from_slice_copy(3, 3, CvType::Cv8UC1, &[1u8, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+    let roi = mat.roi(Rect::new(1, 1, 2, 2)).unwrap();
+    let read: Mat = serde_json::from_str(&serde_json::to_string(&*roi).unwrap()).unwrap();
+    assert_eq!(read.as_slice::<u8>().unwrap(), &[5, 6, 8, 9]);
+}
+
+#[test]
+fn test_mat_validation() {
+    let json = r#"{"rows":2,"cols":2,"type":"Cv8UC1","data":[1,2,3]}"#;
+    assert!(serde_json::from_str::<Mat>(json).is_err());
+
+    let json = r#"{"rows":-1,"cols":2,"type":"Cv8UC1","data":[]}"#;
+    assert!(serde_json::from_str::<Mat>(json).is_err());
+
+    let json = r#"{"rows":1,"cols":2,"type":"Cv16UC1","data":[1,0,2,0]}"#;
+    let mat: Mat = serde_json::from_str(json).unwrap();
+    assert_eq!(mat.as_slice::<u16>().unwrap(), &[1, 2]);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "testing")]
+extern crate cv;
+mod utils;
+
+use cv::*;
+use cv::testing::*;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("cv-test-testing-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn noisy(mat: &Mat, stddev: f64) -> Mat {
+    let mut noise = Mat::zeros(mat.rows, mat.cols, CvType::Cv16SC1 as i32).unwrap();
+    Rng::new(1).unwrap().fill(&mut noise, RngDistribution::Normal, 0.0, stddev, false).unwrap();
+    let mut result = mat.convert_to(CvType::Cv16SC1, 1.0, 0.0).unwrap();
+    for (r, n) in result.as_mut_slice::<i16>().unwrap().iter_mut().zip(noise.as_slice::<i16>().unwrap()) {
+        *r = (*r + n).clamp(0, 255);
+    }
+    result.convert_to(CvType::Cv8UC1, 1.0, 0.0).unwrap()
+}
+
+#[test]
+fn test_assert_mat_near() {
+    let a = Mat::from_slice_copy(1, 3, CvType::Cv32FC1, &[1.0f32, 2.0, 3.0]).unwrap();
+    let b = Mat::from_slice_copy(1, 3, CvType::Cv32FC1, &[1.0f32, 2.1, 3.0]).unwrap();
+    assert_mat_near!(a, a.clone(), 0.0);
+    assert_mat_near!(a, b, 0.11);
+    assert_mat_near!(a, b, 0.11, NormTypes::NormL1);
+
+    assert!(mat_near(&a, &b, 0.05, NormTypes::NormInf).unwrap_err().contains("NormInf"));
+    let reshaped = a.reshape(1, 3).unwrap();
+    assert!(mat_near(&a, &reshaped, 1.0, NormTypes::NormInf).is_err());
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `a ≈ b`")]
+fn test_assert_mat_near_fails() {
+    let a = Mat::zeros(2, 2, CvType::Cv8UC1 as i32).unwrap();
+    let b = Mat::ones(2, 2, CvType::Cv8UC1).unwrap();
+    assert_mat_near!(a, b, 0.5);
+}
+
+#[test]
+fn test_psnr_and_ssim() {
+    let lenna = utils::load_lenna();
+    assert_eq!(psnr(&lenna, &lenna).unwrap(), f64::INFINITY);
+    assert!((ssim(&lenna, &lenna).unwrap() - 1.0).abs() < 1e-9);
+
+    let slightly = noisy(&lenna, 2.0);
+    let very = noisy(&lenna, 30.0);
+    assert!(psnr(&lenna, &slightly).unwrap() > psnr(&lenna, &very).unwrap());
+    assert!(ssim(&lenna, &slightly).unwrap() > ssim(&lenna, &very).unwrap());
+    assert!(ssim(&lenna, &very).unwrap() < 0.9);
+
+    let zeros = Mat::zeros(1, 4, CvType::Cv8UC1 as i32).unwrap();
+    let tens = Mat::from_slice_copy(1, 4, CvType::Cv8UC1, &[10u8; 4]).unwrap();
+    assert!((psnr(&zeros, &tens).unwrap() - 28.1308).abs() < 1e-3);
+
+    assert!(psnr(&lenna, &zeros).is_err());
+    assert!(ssim(&zeros, &zeros.convert_to(CvType::Cv32FC1, 1.0, 0.0).unwrap()).is_err());
+}
+
+#[test]
+fn test_diff_heatmap() {
+    let a = Mat::from_slice_copy(1, 3, CvType::Cv8UC3, &[0u8, 0, 0, 10, 10, 10, 0, 0, 0]).unwrap();
+    let b = Mat::from_slice_copy(1, 3, CvType::Cv8UC3, &[0u8, 0, 0, 0, 0, 0, 0, 0, 40]).unwrap();
+    let heatmap = diff_heatmap(&a, &b).unwrap();
+    assert_eq!(heatmap.cv_type().unwrap(), CvType::Cv8UC3);
+    let pixels = heatmap.as_slice::<[u8; 3]>().unwrap();
+    assert_eq!(pixels[0], [128, 0, 0]);
+    assert_eq!(pixels[2], [0, 0, 128]);
+}
+
+#[test]
+fn test_golden_update_and_compare() {
+    let dir = temp_dir("goldens");
+    let failures = temp_dir("failures");
+    let lenna = utils::load_lenna();
+
+    let golden = Golden::new(&dir).failure_dir(&failures).update(false);
+    let missing = golden.check("lenna", &lenna, Comparison::Psnr(40.0)).unwrap_err();
+    assert!(missing.contains(UPDATE_GOLDENS_ENV));
+
+    golden.clone().update(true).assert_psnr("lenna", &lenna, 40.0);
+    assert!(golden.path("lenna").exists());
+
+    golden.assert_psnr("lenna", &lenna, 40.0);
+    golden.assert_ssim("lenna", &noisy(&lenna, 2.0), 0.8);
+    golden.assert_near("lenna", &lenna, 0.0, NormTypes::NormInf);
+
+    let message = golden.check("lenna", &noisy(&lenna, 30.0), Comparison::Ssim(0.95)).unwrap_err();
+    assert!(message.contains("SSIM"));
+    for suffix in &["actual", "expected", "diff"] {
+        assert!(failures.join(format!("lenna.{}.png", suffix)).exists());
+    }
+}
+
+#[test]
+#[should_panic(expected = "PSNR")]
+fn test_golden_assert_fails() {
+    let dir = temp_dir("assert");
+    let golden = Golden::new(&dir).failure_dir(dir.join("failures")).update(false);
+    let zeros = Mat::zeros(8, 8, CvType::Cv8UC1 as i32).unwrap();
+    golden.clone().update(true).assert_psnr("zeros", &zeros, 40.0);
+    golden.assert_psnr("zeros", &Mat::ones(8, 8, CvType::Cv8UC1).unwrap(), 60.0);
+}